@@ -0,0 +1,214 @@
+//! Exporter of the animation clips played back by the device, see `sl1_protocol::clip` for the
+//! format. Frames are taken from a GIF or from a directory of PNG images sorted by their names,
+//! the LED colors are sampled along the middle row of every image.
+
+use std::fs::{self, File};
+use std::path::Path;
+
+use sl1_protocol::clip::{ClipHeader, FrameKind, MAX_FRAME_LENGTH};
+
+use crate::{Error, Result};
+
+pub const DEFAULT_CLIP_FPS: u8 = 25;
+
+pub type ClipFrame = Vec<[u8; 3]>;
+
+/// Loads the frames of a GIF file or of a directory of PNG images, returns the frame rate of the
+/// GIF if it has one
+pub fn load_frames(path: &Path, led_count: u16) -> Result<(Vec<ClipFrame>, Option<u8>)> {
+    if path.is_dir() {
+        return Ok((load_png_sequence(path, led_count)?, None));
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("gif") => load_gif(path, led_count),
+        Some(ext) if ext.eq_ignore_ascii_case("png") => {
+            Ok((vec![load_png(path, led_count)?], None))
+        }
+        _ => Err(export_error(
+            "expected a GIF, a PNG or a directory of PNG images",
+        )),
+    }
+}
+
+pub fn encode(frames: &[ClipFrame], fps: u8, led_count: u16) -> Result<Vec<u8>> {
+    let frame_count = u16::try_from(frames.len())
+        .ok()
+        .filter(|count| *count > 0)
+        .ok_or_else(|| export_error("a clip must have between 1 and 65535 frames"))?;
+    if fps == 0 {
+        return Err(export_error("frame rate must not be zero"));
+    }
+
+    let header = ClipHeader {
+        frame_count,
+        fps,
+        led_count,
+    };
+    let mut clip = header.to_bytes().to_vec();
+
+    let mut previous: Option<&ClipFrame> = None;
+    for frame in frames {
+        let key = encode_key_frame(frame);
+        let data = match previous {
+            Some(previous) => {
+                let delta = encode_delta_frame(previous, frame);
+                if delta.len() < key.len() { delta } else { key }
+            }
+            None => key,
+        };
+        if data.len() > MAX_FRAME_LENGTH {
+            return Err(export_error("frames are too large, reduce the LED count"));
+        }
+        clip.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        clip.extend_from_slice(&data);
+        previous = Some(frame);
+    }
+
+    Ok(clip)
+}
+
+fn encode_key_frame(frame: &ClipFrame) -> Vec<u8> {
+    let mut data = vec![FrameKind::Key as u8];
+    for run in frame.chunk_by(|a, b| a == b) {
+        for part in run.chunks(u8::MAX as usize) {
+            let [r, g, b] = part[0];
+            data.extend_from_slice(&[part.len() as u8, r, g, b]);
+        }
+    }
+    data
+}
+
+fn encode_delta_frame(previous: &ClipFrame, frame: &ClipFrame) -> Vec<u8> {
+    let mut data = vec![FrameKind::Delta as u8];
+    let mut led = 0;
+    let mut skip = 0;
+
+    while led < frame.len() {
+        if frame[led] == previous[led] {
+            led += 1;
+            skip += 1;
+            continue;
+        }
+        while skip > u8::MAX as usize {
+            data.extend_from_slice(&[u8::MAX, 0]);
+            skip -= u8::MAX as usize;
+        }
+
+        let changed = frame[led..]
+            .iter()
+            .zip(&previous[led..])
+            .take(u8::MAX as usize)
+            .take_while(|(color, previous)| color != previous)
+            .count();
+        data.extend_from_slice(&[skip as u8, changed as u8]);
+        frame[led..led + changed]
+            .iter()
+            .for_each(|color| data.extend_from_slice(color));
+
+        led += changed;
+        skip = 0;
+    }
+    data
+}
+
+fn load_gif(path: &Path, led_count: u16) -> Result<(Vec<ClipFrame>, Option<u8>)> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let file = File::open(path).map_err(Error::FileRead)?;
+    let mut decoder = options.read_info(file).map_err(Error::GifDecode)?;
+
+    let (width, height) = (decoder.width() as usize, decoder.height() as usize);
+    let mut canvas = vec![0u8; width * height * 4];
+    let mut frames = Vec::new();
+    let mut total_delay = 0u32;
+
+    while let Some(frame) = decoder.read_next_frame().map_err(Error::GifDecode)? {
+        let (left, top) = (frame.left as usize, frame.top as usize);
+        let frame_width = frame.width as usize;
+        for (idx, pixel) in frame.buffer.chunks_exact(4).enumerate() {
+            let (x, y) = (left + idx % frame_width, top + idx / frame_width);
+            if pixel[3] == 0 || x >= width || y >= height {
+                continue;
+            }
+            let offset = (y * width + x) * 4;
+            canvas[offset..offset + 4].copy_from_slice(pixel);
+        }
+
+        frames.push(sample_middle_row(&canvas, width, height, 4, led_count));
+        total_delay += frame.delay as u32;
+
+        if frame.dispose == gif::DisposalMethod::Background {
+            for y in top..(top + frame.height as usize).min(height) {
+                let start = (y * width + left) * 4;
+                let end = (y * width + (left + frame_width).min(width)) * 4;
+                canvas[start..end].fill(0);
+            }
+        }
+    }
+
+    // Delays are in hundredths of a second
+    let fps = match total_delay {
+        0 => None,
+        _ => Some((100 * frames.len() as u32 / total_delay).clamp(1, u8::MAX as u32) as u8),
+    };
+    Ok((frames, fps))
+}
+
+fn load_png_sequence(path: &Path, led_count: u16) -> Result<Vec<ClipFrame>> {
+    let mut paths: Vec<_> = fs::read_dir(path)
+        .map_err(Error::FileRead)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        })
+        .collect();
+    paths.sort();
+
+    paths.iter().map(|path| load_png(path, led_count)).collect()
+}
+
+fn load_png(path: &Path, led_count: u16) -> Result<ClipFrame> {
+    let file = File::open(path).map_err(Error::FileRead)?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(Error::PngDecode)?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(Error::PngDecode)?;
+
+    let channels = info.color_type.samples();
+    Ok(sample_middle_row(
+        &buf[..info.buffer_size()],
+        info.width as usize,
+        info.height as usize,
+        channels,
+        led_count,
+    ))
+}
+
+/// Picks `led_count` evenly spaced pixels from the middle row of the image
+fn sample_middle_row(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    channels: usize,
+    led_count: u16,
+) -> ClipFrame {
+    let led_count = led_count as usize;
+    let row = height / 2 * width;
+    (0..led_count)
+        .map(|led| {
+            let x = (2 * led + 1) * width / (2 * led_count);
+            let offset = (row + x) * channels;
+            match channels {
+                1 | 2 => [pixels[offset]; 3],
+                _ => [pixels[offset], pixels[offset + 1], pixels[offset + 2]],
+            }
+        })
+        .collect()
+}
+
+fn export_error(message: &str) -> Error {
+    Error::ClipExport(message.to_string())
+}
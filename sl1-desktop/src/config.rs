@@ -14,6 +14,8 @@ pub const DEVICE_PORT: u16 = 30462;
 pub struct Config {
     device: Device,
     preset_info: Vec<Preset>,
+    #[serde(default)]
+    script_source: String,
 }
 
 impl Config {
@@ -60,6 +62,14 @@ impl Config {
     pub fn set_preset_info(&mut self, preset_info: Vec<Preset>) {
         self.preset_info = preset_info;
     }
+
+    pub fn script_source(&self) -> &str {
+        &self.script_source
+    }
+
+    pub fn set_script_source(&mut self, script_source: String) {
+        self.script_source = script_source;
+    }
 }
 
 impl Default for Config {
@@ -71,6 +81,7 @@ impl Default for Config {
         Self {
             device,
             preset_info,
+            script_source: String::new(),
         }
     }
 }
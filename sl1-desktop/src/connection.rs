@@ -6,9 +6,17 @@ use iced::futures::channel::mpsc;
 use iced::futures::sink::SinkExt;
 use iced::futures::{Stream, StreamExt};
 use iced::stream;
+use sl1_protocol::log::LogRecord;
+use sl1_protocol::{MESSAGE_BUFFER_LENGTH, Method};
 use tokio::net::UdpSocket;
 
-use crate::device::{DeviceSettings, DeviceWifiSettings, Preset, PresetId, PresetSettings};
+use crate::device::{
+    BootSettings, ClipId, ClipList, DevicePalette, DevicePlaylist, DeviceScript, DeviceSettings,
+    DeviceStats, DeviceTime, DeviceWifiSettings, FavoriteId, FinishedClip, FirmwareInfo,
+    FirmwareUpdateRequest, ItemSummary, Layer, LogEntry, LogSettings, ManualTime, MatrixLayout,
+    PaletteId, ParamId, PlaylistId, PresetId, PresetInfoPage, PresetSettings, ResetToken, Schedule,
+    SunriseRequest, TimeSettings, TimerStatus,
+};
 use crate::{Error, Result};
 
 #[derive(Debug, Clone)]
@@ -30,23 +38,56 @@ pub enum GetRequest {
     Ping,
     IsOn,
     CurrentPresetId,
-    PresetInfo,
+    PresetInfo(PresetId),
     Settings,
     WifiSettings,
     CurrentPresetSettings,
+    Stats,
+    Palettes,
+    Palette(PaletteId),
+    Playlists,
+    Playlist(PlaylistId),
+    Favorites,
+    Layers,
+    Script,
+    Clips,
+    Time,
+    Schedules,
+    Timers,
+    BootSettings,
+    ResetToken,
+    FirmwareInfo,
+    LogSettings,
 }
 
 impl GetRequest {
-    fn to_u8(&self) -> u8 {
+    fn method(&self) -> Method {
         use GetRequest as GR;
+        use Method as M;
         match self {
-            GR::Ping => 0x01,
-            GR::IsOn => 0x02,
-            GR::CurrentPresetId => 0x03,
-            GR::PresetInfo => 0x04,
-            GR::Settings => 0x05,
-            GR::WifiSettings => 0x06,
-            GR::CurrentPresetSettings => 0x07,
+            GR::Ping => M::GetPing,
+            GR::IsOn => M::GetIsOn,
+            GR::CurrentPresetId => M::GetCurrentPresetId,
+            GR::PresetInfo(_) => M::GetPresetInfo,
+            GR::Settings => M::GetSettings,
+            GR::CurrentPresetSettings => M::GetCurrentPresetSettings,
+            GR::WifiSettings => M::GetWifiSettings,
+            GR::Stats => M::GetStats,
+            GR::Palettes => M::GetPalettes,
+            GR::Palette(_) => M::GetPalette,
+            GR::Playlists => M::GetPlaylists,
+            GR::Playlist(_) => M::GetPlaylist,
+            GR::Favorites => M::GetFavorites,
+            GR::Layers => M::GetLayers,
+            GR::Script => M::GetScript,
+            GR::Clips => M::GetClips,
+            GR::Time => M::GetTime,
+            GR::Schedules => M::GetSchedules,
+            GR::Timers => M::GetTimers,
+            GR::BootSettings => M::GetBootSettings,
+            GR::ResetToken => M::GetResetToken,
+            GR::FirmwareInfo => M::GetFirmwareInfo,
+            GR::LogSettings => M::GetLogSettings,
         }
     }
 }
@@ -65,23 +106,107 @@ pub enum SetRequest {
     Speed(u8),
     Scale(u8),
     SaveSettings,
+    FrameRate(u8),
+    Color([u8; 3]),
+    UploadPalette(DevicePalette),
+    DeletePalette(PaletteId),
+    Palette(PaletteId),
+    UploadPlaylist(DevicePlaylist),
+    DeletePlaylist(PlaylistId),
+    Playlist(PlaylistId),
+    SaveFavorite(ItemSummary),
+    RecallFavorite(FavoriteId),
+    DeleteFavorite(FavoriteId),
+    Layers(Vec<Layer>),
+    UploadScript(DeviceScript),
+    UploadClipChunk {
+        id: ClipId,
+        offset: u32,
+        data: Vec<u8>,
+    },
+    FinishClip(FinishedClip),
+    DeleteClip(ClipId),
+    PlayClip(ClipId),
+    Layout(MatrixLayout),
+    Time(ManualTime),
+    TimeSettings(TimeSettings),
+    Schedules(Vec<Schedule>),
+    SleepTimer(u8),
+    CancelSleepTimer,
+    Sunrise(SunriseRequest),
+    CancelSunrise,
+    BootSettings(BootSettings),
+    Reboot(u32),
+    FactoryReset(u32),
+    BeginFirmwareUpdate(FirmwareUpdateRequest),
+    UploadFirmwareChunk {
+        offset: u32,
+        data: Vec<u8>,
+    },
+    FinishFirmwareUpdate,
+    LogSettings(LogSettings),
+    SubscribeLogs,
 }
 
 impl SetRequest {
-    fn to_u8(&self) -> u8 {
+    pub fn param(id: ParamId, value: u8) -> Self {
+        match id {
+            ParamId::Brightness => Self::Brightness(value),
+            ParamId::Speed => Self::Speed(value),
+            ParamId::Scale => Self::Scale(value),
+            ParamId::Palette => Self::Palette(value),
+            ParamId::Color => unreachable!("color parameters are set with SetRequest::Color"),
+        }
+    }
+
+    fn method(&self) -> Method {
+        use Method as M;
         use SetRequest as SR;
         match self {
-            SR::Toggle => 0x08,
-            SR::TurnOn => 0x09,
-            SR::TurnOff => 0x0A,
-            SR::Preset(_) => 0x0B,
-            SR::Settings(_) => 0x0C,
-            SR::WifiSettings(_) => 0x0D,
-            SR::CurrentPresetSettings(_) => 0x0E,
-            SR::Brightness(_) => 0x0F,
-            SR::Speed(_) => 0x10,
-            SR::Scale(_) => 0x11,
-            SR::SaveSettings => 0x12,
+            SR::Toggle => M::SetToggle,
+            SR::TurnOn => M::SetTurnOn,
+            SR::TurnOff => M::SetTurnOff,
+            SR::Preset(_) => M::SetPreset,
+            SR::Settings(_) => M::SetSettings,
+            SR::WifiSettings(_) => M::SetWifiSettings,
+            SR::CurrentPresetSettings(_) => M::SetCurrentPresetSettings,
+            SR::Brightness(_) => M::SetBrightness,
+            SR::Speed(_) => M::SetSpeed,
+            SR::Scale(_) => M::SetScale,
+            SR::SaveSettings => M::SaveSettings,
+            SR::FrameRate(_) => M::SetFrameRate,
+            SR::Color(_) => M::SetColor,
+            SR::UploadPalette(_) => M::UploadPalette,
+            SR::DeletePalette(_) => M::DeletePalette,
+            SR::Palette(_) => M::SetPalette,
+            SR::UploadPlaylist(_) => M::UploadPlaylist,
+            SR::DeletePlaylist(_) => M::DeletePlaylist,
+            SR::Playlist(_) => M::SetPlaylist,
+            SR::SaveFavorite(_) => M::SaveFavorite,
+            SR::RecallFavorite(_) => M::RecallFavorite,
+            SR::DeleteFavorite(_) => M::DeleteFavorite,
+            SR::Layers(_) => M::SetLayers,
+            SR::UploadScript(_) => M::UploadScript,
+            SR::UploadClipChunk { .. } => M::UploadClipChunk,
+            SR::FinishClip(_) => M::FinishClip,
+            SR::DeleteClip(_) => M::DeleteClip,
+            SR::PlayClip(_) => M::PlayClip,
+            SR::Layout(_) => M::SetLayout,
+            SR::Time(_) => M::SetTime,
+            SR::TimeSettings(_) => M::SetTimeSettings,
+            SR::Schedules(_) => M::SetSchedules,
+            SR::SleepTimer(_) => M::ArmSleepTimer,
+            SR::CancelSleepTimer => M::CancelSleepTimer,
+            SR::Sunrise(_) => M::ArmSunrise,
+            SR::CancelSunrise => M::CancelSunrise,
+            SR::BootSettings(_) => M::SetBootSettings,
+            SR::Reboot(_) => M::Reboot,
+            SR::FactoryReset(_) => M::FactoryReset,
+            SR::BeginFirmwareUpdate(_) => M::BeginFirmwareUpdate,
+            SR::UploadFirmwareChunk { .. } => M::UploadFirmwareChunk,
+            SR::FinishFirmwareUpdate => M::FinishFirmwareUpdate,
+            SR::LogSettings(_) => M::SetLogSettings,
+            SR::SubscribeLogs => M::SubscribeLogs,
         }
     }
 }
@@ -91,6 +216,8 @@ pub enum DeviceResponse {
     Error,
     Get(DeviceGetResponse),
     Set(DeviceSetResponse),
+    /// Sent by the device without a request while the client is subscribed
+    Log(LogEntry),
 }
 
 #[allow(unused)]
@@ -99,10 +226,26 @@ pub enum DeviceGetResponse {
     Ping,
     IsOn(bool),
     CurrentPresetId(PresetId),
-    PresetInfo(Vec<Preset>),
+    PresetInfo(PresetInfoPage),
     Settings(DeviceSettings),
     CurrentPresetSettings(PresetSettings),
     WifiSettings(DeviceWifiSettings),
+    Stats(DeviceStats),
+    Palettes(Vec<ItemSummary>),
+    Palette(DevicePalette),
+    Playlists(Vec<ItemSummary>),
+    Playlist(DevicePlaylist),
+    Favorites(Vec<ItemSummary>),
+    Layers(Vec<Layer>),
+    Script(DeviceScript),
+    Clips(ClipList),
+    Time(DeviceTime),
+    Schedules(Vec<Schedule>),
+    Timers(TimerStatus),
+    BootSettings(BootSettings),
+    ResetToken(ResetToken),
+    FirmwareInfo(FirmwareInfo),
+    LogSettings(LogSettings),
 }
 
 #[derive(Debug, Clone)]
@@ -118,12 +261,45 @@ pub enum DeviceSetResponse {
     Speed,
     Scale,
     SaveSettings,
+    FrameRate,
+    Color,
+    UploadPalette,
+    DeletePalette,
+    Palette,
+    UploadPlaylist,
+    DeletePlaylist,
+    Playlist,
+    SaveFavorite,
+    RecallFavorite,
+    DeleteFavorite,
+    Layers,
+    UploadScript,
+    UploadClipChunk,
+    FinishClip,
+    DeleteClip,
+    PlayClip,
+    Layout,
+    Time,
+    TimeSettings,
+    Schedules,
+    SleepTimer,
+    CancelSleepTimer,
+    Sunrise,
+    CancelSunrise,
+    BootSettings,
+    Reboot,
+    FactoryReset,
+    BeginFirmwareUpdate,
+    UploadFirmwareChunk,
+    FinishFirmwareUpdate,
+    LogSettings,
+    SubscribeLogs,
 }
 
 struct Sender {
     socket: Arc<UdpSocket>,
     device_addr: Option<SocketAddr>,
-    send_buff: [u8; 1024],
+    send_buff: [u8; MESSAGE_BUFFER_LENGTH],
 }
 
 impl Sender {
@@ -131,7 +307,7 @@ impl Sender {
         Self {
             socket,
             device_addr: None,
-            send_buff: [0; 1024],
+            send_buff: [0; MESSAGE_BUFFER_LENGTH],
         }
     }
 
@@ -148,8 +324,13 @@ impl Sender {
 
     async fn send_get_request(&mut self, request: GetRequest) -> Result<()> {
         self.send_buff[0] = 0x01;
-        self.send_buff[1] = request.to_u8();
-        self.send_with_timeout(2).await?;
+        self.send_buff[1] = request.method() as u8;
+        match request {
+            GetRequest::PresetInfo(id) | GetRequest::Palette(id) | GetRequest::Playlist(id) => {
+                self.send_u8(id).await?
+            }
+            _ => self.send_with_timeout(2).await?,
+        }
         Ok(())
     }
 
@@ -157,9 +338,16 @@ impl Sender {
         use SetRequest as SR;
 
         self.send_buff[0] = 0x01;
-        self.send_buff[1] = request.to_u8();
+        self.send_buff[1] = request.method() as u8;
         match request {
-            SR::Toggle | SR::TurnOn | SR::TurnOff | SR::SaveSettings => {
+            SR::Toggle
+            | SR::TurnOn
+            | SR::TurnOff
+            | SR::SaveSettings
+            | SR::CancelSleepTimer
+            | SR::CancelSunrise
+            | SR::FinishFirmwareUpdate
+            | SR::SubscribeLogs => {
                 self.send_with_timeout(2).await?;
             }
             SR::Settings(settings) => {
@@ -177,9 +365,106 @@ impl Sender {
                     serde_json::to_string(&settings).map_err(Error::SerializeJson)?;
                 self.send_json_string(&settings_string).await?;
             }
-            SR::Preset(value) | SR::Brightness(value) | SR::Speed(value) | SR::Scale(value) => {
+            SR::UploadPalette(palette) => {
+                let palette_string =
+                    serde_json::to_string(&palette).map_err(Error::SerializeJson)?;
+                self.send_json_string(&palette_string).await?;
+            }
+            SR::UploadPlaylist(playlist) => {
+                let playlist_string =
+                    serde_json::to_string(&playlist).map_err(Error::SerializeJson)?;
+                self.send_json_string(&playlist_string).await?;
+            }
+            SR::SaveFavorite(favorite) => {
+                let favorite_string =
+                    serde_json::to_string(&favorite).map_err(Error::SerializeJson)?;
+                self.send_json_string(&favorite_string).await?;
+            }
+            SR::Layers(layers) => {
+                let layers_string = serde_json::to_string(&layers).map_err(Error::SerializeJson)?;
+                self.send_json_string(&layers_string).await?;
+            }
+            SR::UploadScript(script) => {
+                let script_string = serde_json::to_string(&script).map_err(Error::SerializeJson)?;
+                self.send_json_string(&script_string).await?;
+            }
+            SR::UploadClipChunk { id, offset, data } => {
+                self.send_buff[2] = id;
+                self.send_buff[3..7].copy_from_slice(&offset.to_le_bytes());
+                self.send_buff[7..7 + data.len()].copy_from_slice(&data);
+                self.send_with_timeout(7 + data.len()).await?;
+            }
+            SR::BeginFirmwareUpdate(request) => {
+                let request_string =
+                    serde_json::to_string(&request).map_err(Error::SerializeJson)?;
+                self.send_json_string(&request_string).await?;
+            }
+            SR::UploadFirmwareChunk { offset, data } => {
+                self.send_buff[2..6].copy_from_slice(&offset.to_le_bytes());
+                self.send_buff[6..6 + data.len()].copy_from_slice(&data);
+                self.send_with_timeout(6 + data.len()).await?;
+            }
+            SR::LogSettings(settings) => {
+                let settings_string =
+                    serde_json::to_string(&settings).map_err(Error::SerializeJson)?;
+                self.send_json_string(&settings_string).await?;
+            }
+            SR::Layout(layout) => {
+                let layout_string = serde_json::to_string(&layout).map_err(Error::SerializeJson)?;
+                self.send_json_string(&layout_string).await?;
+            }
+            SR::Time(time) => {
+                let time_string = serde_json::to_string(&time).map_err(Error::SerializeJson)?;
+                self.send_json_string(&time_string).await?;
+            }
+            SR::TimeSettings(settings) => {
+                let settings_string =
+                    serde_json::to_string(&settings).map_err(Error::SerializeJson)?;
+                self.send_json_string(&settings_string).await?;
+            }
+            SR::Schedules(schedules) => {
+                let schedules_string =
+                    serde_json::to_string(&schedules).map_err(Error::SerializeJson)?;
+                self.send_json_string(&schedules_string).await?;
+            }
+            SR::Sunrise(sunrise) => {
+                let sunrise_string =
+                    serde_json::to_string(&sunrise).map_err(Error::SerializeJson)?;
+                self.send_json_string(&sunrise_string).await?;
+            }
+            SR::BootSettings(settings) => {
+                let settings_string =
+                    serde_json::to_string(&settings).map_err(Error::SerializeJson)?;
+                self.send_json_string(&settings_string).await?;
+            }
+            SR::FinishClip(clip) => {
+                let clip_string = serde_json::to_string(&clip).map_err(Error::SerializeJson)?;
+                self.send_json_string(&clip_string).await?;
+            }
+            SR::Preset(value)
+            | SR::Brightness(value)
+            | SR::Speed(value)
+            | SR::Scale(value)
+            | SR::FrameRate(value)
+            | SR::DeletePalette(value)
+            | SR::Palette(value)
+            | SR::DeletePlaylist(value)
+            | SR::Playlist(value)
+            | SR::RecallFavorite(value)
+            | SR::DeleteFavorite(value)
+            | SR::DeleteClip(value)
+            | SR::PlayClip(value)
+            | SR::SleepTimer(value) => {
                 self.send_u8(value).await?;
             }
+            SR::Color(color) => {
+                self.send_buff[2..5].copy_from_slice(&color);
+                self.send_with_timeout(5).await?;
+            }
+            SR::Reboot(token) | SR::FactoryReset(token) => {
+                self.send_buff[2..6].copy_from_slice(&token.to_le_bytes());
+                self.send_with_timeout(6).await?;
+            }
         }
         Ok(())
     }
@@ -230,14 +515,14 @@ impl Sender {
 
 struct Reciever {
     socket: Arc<UdpSocket>,
-    recv_buff: [u8; 1024],
+    recv_buff: [u8; MESSAGE_BUFFER_LENGTH],
 }
 
 impl Reciever {
     fn new(socket: Arc<UdpSocket>) -> Self {
         Self {
             socket,
-            recv_buff: [0; 1024],
+            recv_buff: [0; MESSAGE_BUFFER_LENGTH],
         }
     }
 
@@ -245,6 +530,7 @@ impl Reciever {
         use DeviceGetResponse as DGR;
         use DeviceResponse as DR;
         use DeviceSetResponse as DSR;
+        use Method as M;
 
         let size = self
             .socket
@@ -256,51 +542,170 @@ impl Reciever {
             return Err(Error::InvalidProtocolVersion);
         }
 
-        match self.recv_buff[1] {
-            0x00 => Ok(DR::Error),
-            0x01 => Ok(DR::Get(DGR::Ping)),
-            0x02 => {
+        let method =
+            Method::try_from(self.recv_buff[1]).map_err(|_| Error::UnknownResponseMethod)?;
+        match method {
+            M::Error => Ok(DR::Error),
+            M::GetPing => Ok(DR::Get(DGR::Ping)),
+            M::GetIsOn => {
                 let is_on = self.recv_buff[2] != 0;
                 Ok(DR::Get(DGR::IsOn(is_on)))
             }
-            0x03 => {
+            M::GetCurrentPresetId => {
                 let preset_id = self.recv_buff[2];
                 Ok(DR::Get(DGR::CurrentPresetId(preset_id)))
             }
-            0x04 => {
-                let preset_info: Vec<Preset> = serde_json::from_slice(&self.recv_buff[2..size])
+            M::GetPresetInfo => {
+                let preset_info: PresetInfoPage = serde_json::from_slice(&self.recv_buff[2..size])
                     .map_err(Error::DeserializeJson)?;
                 Ok(DR::Get(DGR::PresetInfo(preset_info)))
             }
-            0x05 => {
+            M::GetSettings => {
                 let settings: DeviceSettings = serde_json::from_slice(&self.recv_buff[2..size])
                     .map_err(Error::DeserializeJson)?;
                 Ok(DR::Get(DGR::Settings(settings)))
             }
-            0x06 => {
+            M::GetCurrentPresetSettings => {
                 let preset_settings: PresetSettings =
                     serde_json::from_slice(&self.recv_buff[2..size])
                         .map_err(Error::DeserializeJson)?;
                 Ok(DR::Get(DGR::CurrentPresetSettings(preset_settings)))
             }
-            0x07 => {
+            M::GetWifiSettings => {
                 let wifi_settings: DeviceWifiSettings =
                     serde_json::from_slice(&self.recv_buff[2..size])
                         .map_err(Error::DeserializeJson)?;
                 Ok(DR::Get(DGR::WifiSettings(wifi_settings)))
             }
-            0x08 => Ok(DR::Set(DSR::Toggle)),
-            0x09 => Ok(DR::Set(DSR::TurnOn)),
-            0x0A => Ok(DR::Set(DSR::TurnOff)),
-            0x0B => Ok(DR::Set(DSR::Preset)),
-            0x0C => Ok(DR::Set(DSR::Settings)),
-            0x0D => Ok(DR::Set(DSR::WifiSettings)),
-            0x0E => Ok(DR::Set(DSR::CurrentPresetSettings)),
-            0x0F => Ok(DR::Set(DSR::Brightness)),
-            0x10 => Ok(DR::Set(DSR::Speed)),
-            0x11 => Ok(DR::Set(DSR::Scale)),
-            0x12 => Ok(DR::Set(DSR::SaveSettings)),
-            _ => Err(Error::UnknownResponseMethod),
+            M::SetToggle => Ok(DR::Set(DSR::Toggle)),
+            M::SetTurnOn => Ok(DR::Set(DSR::TurnOn)),
+            M::SetTurnOff => Ok(DR::Set(DSR::TurnOff)),
+            M::SetPreset => Ok(DR::Set(DSR::Preset)),
+            M::SetSettings => Ok(DR::Set(DSR::Settings)),
+            M::SetWifiSettings => Ok(DR::Set(DSR::WifiSettings)),
+            M::SetCurrentPresetSettings => Ok(DR::Set(DSR::CurrentPresetSettings)),
+            M::SetBrightness => Ok(DR::Set(DSR::Brightness)),
+            M::SetSpeed => Ok(DR::Set(DSR::Speed)),
+            M::SetScale => Ok(DR::Set(DSR::Scale)),
+            M::SaveSettings => Ok(DR::Set(DSR::SaveSettings)),
+            M::GetStats => {
+                let stats: DeviceStats = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Stats(stats)))
+            }
+            M::SetFrameRate => Ok(DR::Set(DSR::FrameRate)),
+            M::SetColor => Ok(DR::Set(DSR::Color)),
+            M::GetPalettes => {
+                let palettes: Vec<ItemSummary> = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Palettes(palettes)))
+            }
+            M::GetPalette => {
+                let palette: DevicePalette = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Palette(palette)))
+            }
+            M::UploadPalette => Ok(DR::Set(DSR::UploadPalette)),
+            M::DeletePalette => Ok(DR::Set(DSR::DeletePalette)),
+            M::SetPalette => Ok(DR::Set(DSR::Palette)),
+            M::GetPlaylists => {
+                let playlists: Vec<ItemSummary> = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Playlists(playlists)))
+            }
+            M::GetPlaylist => {
+                let playlist: DevicePlaylist = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Playlist(playlist)))
+            }
+            M::UploadPlaylist => Ok(DR::Set(DSR::UploadPlaylist)),
+            M::DeletePlaylist => Ok(DR::Set(DSR::DeletePlaylist)),
+            M::SetPlaylist => Ok(DR::Set(DSR::Playlist)),
+            M::GetFavorites => {
+                let favorites: Vec<ItemSummary> = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Favorites(favorites)))
+            }
+            M::SaveFavorite => Ok(DR::Set(DSR::SaveFavorite)),
+            M::RecallFavorite => Ok(DR::Set(DSR::RecallFavorite)),
+            M::DeleteFavorite => Ok(DR::Set(DSR::DeleteFavorite)),
+            M::GetLayers => {
+                let layers: Vec<Layer> = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Layers(layers)))
+            }
+            M::SetLayers => Ok(DR::Set(DSR::Layers)),
+            M::GetScript => {
+                let script: DeviceScript = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Script(script)))
+            }
+            M::UploadScript => Ok(DR::Set(DSR::UploadScript)),
+            M::GetClips => {
+                let clips: ClipList = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Clips(clips)))
+            }
+            M::UploadClipChunk => Ok(DR::Set(DSR::UploadClipChunk)),
+            M::FinishClip => Ok(DR::Set(DSR::FinishClip)),
+            M::DeleteClip => Ok(DR::Set(DSR::DeleteClip)),
+            M::PlayClip => Ok(DR::Set(DSR::PlayClip)),
+            M::SetLayout => Ok(DR::Set(DSR::Layout)),
+            M::GetTime => {
+                let time: DeviceTime = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Time(time)))
+            }
+            M::SetTime => Ok(DR::Set(DSR::Time)),
+            M::SetTimeSettings => Ok(DR::Set(DSR::TimeSettings)),
+            M::GetSchedules => {
+                let schedules: Vec<Schedule> = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Schedules(schedules)))
+            }
+            M::SetSchedules => Ok(DR::Set(DSR::Schedules)),
+            M::GetTimers => {
+                let timers: TimerStatus = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::Timers(timers)))
+            }
+            M::ArmSleepTimer => Ok(DR::Set(DSR::SleepTimer)),
+            M::CancelSleepTimer => Ok(DR::Set(DSR::CancelSleepTimer)),
+            M::ArmSunrise => Ok(DR::Set(DSR::Sunrise)),
+            M::CancelSunrise => Ok(DR::Set(DSR::CancelSunrise)),
+            M::GetBootSettings => {
+                let settings: BootSettings = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::BootSettings(settings)))
+            }
+            M::SetBootSettings => Ok(DR::Set(DSR::BootSettings)),
+            M::GetResetToken => {
+                let token: ResetToken = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::ResetToken(token)))
+            }
+            M::Reboot => Ok(DR::Set(DSR::Reboot)),
+            M::FactoryReset => Ok(DR::Set(DSR::FactoryReset)),
+            M::GetFirmwareInfo => {
+                let info: FirmwareInfo = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::FirmwareInfo(info)))
+            }
+            M::BeginFirmwareUpdate => Ok(DR::Set(DSR::BeginFirmwareUpdate)),
+            M::UploadFirmwareChunk => Ok(DR::Set(DSR::UploadFirmwareChunk)),
+            M::FinishFirmwareUpdate => Ok(DR::Set(DSR::FinishFirmwareUpdate)),
+            M::GetLogSettings => {
+                let settings: LogSettings = serde_json::from_slice(&self.recv_buff[2..size])
+                    .map_err(Error::DeserializeJson)?;
+                Ok(DR::Get(DGR::LogSettings(settings)))
+            }
+            M::SetLogSettings => Ok(DR::Set(DSR::LogSettings)),
+            M::SubscribeLogs => Ok(DR::Set(DSR::SubscribeLogs)),
+            M::LogRecord => {
+                let record =
+                    LogRecord::from_bytes(&self.recv_buff[2..size]).map_err(Error::LogRecord)?;
+                Ok(DR::Log(record.into()))
+            }
         }
     }
 }
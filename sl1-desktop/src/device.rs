@@ -1,15 +1,40 @@
 use std::net::{IpAddr, SocketAddr};
 
 use serde::{Deserialize, Serialize};
+use sl1_protocol::log::{LogLevel, LogRecord};
 
 pub type PresetId = u8;
+pub type PaletteId = u8;
+pub type PlaylistId = u8;
+pub type FavoriteId = u8;
+pub type ClipId = u8;
+
+pub const MAX_PALETTES: u8 = 16;
+pub const MAX_PALETTE_STOPS: usize = 16;
+pub const MAX_PLAYLISTS: u8 = 8;
+pub const MAX_PLAYLIST_ENTRIES: usize = 16;
+pub const MAX_FAVORITES: u8 = 16;
+pub const MAX_LAYERS: usize = 4;
+pub const MAX_CLIPS: u8 = 4;
+pub const MAX_SCHEDULES: usize = 16;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceSettings {
     wifi_settings: DeviceWifiSettings,
-    preset_settings: Vec<PresetSettings>,
+    /// Settings of the presets that differ from their defaults
+    preset_settings: Vec<(PresetId, PresetSettings)>,
     current_preset_id: PresetId,
     is_on: bool,
+    #[serde(default)]
+    power_settings: PowerSettings,
+    #[serde(default)]
+    renderer_settings: RendererSettings,
+    #[serde(default)]
+    active_playlist: Option<PlaylistId>,
+    #[serde(default)]
+    active_clip: Option<ClipId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    layout: Option<MatrixLayout>,
 }
 
 impl DeviceSettings {
@@ -18,10 +43,6 @@ impl DeviceSettings {
         &self.wifi_settings
     }
 
-    pub fn preset_settings(&self) -> &[PresetSettings] {
-        &self.preset_settings
-    }
-
     pub fn current_preset_id(&self) -> u8 {
         self.current_preset_id
     }
@@ -29,6 +50,353 @@ impl DeviceSettings {
     pub fn is_on(&self) -> bool {
         self.is_on
     }
+
+    pub fn renderer_settings(&self) -> &RendererSettings {
+        &self.renderer_settings
+    }
+
+    pub fn active_playlist(&self) -> Option<PlaylistId> {
+        self.active_playlist
+    }
+
+    pub fn active_clip(&self) -> Option<ClipId> {
+        self.active_clip
+    }
+
+    pub fn layout(&self) -> Option<MatrixLayout> {
+        self.layout
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PowerSettings {
+    #[serde(rename = "max_ma")]
+    max_current: u16,
+    #[serde(rename = "ch_ma")]
+    channel_current: u8,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            max_current: 2000,
+            channel_current: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RendererSettings {
+    dithering: bool,
+    #[serde(rename = "fps")]
+    frame_rate: u8,
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        Self {
+            dithering: true,
+            frame_rate: 50,
+        }
+    }
+}
+
+impl RendererSettings {
+    pub fn frame_rate(&self) -> u8 {
+        self.frame_rate
+    }
+}
+
+/// Arrangement of the LEDs on a panel, used by the 2-D presets of the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixLayout {
+    #[serde(rename = "w")]
+    pub width: u8,
+    #[serde(rename = "h")]
+    pub height: u8,
+    /// Every other row is wired in the opposite direction
+    #[serde(rename = "serp")]
+    pub serpentine: bool,
+    /// Clockwise rotation of the panel in quarter turns
+    #[serde(rename = "rot")]
+    pub rotation: u8,
+    #[serde(rename = "fx")]
+    pub flip_x: bool,
+    #[serde(rename = "fy")]
+    pub flip_y: bool,
+}
+
+/// Missing fields are left at their defaults, as older firmware sends fewer stats
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceStats {
+    pub power_ma: u32,
+    pub power_limited: bool,
+    pub frame_rate: u8,
+    pub render_time_us: u32,
+    pub write_time_us: u32,
+    /// Longest frame since the stats have last been requested
+    pub max_frame_time_us: u32,
+    pub dropped_frames: u32,
+    pub uptime_s: u64,
+    pub heap_used: u32,
+    pub heap_free: u32,
+    /// Signal strength in dBm, `None` while the device is disconnected
+    pub rssi: Option<i8>,
+    pub wifi_reconnects: u32,
+    pub messages_received: u32,
+    pub protocol_errors: u32,
+}
+
+impl std::fmt::Display for DeviceStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} FPS | render: {} us | write: {} us | dropped frames: {} | power: {} mA{}",
+            self.frame_rate,
+            self.render_time_us,
+            self.write_time_us,
+            self.dropped_frames,
+            self.power_ma,
+            if self.power_limited { " (limited)" } else { "" }
+        )
+    }
+}
+
+/// Current time of the device along with its time settings
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceTime {
+    /// Unix time in milliseconds, `None` until the clock of the device has been set
+    pub unix_ms: Option<u64>,
+    /// Where the time comes from: `none`, `sntp` or `manual`
+    pub source: String,
+    #[serde(flatten)]
+    pub settings: TimeSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeSettings {
+    /// Offset of the local time from UTC in minutes
+    pub utc_offset: i16,
+    /// Time is not synchronized over SNTP when it is empty
+    pub ntp_server: String,
+}
+
+/// Time set on networks without an SNTP server
+#[derive(Debug, Clone, Serialize)]
+pub struct ManualTime {
+    pub unix_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleAction {
+    TurnOn,
+    TurnOff,
+    Preset(PresetId),
+    Favorite(FavoriteId),
+    Brightness(u8),
+}
+
+/// Whether the lights are on after the device is powered on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerOnBehavior {
+    On,
+    Off,
+    #[default]
+    Restore,
+}
+
+impl PowerOnBehavior {
+    pub const ALL: [PowerOnBehavior; 3] = [
+        PowerOnBehavior::On,
+        PowerOnBehavior::Off,
+        PowerOnBehavior::Restore,
+    ];
+}
+
+impl std::fmt::Display for PowerOnBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PowerOnBehavior::On => "Always on",
+            PowerOnBehavior::Off => "Always off",
+            PowerOnBehavior::Restore => "Restore last state",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BootSettings {
+    pub power_on: PowerOnBehavior,
+    pub boot_animation: bool,
+}
+
+/// Firmware running on the device
+#[derive(Debug, Clone, Deserialize)]
+pub struct FirmwareInfo {
+    pub version: String,
+    /// OTA slot the firmware has been booted from
+    pub slot: u8,
+    /// Firmware has been updated and has not confirmed itself healthy yet
+    pub pending_verify: bool,
+    /// Largest image that fits into an OTA slot
+    pub max_size: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FirmwareUpdateRequest {
+    pub size: u32,
+    pub sha256: [u8; sl1_protocol::ota::SHA256_LENGTH],
+}
+
+/// Most verbose level the device logs at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFilter {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogFilter {
+    pub const ALL: [LogFilter; 6] = [
+        LogFilter::Off,
+        LogFilter::Error,
+        LogFilter::Warn,
+        LogFilter::Info,
+        LogFilter::Debug,
+        LogFilter::Trace,
+    ];
+}
+
+impl std::fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LogFilter::Off => "Off",
+            LogFilter::Error => "Error",
+            LogFilter::Warn => "Warning",
+            LogFilter::Info => "Info",
+            LogFilter::Debug => "Debug",
+            LogFilter::Trace => "Trace",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Host the device sends every log record to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogCollector {
+    pub address: [u8; 4],
+    pub port: u16,
+}
+
+impl std::fmt::Display for LogCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d] = self.address;
+        write!(f, "{a}.{b}.{c}.{d}:{}", self.port)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LogSettings {
+    pub level: LogFilter,
+    pub collector: Option<LogCollector>,
+}
+
+/// Log record streamed by the device
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub level: LogLevel,
+    pub uptime_ms: u64,
+    pub target: String,
+    pub message: String,
+}
+
+impl From<LogRecord<'_>> for LogEntry {
+    fn from(record: LogRecord<'_>) -> Self {
+        Self {
+            level: record.level,
+            uptime_ms: record.uptime_ms,
+            target: record.target.to_string(),
+            message: record.message.to_string(),
+        }
+    }
+}
+
+/// Token the device expects back to reboot or reset to factory settings
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ResetToken {
+    pub token: u32,
+}
+
+/// Timers armed on the device
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimerStatus {
+    /// Seconds until the lights are turned off
+    pub sleep_remaining: Option<u64>,
+    pub sunrise: Option<SunriseAlarm>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SunriseAlarm {
+    /// Unix time in seconds the sunrise ends at
+    pub target: u64,
+    /// Duration in minutes
+    pub duration: u16,
+    pub started: bool,
+}
+
+/// Sunrise ending at the next occurrence of the time of the day
+#[derive(Debug, Clone, Serialize)]
+pub struct SunriseRequest {
+    /// Local time of the day in minutes since midnight
+    pub minute: u16,
+    /// Duration in minutes
+    pub duration: u16,
+}
+
+/// Action the device runs at a time of the day on the selected days of the week
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Schedule {
+    pub enabled: bool,
+    /// Days of the week, bit 0 is Monday
+    pub days: u8,
+    /// Local time of the day in minutes since midnight
+    pub minute: u16,
+    pub action: ScheduleAction,
+}
+
+impl Schedule {
+    const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    pub fn weekday_name(weekday: usize) -> &'static str {
+        Self::WEEKDAY_NAMES[weekday]
+    }
+
+    pub fn days_text(&self) -> String {
+        match self.days {
+            sl1_protocol::schedule::EVERY_DAY => "Every day".to_string(),
+            0 => "Never".to_string(),
+            days => (0..7)
+                .filter(|weekday| days & (1 << weekday) != 0)
+                .map(Self::weekday_name)
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    pub fn trigger(&self) -> sl1_protocol::schedule::Trigger {
+        sl1_protocol::schedule::Trigger {
+            days: self.days,
+            minute: self.minute,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,9 +413,27 @@ pub struct PresetSettings {
     speed: u8,
     #[serde(rename = "sc")]
     scale: u8,
+    #[serde(rename = "c", default = "default_color")]
+    color: [u8; 3],
+    #[serde(rename = "p", default)]
+    palette: PaletteId,
+}
+
+fn default_color() -> [u8; 3] {
+    [255, 255, 255]
 }
 
 impl PresetSettings {
+    pub fn new(brightness: u8, speed: u8, scale: u8, color: [u8; 3], palette: PaletteId) -> Self {
+        Self {
+            brightness,
+            speed,
+            scale,
+            color,
+            palette,
+        }
+    }
+
     pub fn brightness(&self) -> u8 {
         self.brightness
     }
@@ -59,12 +445,96 @@ impl PresetSettings {
     pub fn scale(&self) -> u8 {
         self.scale
     }
+
+    pub fn color(&self) -> [u8; 3] {
+        self.color
+    }
+
+    pub fn palette(&self) -> PaletteId {
+        self.palette
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
     id: PresetId,
     name: String,
+    #[serde(default)]
+    params: Vec<PresetParam>,
+}
+
+/// Single preset description as sent by the device, along with the total preset count
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresetInfoPage {
+    pub count: u8,
+    #[serde(flatten)]
+    pub preset: Preset,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetParam {
+    name: ParamId,
+    label: String,
+    #[serde(rename = "type")]
+    kind: ParamKind,
+    min: u8,
+    max: u8,
+    default: u32,
+}
+
+impl PresetParam {
+    /// Controls shown for presets the device has not described yet
+    pub fn generic() -> Vec<Self> {
+        let param = |name, label: &str| Self {
+            name,
+            label: label.to_string(),
+            kind: ParamKind::U8,
+            min: 0,
+            max: 255,
+            default: 0,
+        };
+        vec![
+            param(ParamId::Brightness, "Brightness"),
+            param(ParamId::Speed, "Speed"),
+            param(ParamId::Scale, "Scale"),
+        ]
+    }
+
+    pub fn id(&self) -> ParamId {
+        self.name
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn range(&self) -> std::ops::RangeInclusive<u8> {
+        self.min..=self.max
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamId {
+    #[serde(rename = "b")]
+    Brightness,
+    #[serde(rename = "sp")]
+    Speed,
+    #[serde(rename = "sc")]
+    Scale,
+    #[serde(rename = "c")]
+    Color,
+    #[serde(rename = "p")]
+    Palette,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamKind {
+    #[serde(rename = "u8")]
+    U8,
+    #[serde(rename = "color")]
+    Color,
+    #[serde(rename = "palette")]
+    Palette,
 }
 
 impl std::fmt::Display for Preset {
@@ -77,6 +547,222 @@ impl Preset {
     pub fn id(&self) -> u8 {
         self.id
     }
+
+    pub fn params(&self) -> &[PresetParam] {
+        &self.params
+    }
+}
+
+/// Entry of the palette, playlist and favorite lists sent by the device, also used to name the
+/// favorite the current preset is saved as
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemSummary {
+    id: u8,
+    name: String,
+}
+
+impl ItemSummary {
+    pub fn new(id: u8, name: String) -> Self {
+        Self { id, name }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+}
+
+impl std::fmt::Display for ItemSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.id, self.name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevicePalette {
+    pub id: PaletteId,
+    pub name: String,
+    /// Gradient stops as `[position, r, g, b]`
+    pub stops: Vec<[u8; 4]>,
+}
+
+impl DevicePalette {
+    pub fn new(id: PaletteId) -> Self {
+        Self {
+            id,
+            name: format!("Palette {id}"),
+            stops: vec![[0, 0, 0, 0], [255, 255, 255, 255]],
+        }
+    }
+
+    /// Color of the gradient at `position`, computed the same way as on the device
+    pub fn color_at(&self, position: u8) -> [u8; 3] {
+        let mut stops = self.stops.clone();
+        stops.sort_by_key(|stop| stop[0]);
+        let rgb = |stop: &[u8; 4]| [stop[1], stop[2], stop[3]];
+
+        let Some(first) = stops.first() else {
+            return [0; 3];
+        };
+        if position <= first[0] {
+            return rgb(first);
+        }
+
+        for pair in stops.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            if position <= to[0] {
+                let frac = (position - from[0]) as u32 * 255 / (to[0] - from[0]) as u32;
+                let lerp =
+                    |a: u8, b: u8| (a as i32 + (b as i32 - a as i32) * frac as i32 / 256) as u8;
+                return [
+                    lerp(from[1], to[1]),
+                    lerp(from[2], to[2]),
+                    lerp(from[3], to[3]),
+                ];
+            }
+        }
+
+        rgb(stops.last().unwrap())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    #[serde(rename = "id")]
+    pub preset_id: PresetId,
+    #[serde(rename = "s")]
+    pub preset_settings: PresetSettings,
+    /// How long the preset is shown, in seconds
+    #[serde(rename = "d")]
+    pub duration: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevicePlaylist {
+    pub id: PlaylistId,
+    pub name: String,
+    #[serde(default)]
+    pub shuffle: bool,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+impl DevicePlaylist {
+    pub fn new(id: PlaylistId) -> Self {
+        Self {
+            id,
+            name: format!("Playlist {id}"),
+            shuffle: false,
+            entries: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Add,
+    Multiply,
+    Screen,
+    Max,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 5] = [
+        BlendMode::Normal,
+        BlendMode::Add,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Max,
+    ];
+
+    /// Blends two colors the same way the device does, used to preview layers
+    pub fn blend(self, below: [u8; 3], above: [u8; 3], opacity: u8) -> [u8; 3] {
+        std::array::from_fn(|channel| {
+            let (below, above) = (below[channel] as i32, above[channel] as i32);
+            let blended = match self {
+                BlendMode::Normal => above,
+                BlendMode::Add => (below + above).min(255),
+                BlendMode::Multiply => below * above / 255,
+                BlendMode::Screen => 255 - (255 - below) * (255 - above) / 255,
+                BlendMode::Max => below.max(above),
+            };
+            (below + (blended - below) * opacity as i32 / 255) as u8
+        })
+    }
+}
+
+impl std::fmt::Display for BlendMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Add => "Add",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Screen => "Screen",
+            BlendMode::Max => "Max",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Preset rendered by the device on top of the current preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layer {
+    #[serde(rename = "id")]
+    pub preset_id: PresetId,
+    #[serde(rename = "s")]
+    pub preset_settings: PresetSettings,
+    #[serde(rename = "m", default)]
+    pub blend_mode: BlendMode,
+    #[serde(rename = "o")]
+    pub opacity: u8,
+}
+
+/// Compiled effect script run by the script preset of the device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceScript {
+    pub name: String,
+    pub code: Vec<u8>,
+}
+
+/// Clips stored on the device, along with the limits of the clips it accepts
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipList {
+    pub led_count: u16,
+    pub max_length: u32,
+    pub clips: Vec<ClipSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipSummary {
+    pub id: ClipId,
+    pub name: String,
+    /// Length of the encoded clip in bytes
+    pub length: u32,
+}
+
+impl std::fmt::Display for ClipSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} ({} KiB)",
+            self.id,
+            self.name,
+            self.length.div_ceil(1024)
+        )
+    }
+}
+
+/// Sent once every chunk of a clip has been uploaded
+#[derive(Debug, Clone, Serialize)]
+pub struct FinishedClip {
+    pub id: ClipId,
+    pub name: String,
+    pub length: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
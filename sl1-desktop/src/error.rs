@@ -6,6 +6,8 @@ pub enum Error {
     DeserializeJson(serde_json::Error),
     #[error("error deserializing toml: {0}")]
     DeserializeToml(toml::de::Error),
+    #[error("error exporting clip: {0}")]
+    ClipExport(String),
     #[error("error reading file: {0}")]
     FileRead(std::io::Error),
     #[error("error converting byte sequence to utf-8 sting: {0}")]
@@ -14,16 +16,24 @@ pub enum Error {
     Fs(String),
     #[error("error writing to filesystem: {0}")]
     FsWrite(std::io::Error),
+    #[error("error decoding gif: {0}")]
+    GifDecode(gif::DecodingError),
     #[error("recieved message with invalid protocol version")]
     InvalidProtocolVersion,
+    #[error("error decoding log record: {0}")]
+    LogRecord(sl1_protocol::log::LogRecordError),
     #[error("error loading config: config file does not exist")]
     MissingConfig,
     #[error("error sending data via mpsc: {0}")]
     MpscSend(iced::futures::channel::mpsc::SendError),
+    #[error("error decoding png: {0}")]
+    PngDecode(png::DecodingError),
     #[error("error parsing port: {0}")]
     PortParse(std::num::ParseIntError),
     #[error("error serializing toml: {0}")]
     SerializeToml(toml::ser::Error),
+    #[error("error compiling script: {0}")]
+    ScriptCompile(String),
     #[error("error serializing json: {0}")]
     SerializeJson(serde_json::Error),
     #[error("reached timeout while executing future")]
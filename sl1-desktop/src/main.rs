@@ -1,11 +1,16 @@
+mod clip;
 mod config;
 mod connection;
 mod detector;
 mod device;
 mod error;
+mod script;
 
-use std::net::IpAddr;
-use std::time::{Duration, Instant};
+use std::collections::VecDeque;
+use std::net::{IpAddr, SocketAddrV4};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
 
 use connection::{DeviceResponse, GetRequest, SetRequest};
 use device::PresetId;
@@ -14,22 +19,51 @@ use iced::alignment::Vertical::Bottom;
 use iced::futures::channel::mpsc;
 use iced::theme::Palette;
 use iced::widget::{
-    self, Space, button, column, combo_box, horizontal_space, row, scrollable, slider, text,
-    text_editor, text_input,
+    self, Space, button, checkbox, column, combo_box, container, horizontal_space, pick_list, row,
+    scrollable, slider, text, text_editor, text_input,
 };
 use iced::{Element, Subscription, Task, Theme};
 use ipnetwork::IpNetwork;
+use sl1_protocol::log::LogLevel;
+use sl1_protocol::ota::{FIRMWARE_IMAGE_MAGIC, Sha256};
+use sl1_protocol::schedule::{EVERY_DAY, MINUTES_PER_DAY};
+use sl1_protocol::time::DateTime;
 
 use crate::config::Config;
 use crate::connection::{
     DeviceGetResponse, DeviceSetResponse, Request, Response, connection_worker,
 };
-use crate::device::{Device, DeviceSettings, Preset};
+use crate::device::{
+    BlendMode, BootSettings, ClipId, ClipList, Device, DevicePalette, DevicePlaylist, DeviceScript,
+    DeviceSettings, DeviceStats, DeviceTime, FinishedClip, FirmwareInfo, FirmwareUpdateRequest,
+    ItemSummary, Layer, LogCollector, LogEntry, LogFilter, LogSettings, MAX_CLIPS, MAX_FAVORITES,
+    MAX_LAYERS, MAX_PALETTE_STOPS, MAX_PALETTES, MAX_PLAYLIST_ENTRIES, MAX_PLAYLISTS,
+    MAX_SCHEDULES, ManualTime, MatrixLayout, PaletteId, ParamId, PlaylistEntry, PlaylistId,
+    PowerOnBehavior, Preset, PresetParam, PresetSettings, Schedule, ScheduleAction, SunriseRequest,
+    TimeSettings, TimerStatus,
+};
 
 pub use crate::error::{Error, Result};
 
 const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Stats are polled more often while the diagnostics page is open
+const DIAGNOSTICS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Stats samples shown in the diagnostics graphs
+const STATS_HISTORY_LENGTH: usize = 120;
+const GRAPH_HEIGHT: f32 = 60.0;
+/// Subscriptions to the device logs expire after 30 seconds unless they are renewed
+const LOG_SUBSCRIBE_INTERVAL: Duration = Duration::from_secs(10);
+/// Log records kept by the log viewer, older records are dropped
+const LOG_HISTORY_LENGTH: usize = 1000;
 const DEVICE_DISCONNECT_INTERVAL: Duration = Duration::from_secs(5);
+const PALETTE_PREVIEW_STEPS: u16 = 64;
+const DEFAULT_PLAYLIST_ENTRY_DURATION: u16 = 60;
+/// Clip bytes sent per message, leaves room for the chunk header in the device message buffer
+const CLIP_CHUNK_LENGTH: usize = 1024;
+/// Firmware bytes sent per message, four chunks fill a flash sector of the device
+const FIRMWARE_CHUNK_LENGTH: usize = 1024;
+/// Longest sunrise the device accepts, in minutes
+const MAX_SUNRISE_DURATION: u16 = 120;
 
 fn main() -> iced::Result {
     tracing_subscriber::fmt::init();
@@ -55,8 +89,65 @@ struct App {
     brightness: u8,
     speed: u8,
     scale: u8,
+    color: [u8; 3],
+    color_hex_text: String,
+    palette: PaletteId,
+    palettes: Vec<ItemSummary>,
+    edited_palette: Option<DevicePalette>,
+    palette_stop_hex_texts: Vec<String>,
+    active_playlist: Option<PlaylistId>,
+    playlists: Vec<ItemSummary>,
+    edited_playlist: Option<DevicePlaylist>,
+    playlist_duration_texts: Vec<String>,
+    favorites: Vec<ItemSummary>,
+    favorite_name_text: String,
+    layers: Vec<Layer>,
+    script_content: text_editor::Content,
+    script_name: String,
+    device_script: Option<DeviceScript>,
+    clip_list: Option<ClipList>,
+    active_clip: Option<ClipId>,
+    clip_path_text: String,
+    clip_fps_text: String,
+    clip_name_text: String,
+    clip_upload: Option<ClipUpload>,
+    layout: Option<MatrixLayout>,
+    layout_width_text: String,
+    layout_height_text: String,
+    /// Time of the device and the moment it has been received, so that the clock keeps ticking
+    device_time: Option<(DeviceTime, Instant)>,
+    utc_offset_text: String,
+    ntp_server_text: String,
+    boot_settings: Option<BootSettings>,
+    /// Restart the user has been asked to confirm
+    restart_confirmation: Option<DeviceRestart>,
+    /// Restart waiting for the device to hand out a token and to acknowledge it
+    requested_restart: Option<DeviceRestart>,
+    restart_message: Option<RestartMessage>,
+    firmware_info: Option<FirmwareInfo>,
+    firmware_path_text: String,
+    firmware_upload: Option<FirmwareUpload>,
+    firmware_message: Option<FirmwareMessage>,
+    schedules: Vec<Schedule>,
+    schedule_time_text: String,
+    schedule_days: u8,
+    schedule_action: ScheduleActionKind,
+    schedule_favorite: Option<ItemSummary>,
+    schedule_brightness: u8,
+    /// Timers of the device and the moment they have been received, so that they keep counting
+    timers: Option<(TimerStatus, Instant)>,
+    sleep_minutes_text: String,
+    sunrise_time_text: String,
+    sunrise_duration_text: String,
+    frame_rate: u8,
+    stats: Option<DeviceStats>,
+    stats_history: VecDeque<DeviceStats>,
+    log_settings: Option<LogSettings>,
+    log_collector_text: String,
+    log_entries: VecDeque<LogEntry>,
     preset: combo_box::State<Preset>,
     selected_preset: Option<Preset>,
+    loaded_presets: Vec<Preset>,
     preset_info_message: Option<PresetInfoMessage>,
     ip_text: String,
     port_text: String,
@@ -67,6 +158,20 @@ struct App {
     ip_port_error_message: Option<IpPortErrorMessage>,
     device_settings_error_message: Option<DeviceSettingsErrorMessage>,
     detector_error_message: Option<DetectorErrorMessage>,
+    color_error_message: Option<ColorErrorMessage>,
+    palette_error_message: Option<PaletteErrorMessage>,
+    playlist_error_message: Option<PlaylistErrorMessage>,
+    favorite_error_message: Option<FavoriteErrorMessage>,
+    layer_error_message: Option<LayerErrorMessage>,
+    script_error_message: Option<ScriptErrorMessage>,
+    clip_error_message: Option<ClipErrorMessage>,
+    layout_error_message: Option<LayoutErrorMessage>,
+    time_error_message: Option<TimeErrorMessage>,
+    schedule_error_message: Option<ScheduleErrorMessage>,
+    timer_error_message: Option<TimerErrorMessage>,
+    restart_error_message: Option<RestartErrorMessage>,
+    firmware_error_message: Option<FirmwareErrorMessage>,
+    log_error_message: Option<LogErrorMessage>,
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +179,14 @@ enum Message {
     Page(Page),
     UI(UIMessage),
     Settings(SettingsMessage),
+    PaletteEditor(PaletteEditorMessage),
+    PlaylistEditor(PlaylistEditorMessage),
+    LayerEditor(LayerEditorMessage),
+    ScriptEditor(ScriptEditorMessage),
+    ClipEditor(ClipEditorMessage),
+    LayoutEditor(LayoutEditorMessage),
+    ScheduleEditor(ScheduleEditorMessage),
+    LogViewer(LogViewerMessage),
     Request(Request),
     Response(Response),
 }
@@ -100,8 +213,61 @@ impl App {
             brightness: 128,
             speed: 128,
             scale: 128,
+            color: [255, 255, 255],
+            color_hex_text: hex_color(&[255, 255, 255]),
+            palette: 0,
+            palettes: Vec::new(),
+            edited_palette: None,
+            palette_stop_hex_texts: Vec::new(),
+            active_playlist: None,
+            playlists: Vec::new(),
+            edited_playlist: None,
+            playlist_duration_texts: Vec::new(),
+            favorites: Vec::new(),
+            favorite_name_text: String::new(),
+            layers: Vec::new(),
+            script_content: text_editor::Content::with_text(config.script_source()),
+            script_name: "Script".to_string(),
+            device_script: None,
+            clip_list: None,
+            active_clip: None,
+            clip_path_text: String::new(),
+            clip_fps_text: String::new(),
+            clip_name_text: String::new(),
+            clip_upload: None,
+            layout: None,
+            layout_width_text: String::new(),
+            layout_height_text: String::new(),
+            device_time: None,
+            utc_offset_text: String::new(),
+            ntp_server_text: String::new(),
+            boot_settings: None,
+            restart_confirmation: None,
+            requested_restart: None,
+            restart_message: None,
+            firmware_info: None,
+            firmware_path_text: String::new(),
+            firmware_upload: None,
+            firmware_message: None,
+            schedules: Vec::new(),
+            schedule_time_text: String::from("07:00"),
+            schedule_days: EVERY_DAY,
+            schedule_action: ScheduleActionKind::TurnOn,
+            schedule_favorite: None,
+            schedule_brightness: 255,
+            timers: None,
+            sleep_minutes_text: String::from("30"),
+            sunrise_time_text: String::from("07:00"),
+            sunrise_duration_text: String::from("30"),
+            frame_rate: 50,
+            stats: None,
+            stats_history: VecDeque::with_capacity(STATS_HISTORY_LENGTH),
+            log_settings: None,
+            log_collector_text: String::new(),
+            log_entries: VecDeque::with_capacity(LOG_HISTORY_LENGTH),
             preset: combo_box::State::new(config.preset_info().to_vec()),
             selected_preset: None,
+            loaded_presets: Vec::new(),
             preset_info_message: None,
             ip_text: config.device().ip().to_string(),
             port_text: config.device().port().to_string(),
@@ -112,6 +278,20 @@ impl App {
             ip_port_error_message: None,
             device_settings_error_message: None,
             detector_error_message: None,
+            color_error_message: None,
+            palette_error_message: None,
+            playlist_error_message: None,
+            favorite_error_message: None,
+            layer_error_message: None,
+            script_error_message: None,
+            clip_error_message: None,
+            layout_error_message: None,
+            time_error_message: None,
+            schedule_error_message: None,
+            timer_error_message: None,
+            restart_error_message: None,
+            firmware_error_message: None,
+            log_error_message: None,
         };
 
         (app, Task::none())
@@ -127,16 +307,32 @@ impl App {
         match message {
             Message::Page(page) => self.handle_page(page),
             Message::Settings(message) => self.handle_settings_message(message),
+            Message::PaletteEditor(message) => self.handle_palette_editor_message(message),
+            Message::PlaylistEditor(message) => self.handle_playlist_editor_message(message),
+            Message::LayerEditor(message) => self.handle_layer_editor_message(message),
+            Message::ScriptEditor(message) => self.handle_script_editor_message(message),
+            Message::ClipEditor(message) => self.handle_clip_editor_message(message),
+            Message::LayoutEditor(message) => self.handle_layout_editor_message(message),
+            Message::ScheduleEditor(message) => self.handle_schedule_editor_message(message),
+            Message::LogViewer(message) => self.handle_log_viewer_message(message),
             Message::Request(message) => self.handle_request_message(message),
             Message::Response(message) => self.handle_response_message(message),
             Message::UI(message) => self.handle_ui_message(message),
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         match self.page {
             Page::Home => self.home_page(),
             Page::Settings => self.settings_page(),
+            Page::Palettes => self.palettes_page(),
+            Page::Playlists => self.playlists_page(),
+            Page::Layers => self.layers_page(),
+            Page::Script => self.script_page(),
+            Page::Clips => self.clips_page(),
+            Page::Schedules => self.schedules_page(),
+            Page::Diagnostics => self.diagnostics_page(),
+            Page::Logs => self.logs_page(),
         }
     }
 
@@ -145,16 +341,35 @@ impl App {
     }
 
     fn subscription(&self) -> Subscription<Message> {
+        let stats_poll_interval = match self.page {
+            Page::Diagnostics => DIAGNOSTICS_POLL_INTERVAL,
+            _ => DEVICE_POLL_INTERVAL,
+        };
+        let log_subscription = match self.page {
+            Page::Logs => iced::time::every(LOG_SUBSCRIBE_INTERVAL)
+                .map(|_| Message::Request(Request::Set(SetRequest::SubscribeLogs))),
+            _ => Subscription::none(),
+        };
         Subscription::batch([
             Subscription::run(connection_worker).map(Message::Response),
             iced::time::every(DEVICE_POLL_INTERVAL)
                 .map(|_| Message::Request(Request::Get(GetRequest::Ping))),
+            iced::time::every(stats_poll_interval)
+                .map(|_| Message::Request(Request::Get(GetRequest::Stats))),
+            log_subscription,
         ])
     }
 
     fn handle_page(&mut self, page: Page) -> Task<Message> {
         self.page = page;
-        Task::none()
+        match page {
+            // The subscription is only renewed periodically, so the first records would be missed
+            Page::Logs => Task::batch([
+                self.update(Message::Request(Request::Set(SetRequest::SubscribeLogs))),
+                self.update(Message::Request(Request::Get(GetRequest::LogSettings))),
+            ]),
+            _ => Task::none(),
+        }
     }
 
     fn handle_settings_message(&mut self, message: SettingsMessage) -> Task<Message> {
@@ -167,15 +382,35 @@ impl App {
             SM::DetectDevice => self.handle_detect_device(),
             SM::DetectorOutput(devices) => self.handle_detector_output(devices),
             SM::SetDetectedDevice(device) => self.handle_set_detected_device(device),
+            SM::SaveTimeSettings => self.handle_save_time_settings(),
+            SM::SetTimeFromComputer => self.handle_set_time_from_computer(),
+            SM::PowerOn(power_on) => {
+                self.set_boot_settings(|settings| settings.power_on = power_on)
+            }
+            SM::BootAnimation(boot_animation) => {
+                self.set_boot_settings(|settings| settings.boot_animation = boot_animation)
+            }
+            SM::Restart(restart) => {
+                self.restart_confirmation = Some(restart);
+                self.restart_message = None;
+                self.restart_error_message = None;
+                Task::none()
+            }
+            SM::ConfirmRestart => self.handle_confirm_restart(),
+            SM::UpdateFirmware => self.handle_update_firmware(),
+            SM::CancelRestart => {
+                self.restart_confirmation = None;
+                Task::none()
+            }
         }
     }
 
     fn handle_request_message(&mut self, message: Request) -> Task<Message> {
         log::info!("Processing request: {:?}", &message);
-        if let Some(sender) = &mut self.sender {
-            if let Err(err) = sender.try_send(message.clone()) {
-                log::error!("Error sending message: {err}");
-            }
+        if let Some(sender) = &mut self.sender
+            && let Err(err) = sender.try_send(message.clone())
+        {
+            log::error!("Error sending message: {err}");
         }
         if let Request::Set(message) = &message {
             match message {
@@ -185,10 +420,52 @@ impl App {
                 }
                 SetRequest::Preset(id) => {
                     self.set_selected_preset(id);
+                    self.active_playlist = None;
+                    self.active_clip = None;
                     self.update(Message::Request(Request::Get(
                         GetRequest::CurrentPresetSettings,
                     )))
                 }
+                SetRequest::Palette(id) => {
+                    self.palette = *id;
+                    Task::none()
+                }
+                SetRequest::DeletePalette(id) => {
+                    if self.edited_palette.as_ref().is_some_and(|p| p.id == *id) {
+                        self.edited_palette = None;
+                    }
+                    Task::none()
+                }
+                SetRequest::Playlist(id) => {
+                    self.active_playlist = Some(*id);
+                    self.active_clip = None;
+                    Task::none()
+                }
+                SetRequest::DeletePlaylist(id) => {
+                    if self.active_playlist == Some(*id) {
+                        self.active_playlist = None;
+                    }
+                    if self.edited_playlist.as_ref().is_some_and(|p| p.id == *id) {
+                        self.edited_playlist = None;
+                    }
+                    Task::none()
+                }
+                SetRequest::RecallFavorite(_) => {
+                    self.active_playlist = None;
+                    self.active_clip = None;
+                    Task::none()
+                }
+                SetRequest::PlayClip(id) => {
+                    self.active_clip = Some(*id);
+                    self.active_playlist = None;
+                    Task::none()
+                }
+                SetRequest::DeleteClip(id) => {
+                    if self.active_clip == Some(*id) {
+                        self.active_clip = None;
+                    }
+                    Task::none()
+                }
                 _ => Task::none(),
             }
         } else {
@@ -214,7 +491,7 @@ impl App {
                     log::error!("Error sending message throuh mpsc sender: {err}");
                 }
                 self.sender = Some(sender);
-                self.update(Message::Request(Request::Get(GetRequest::Settings)))
+                self.fetch_device_state()
             }
             Response::Device(response) => self.handle_device_response(response),
         }
@@ -230,6 +507,18 @@ impl App {
         self.last_handshake = Instant::now();
 
         match response {
+            DR::Error if self.clip_upload.is_some() => {
+                self.clip_upload = None;
+                self.clip_error_message = Some(ClipErrorMessage::UploadFailed);
+            }
+            DR::Error if self.firmware_upload.is_some() => {
+                self.firmware_upload = None;
+                self.firmware_error_message = Some(FirmwareErrorMessage::UploadFailed);
+            }
+            DR::Error if self.requested_restart.is_some() => {
+                self.requested_restart = None;
+                self.restart_error_message = Some(RestartErrorMessage::Rejected);
+            }
             DR::Error
             | DR::Get(DGR::Ping)
             | DR::Get(DGR::WifiSettings(_))
@@ -237,34 +526,109 @@ impl App {
             | DR::Set(DSR::TurnOn)
             | DR::Set(DSR::TurnOff)
             | DR::Set(DSR::Preset)
-            | DR::Set(DSR::Settings)
             | DR::Set(DSR::WifiSettings)
             | DR::Set(DSR::CurrentPresetSettings)
             | DR::Set(DSR::Brightness)
             | DR::Set(DSR::Speed)
             | DR::Set(DSR::SaveSettings)
+            | DR::Set(DSR::FrameRate)
+            | DR::Set(DSR::Color)
+            | DR::Set(DSR::Palette)
+            | DR::Set(DSR::Playlist)
+            | DR::Set(DSR::Layers)
+            | DR::Set(DSR::PlayClip)
+            | DR::Set(DSR::Layout)
+            | DR::Set(DSR::SubscribeLogs)
             | DR::Set(DSR::Scale) => {}
 
+            // Settings are applied without a reboot, the device state may have changed
+            DR::Set(DSR::RecallFavorite) | DR::Set(DSR::Settings) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Settings)));
+            }
+            DR::Set(DSR::UploadScript) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Script)));
+            }
+            DR::Set(DSR::SaveFavorite) | DR::Set(DSR::DeleteFavorite) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Favorites)));
+            }
+            DR::Set(DSR::UploadClipChunk) => {
+                return self.send_next_clip_chunk();
+            }
+            DR::Set(DSR::SleepTimer)
+            | DR::Set(DSR::CancelSleepTimer)
+            | DR::Set(DSR::Sunrise)
+            | DR::Set(DSR::CancelSunrise) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Timers)));
+            }
+            DR::Set(DSR::BootSettings) => {
+                return self.update(Message::Request(Request::Get(GetRequest::BootSettings)));
+            }
+            DR::Set(DSR::LogSettings) => {
+                return self.update(Message::Request(Request::Get(GetRequest::LogSettings)));
+            }
+            DR::Set(DSR::BeginFirmwareUpdate) | DR::Set(DSR::UploadFirmwareChunk) => {
+                return self.send_next_firmware_chunk();
+            }
+            DR::Set(DSR::FinishFirmwareUpdate) => {
+                self.firmware_message = Some(FirmwareMessage::Restarting);
+            }
+            DR::Set(DSR::Reboot) | DR::Set(DSR::FactoryReset) => {
+                self.restart_message = self.requested_restart.take().map(RestartMessage::from);
+            }
+            DR::Set(DSR::Schedules) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Schedules)));
+            }
+            DR::Set(DSR::Time) | DR::Set(DSR::TimeSettings) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Time)));
+            }
+            DR::Set(DSR::FinishClip) | DR::Set(DSR::DeleteClip) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Clips)));
+            }
+
+            DR::Set(DSR::UploadPalette) | DR::Set(DSR::DeletePalette) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Palettes)));
+            }
+            DR::Set(DSR::UploadPlaylist) | DR::Set(DSR::DeletePlaylist) => {
+                return self.update(Message::Request(Request::Get(GetRequest::Playlists)));
+            }
+
             DR::Get(DGR::IsOn(is_on)) => {
                 self.is_on = is_on;
             }
             DR::Get(DGR::CurrentPresetId(id)) => {
                 self.set_selected_preset(&id);
             }
-            DR::Get(DGR::PresetInfo(preset_info)) => {
+            DR::Get(DGR::PresetInfo(page)) => {
+                let next_id = page.preset.id() + 1;
+                if page.preset.id() == 0 {
+                    self.loaded_presets.clear();
+                }
+                self.loaded_presets.push(page.preset);
+
+                if next_id < page.count {
+                    return self.update(Message::Request(Request::Get(GetRequest::PresetInfo(
+                        next_id,
+                    ))));
+                }
+
+                let preset_info = std::mem::take(&mut self.loaded_presets);
                 self.config.set_preset_info(preset_info.clone());
                 self.preset = combo_box::State::new(preset_info);
+                if let Some(selected_preset) = &self.selected_preset {
+                    self.set_selected_preset(&selected_preset.id());
+                }
                 self.preset_info_message = Some(PresetInfoMessage::PresetInfoLoaded);
                 self.save_config();
             }
             DR::Get(DGR::Settings(settings)) => {
                 self.is_on = settings.is_on();
                 self.set_selected_preset(&settings.current_preset_id());
-                let current_preset_settings =
-                    &settings.preset_settings()[settings.current_preset_id() as usize];
-                self.brightness = current_preset_settings.brightness();
-                self.speed = current_preset_settings.speed();
-                self.scale = current_preset_settings.scale();
+                self.active_playlist = settings.active_playlist();
+                self.active_clip = settings.active_clip();
+                self.frame_rate = settings.renderer_settings().frame_rate();
+                if let Some(layout) = settings.layout() {
+                    self.set_layout(layout);
+                }
 
                 match serde_json::to_string_pretty(&settings) {
                     Ok(text) => {
@@ -272,26 +636,135 @@ impl App {
                     }
                     Err(err) => log::error!("{err}"),
                 }
+
+                // Only changed preset settings are included, the device fills in the defaults
+                return self.update(Message::Request(Request::Get(
+                    GetRequest::CurrentPresetSettings,
+                )));
             }
             DR::Get(DGR::CurrentPresetSettings(preset_settings)) => {
                 self.brightness = preset_settings.brightness();
                 self.speed = preset_settings.speed();
                 self.scale = preset_settings.scale();
+                self.set_color(preset_settings.color());
+                self.palette = preset_settings.palette();
+            }
+            DR::Get(DGR::Stats(stats)) => {
+                if self.stats_history.len() == STATS_HISTORY_LENGTH {
+                    self.stats_history.pop_front();
+                }
+                self.stats_history.push_back(stats);
+                self.stats = Some(stats);
+            }
+            DR::Get(DGR::Palettes(palettes)) => {
+                self.palettes = palettes;
+            }
+            DR::Get(DGR::Palette(palette)) => {
+                self.set_edited_palette(palette);
+            }
+            DR::Get(DGR::Playlists(playlists)) => {
+                self.playlists = playlists;
+            }
+            DR::Get(DGR::Playlist(playlist)) => {
+                self.set_edited_playlist(playlist);
+            }
+            DR::Get(DGR::Favorites(favorites)) => {
+                self.favorites = favorites;
+            }
+            DR::Get(DGR::Layers(layers)) => {
+                self.layers = layers;
+            }
+            DR::Get(DGR::Script(script)) => {
+                self.device_script = Some(script);
+            }
+            DR::Get(DGR::Clips(clip_list)) => {
+                self.clip_list = Some(clip_list);
+            }
+            DR::Get(DGR::Time(time)) => {
+                self.utc_offset_text = format_utc_offset(time.settings.utc_offset);
+                self.ntp_server_text = time.settings.ntp_server.clone();
+                self.device_time = Some((time, Instant::now()));
+            }
+            DR::Get(DGR::Schedules(schedules)) => {
+                self.schedules = schedules;
+            }
+            DR::Get(DGR::BootSettings(boot_settings)) => {
+                self.boot_settings = Some(boot_settings);
+            }
+            DR::Get(DGR::FirmwareInfo(info)) => {
+                self.firmware_info = Some(info);
+            }
+            DR::Get(DGR::LogSettings(log_settings)) => {
+                self.log_collector_text = log_settings
+                    .collector
+                    .map(|collector| collector.to_string())
+                    .unwrap_or_default();
+                self.log_settings = Some(log_settings);
+            }
+            DR::Log(entry) => {
+                if self.log_entries.len() == LOG_HISTORY_LENGTH {
+                    self.log_entries.pop_front();
+                }
+                self.log_entries.push_back(entry);
+            }
+            DR::Get(DGR::ResetToken(token)) => {
+                if let Some(restart) = self.requested_restart {
+                    let request = match restart {
+                        DeviceRestart::Reboot => SetRequest::Reboot(token.token),
+                        DeviceRestart::FactoryReset => SetRequest::FactoryReset(token.token),
+                    };
+                    return self.update(Message::Request(Request::Set(request)));
+                }
+            }
+            DR::Get(DGR::Timers(timers)) => {
+                self.timers = Some((timers, Instant::now()));
             }
         }
 
         // Fetch device info if it has been reconnected
         match self.is_device_connected {
-            false => self.update(Message::Request(Request::Get(GetRequest::Settings))),
+            false => self.fetch_device_state(),
             true => Task::none(),
         }
     }
 
+    fn fetch_device_state(&mut self) -> Task<Message> {
+        Task::batch([
+            self.update(Message::Request(Request::Get(GetRequest::Settings))),
+            self.update(Message::Request(Request::Get(GetRequest::Palettes))),
+            self.update(Message::Request(Request::Get(GetRequest::Playlists))),
+            self.update(Message::Request(Request::Get(GetRequest::Favorites))),
+            self.update(Message::Request(Request::Get(GetRequest::Layers))),
+            self.update(Message::Request(Request::Get(GetRequest::Script))),
+            self.update(Message::Request(Request::Get(GetRequest::Clips))),
+            self.update(Message::Request(Request::Get(GetRequest::Time))),
+            self.update(Message::Request(Request::Get(GetRequest::Schedules))),
+            self.update(Message::Request(Request::Get(GetRequest::Timers))),
+            self.update(Message::Request(Request::Get(GetRequest::BootSettings))),
+            self.update(Message::Request(Request::Get(GetRequest::FirmwareInfo))),
+        ])
+    }
+
     fn handle_ui_message(&mut self, message: UIMessage) -> Task<Message> {
         match message {
-            UIMessage::Brightness(val) => self.brightness = val,
-            UIMessage::Speed(val) => self.speed = val,
-            UIMessage::Scale(val) => self.scale = val,
+            UIMessage::Param(ParamId::Brightness, val) => self.brightness = val,
+            UIMessage::Param(ParamId::Speed, val) => self.speed = val,
+            UIMessage::Param(ParamId::Scale, val) => self.scale = val,
+            UIMessage::Param(ParamId::Color | ParamId::Palette, _) => {}
+            UIMessage::Color(color) => self.set_color(color),
+            UIMessage::ColorHex(hex) => self.color_hex_text = hex,
+            UIMessage::SubmitColorHex => return self.handle_submit_color_hex(),
+            UIMessage::FavoriteName(name) => self.favorite_name_text = name,
+            UIMessage::SaveFavorite => return self.handle_save_favorite(),
+            UIMessage::FrameRate(val) => self.frame_rate = val,
+            UIMessage::UtcOffset(offset) => self.utc_offset_text = offset,
+            UIMessage::NtpServer(server) => self.ntp_server_text = server,
+            UIMessage::FirmwarePath(path) => self.firmware_path_text = path,
+            UIMessage::SleepMinutes(minutes) => self.sleep_minutes_text = minutes,
+            UIMessage::SunriseTime(time) => self.sunrise_time_text = time,
+            UIMessage::SunriseDuration(duration) => self.sunrise_duration_text = duration,
+            UIMessage::StartSleepTimer => return self.handle_start_sleep_timer(),
+            UIMessage::SetSunrise => return self.handle_set_sunrise(),
             UIMessage::Ip(ip) => self.ip_text = ip,
             UIMessage::Port(port) => self.port_text = port,
             UIMessage::Subnet(subnet) => self.subnet_text = subnet,
@@ -308,6 +781,541 @@ impl App {
         Task::none()
     }
 
+    fn set_color(&mut self, color: [u8; 3]) {
+        self.color = color;
+        self.color_hex_text = hex_color(&color);
+        self.color_error_message = None;
+    }
+
+    fn handle_submit_color_hex(&mut self) -> Task<Message> {
+        match parse_hex_color(&self.color_hex_text) {
+            Some(color) => {
+                self.set_color(color);
+                self.update(Message::Request(Request::Set(SetRequest::Color(color))))
+            }
+            None => {
+                self.color_error_message = Some(ColorErrorMessage::InvalidHex);
+                Task::none()
+            }
+        }
+    }
+
+    fn handle_start_sleep_timer(&mut self) -> Task<Message> {
+        let Some(minutes) = self
+            .sleep_minutes_text
+            .trim()
+            .parse::<u8>()
+            .ok()
+            .filter(|minutes| *minutes > 0)
+        else {
+            self.timer_error_message = Some(TimerErrorMessage::InvalidSleepMinutes);
+            return Task::none();
+        };
+        self.timer_error_message = None;
+        self.update(Message::Request(Request::Set(SetRequest::SleepTimer(
+            minutes,
+        ))))
+    }
+
+    fn handle_set_sunrise(&mut self) -> Task<Message> {
+        let Some(minute) = parse_time_of_day(&self.sunrise_time_text) else {
+            self.timer_error_message = Some(TimerErrorMessage::InvalidSunriseTime);
+            return Task::none();
+        };
+        let Some(duration) = self
+            .sunrise_duration_text
+            .trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|duration| (1..=MAX_SUNRISE_DURATION).contains(duration))
+        else {
+            self.timer_error_message = Some(TimerErrorMessage::InvalidSunriseDuration);
+            return Task::none();
+        };
+        // The device can only tell when the sunrise should start once it knows the time
+        let is_device_time_set = self
+            .device_time
+            .as_ref()
+            .is_some_and(|(time, _)| time.unix_ms.is_some());
+        if !is_device_time_set {
+            self.timer_error_message = Some(TimerErrorMessage::DeviceTimeNotSet);
+            return Task::none();
+        }
+        self.timer_error_message = None;
+        self.update(Message::Request(Request::Set(SetRequest::Sunrise(
+            SunriseRequest { minute, duration },
+        ))))
+    }
+
+    /// Saves the current preset under the entered name, replacing the favorite with the same name
+    fn handle_save_favorite(&mut self) -> Task<Message> {
+        let name = self.favorite_name_text.trim().to_string();
+        if name.is_empty() {
+            self.favorite_error_message = Some(FavoriteErrorMessage::EmptyName);
+            return Task::none();
+        }
+
+        let id = match self.favorites.iter().find(|f| f.name() == name) {
+            Some(favorite) => Some(favorite.id()),
+            None => (0..MAX_FAVORITES).find(|id| self.favorites.iter().all(|f| f.id() != *id)),
+        };
+        let Some(id) = id else {
+            self.favorite_error_message = Some(FavoriteErrorMessage::NoFreeSlot);
+            return Task::none();
+        };
+
+        self.favorite_name_text.clear();
+        self.favorite_error_message = None;
+        self.update(Message::Request(Request::Set(SetRequest::SaveFavorite(
+            ItemSummary::new(id, name),
+        ))))
+    }
+
+    fn handle_palette_editor_message(&mut self, message: PaletteEditorMessage) -> Task<Message> {
+        use PaletteEditorMessage as PEM;
+
+        if let PEM::New = message {
+            let free_id = (0..MAX_PALETTES).find(|id| self.palettes.iter().all(|p| p.id() != *id));
+            match free_id {
+                Some(id) => self.set_edited_palette(DevicePalette::new(id)),
+                None => self.palette_error_message = Some(PaletteErrorMessage::NoFreeSlot),
+            }
+            return Task::none();
+        }
+
+        let Some(palette) = &mut self.edited_palette else {
+            return Task::none();
+        };
+
+        match message {
+            PEM::New => {}
+            PEM::Name(name) => palette.name = name,
+            PEM::StopPosition(idx, position) => palette.stops[idx][0] = position,
+            PEM::StopHex(idx, hex) => self.palette_stop_hex_texts[idx] = hex,
+            PEM::SubmitStopHex(idx) => match parse_hex_color(&self.palette_stop_hex_texts[idx]) {
+                Some(color) => {
+                    palette.stops[idx][1..].copy_from_slice(&color);
+                    self.palette_stop_hex_texts[idx] = hex_color(&color);
+                    self.palette_error_message = None;
+                }
+                None => self.palette_error_message = Some(PaletteErrorMessage::InvalidHex),
+            },
+            PEM::AddStop => {
+                if palette.stops.len() >= MAX_PALETTE_STOPS {
+                    self.palette_error_message = Some(PaletteErrorMessage::TooManyStops);
+                    return Task::none();
+                }
+                let [r, g, b] = palette.color_at(128);
+                palette.stops.push([128, r, g, b]);
+                self.palette_stop_hex_texts.push(hex_color(&[r, g, b]));
+            }
+            PEM::RemoveStop(idx) => {
+                if palette.stops.len() > 1 {
+                    palette.stops.remove(idx);
+                    self.palette_stop_hex_texts.remove(idx);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    fn set_edited_palette(&mut self, palette: DevicePalette) {
+        self.palette_stop_hex_texts = palette
+            .stops
+            .iter()
+            .map(|stop| hex_color(&[stop[1], stop[2], stop[3]]))
+            .collect();
+        self.edited_palette = Some(palette);
+        self.palette_error_message = None;
+    }
+
+    fn handle_playlist_editor_message(&mut self, message: PlaylistEditorMessage) -> Task<Message> {
+        use PlaylistEditorMessage as PEM;
+
+        if let PEM::New = message {
+            let free_id =
+                (0..MAX_PLAYLISTS).find(|id| self.playlists.iter().all(|p| p.id() != *id));
+            match free_id {
+                Some(id) => self.set_edited_playlist(DevicePlaylist::new(id)),
+                None => self.playlist_error_message = Some(PlaylistErrorMessage::NoFreeSlot),
+            }
+            return Task::none();
+        }
+
+        let current_entry = self.current_playlist_entry();
+        let Some(playlist) = &mut self.edited_playlist else {
+            return Task::none();
+        };
+
+        match message {
+            PEM::New => {}
+            PEM::Name(name) => playlist.name = name,
+            PEM::Shuffle(shuffle) => playlist.shuffle = shuffle,
+            PEM::AddCurrentPreset => {
+                let Some(entry) = current_entry else {
+                    self.playlist_error_message = Some(PlaylistErrorMessage::NoPresetSelected);
+                    return Task::none();
+                };
+                if playlist.entries.len() >= MAX_PLAYLIST_ENTRIES {
+                    self.playlist_error_message = Some(PlaylistErrorMessage::TooManyEntries);
+                    return Task::none();
+                }
+                self.playlist_duration_texts
+                    .push(entry.duration.to_string());
+                playlist.entries.push(entry);
+                self.playlist_error_message = None;
+            }
+            PEM::EntryDuration(idx, text) => {
+                match text.trim().parse() {
+                    Ok(duration) => {
+                        playlist.entries[idx].duration = duration;
+                        self.playlist_error_message = None;
+                    }
+                    Err(_) => {
+                        self.playlist_error_message = Some(PlaylistErrorMessage::InvalidDuration)
+                    }
+                }
+                self.playlist_duration_texts[idx] = text;
+            }
+            PEM::MoveEntryUp(idx) => {
+                if idx > 0 {
+                    playlist.entries.swap(idx - 1, idx);
+                    self.playlist_duration_texts.swap(idx - 1, idx);
+                }
+            }
+            PEM::RemoveEntry(idx) => {
+                playlist.entries.remove(idx);
+                self.playlist_duration_texts.remove(idx);
+            }
+        }
+        Task::none()
+    }
+
+    /// Playlist entry showing the selected preset with the settings currently set on the device
+    fn current_playlist_entry(&self) -> Option<PlaylistEntry> {
+        let preset = self.selected_preset.as_ref()?;
+        Some(PlaylistEntry {
+            preset_id: preset.id(),
+            preset_settings: PresetSettings::new(
+                self.brightness,
+                self.speed,
+                self.scale,
+                self.color,
+                self.palette,
+            ),
+            duration: DEFAULT_PLAYLIST_ENTRY_DURATION,
+        })
+    }
+
+    fn handle_layer_editor_message(&mut self, message: LayerEditorMessage) -> Task<Message> {
+        use LayerEditorMessage as LEM;
+
+        match message {
+            LEM::AddCurrentPreset => {
+                let Some(entry) = self.current_playlist_entry() else {
+                    self.layer_error_message = Some(LayerErrorMessage::NoPresetSelected);
+                    return Task::none();
+                };
+                if self.layers.len() >= MAX_LAYERS {
+                    self.layer_error_message = Some(LayerErrorMessage::TooManyLayers);
+                    return Task::none();
+                }
+                self.layers.push(Layer {
+                    preset_id: entry.preset_id,
+                    preset_settings: entry.preset_settings,
+                    blend_mode: BlendMode::default(),
+                    opacity: 255,
+                });
+                self.layer_error_message = None;
+            }
+            LEM::BlendMode(idx, blend_mode) => self.layers[idx].blend_mode = blend_mode,
+            LEM::Opacity(idx, opacity) => {
+                self.layers[idx].opacity = opacity;
+                // The slider only previews the layer on the device once it is released
+                return Task::none();
+            }
+            LEM::SubmitOpacity => {}
+            LEM::MoveUp(idx) => {
+                if idx > 0 {
+                    self.layers.swap(idx - 1, idx);
+                }
+            }
+            LEM::Remove(idx) => {
+                self.layers.remove(idx);
+            }
+        }
+        self.update(Message::Request(Request::Set(SetRequest::Layers(
+            self.layers.clone(),
+        ))))
+    }
+
+    fn handle_schedule_editor_message(&mut self, message: ScheduleEditorMessage) -> Task<Message> {
+        use ScheduleEditorMessage as SEM;
+
+        match message {
+            SEM::Time(time) => {
+                self.schedule_time_text = time;
+                return Task::none();
+            }
+            SEM::Day(weekday, is_selected) => {
+                match is_selected {
+                    true => self.schedule_days |= 1 << weekday,
+                    false => self.schedule_days &= !(1 << weekday),
+                }
+                return Task::none();
+            }
+            SEM::Action(action) => {
+                self.schedule_action = action;
+                return Task::none();
+            }
+            SEM::Favorite(favorite) => {
+                self.schedule_favorite = Some(favorite);
+                return Task::none();
+            }
+            SEM::Brightness(brightness) => {
+                self.schedule_brightness = brightness;
+                return Task::none();
+            }
+            SEM::Add => {
+                let Some(minute) = parse_time_of_day(&self.schedule_time_text) else {
+                    self.schedule_error_message = Some(ScheduleErrorMessage::InvalidTime);
+                    return Task::none();
+                };
+                if self.schedules.len() >= MAX_SCHEDULES {
+                    self.schedule_error_message = Some(ScheduleErrorMessage::TooManySchedules);
+                    return Task::none();
+                }
+                let action = match self.schedule_action {
+                    ScheduleActionKind::TurnOn => ScheduleAction::TurnOn,
+                    ScheduleActionKind::TurnOff => ScheduleAction::TurnOff,
+                    ScheduleActionKind::Preset => match &self.selected_preset {
+                        Some(preset) => ScheduleAction::Preset(preset.id()),
+                        None => {
+                            self.schedule_error_message =
+                                Some(ScheduleErrorMessage::NoPresetSelected);
+                            return Task::none();
+                        }
+                    },
+                    ScheduleActionKind::Favorite => match &self.schedule_favorite {
+                        Some(favorite) => ScheduleAction::Favorite(favorite.id()),
+                        None => {
+                            self.schedule_error_message =
+                                Some(ScheduleErrorMessage::NoFavoriteSelected);
+                            return Task::none();
+                        }
+                    },
+                    ScheduleActionKind::Brightness => {
+                        ScheduleAction::Brightness(self.schedule_brightness)
+                    }
+                };
+                self.schedules.push(Schedule {
+                    enabled: true,
+                    days: self.schedule_days,
+                    minute,
+                    action,
+                });
+                self.schedule_error_message = None;
+            }
+            SEM::Enabled(idx, enabled) => self.schedules[idx].enabled = enabled,
+            SEM::Remove(idx) => {
+                self.schedules.remove(idx);
+            }
+        }
+        self.update(Message::Request(Request::Set(SetRequest::Schedules(
+            self.schedules.clone(),
+        ))))
+    }
+
+    fn handle_script_editor_message(&mut self, message: ScriptEditorMessage) -> Task<Message> {
+        use ScriptEditorMessage as SEM;
+
+        match message {
+            SEM::Edit(action) => self.script_content.perform(action),
+            SEM::Name(name) => self.script_name = name,
+            SEM::Upload => {
+                let source = self.script_content.text();
+                let code = match script::compile(&source) {
+                    Ok(code) => code,
+                    Err(err) => {
+                        self.script_error_message = Some(ScriptErrorMessage::Compile(err));
+                        return Task::none();
+                    }
+                };
+                self.script_error_message = None;
+                self.config.set_script_source(source);
+                self.save_config();
+                return self.update(Message::Request(Request::Set(SetRequest::UploadScript(
+                    DeviceScript {
+                        name: self.script_name.clone(),
+                        code,
+                    },
+                ))));
+            }
+        }
+        Task::none()
+    }
+
+    fn handle_clip_editor_message(&mut self, message: ClipEditorMessage) -> Task<Message> {
+        use ClipEditorMessage as CEM;
+
+        match message {
+            CEM::Path(path) => self.clip_path_text = path,
+            CEM::Fps(fps) => self.clip_fps_text = fps,
+            CEM::Name(name) => self.clip_name_text = name,
+            CEM::Export => return self.handle_export_clip(),
+        }
+        Task::none()
+    }
+
+    /// Encodes the selected GIF or image sequence and starts uploading it into the slot of the
+    /// clip with the same name, or into a free slot
+    fn handle_export_clip(&mut self) -> Task<Message> {
+        let Some(clip_list) = &self.clip_list else {
+            return Task::none();
+        };
+
+        let name = self.clip_name_text.trim().to_string();
+        if name.is_empty() {
+            self.clip_error_message = Some(ClipErrorMessage::EmptyName);
+            return Task::none();
+        }
+        let fps_text = self.clip_fps_text.trim();
+        let fps = match fps_text.is_empty() {
+            true => None,
+            false => match fps_text.parse() {
+                Ok(fps) if fps > 0 => Some(fps),
+                _ => {
+                    self.clip_error_message = Some(ClipErrorMessage::InvalidFps);
+                    return Task::none();
+                }
+            },
+        };
+
+        let id = match clip_list.clips.iter().find(|c| c.name == name) {
+            Some(clip) => Some(clip.id),
+            None => (0..MAX_CLIPS).find(|id| clip_list.clips.iter().all(|c| c.id != *id)),
+        };
+        let Some(id) = id else {
+            self.clip_error_message = Some(ClipErrorMessage::NoFreeSlot);
+            return Task::none();
+        };
+
+        let led_count = clip_list.led_count;
+        let data = clip::load_frames(Path::new(self.clip_path_text.trim()), led_count).and_then(
+            |(frames, file_fps)| {
+                let fps = fps.or(file_fps).unwrap_or(clip::DEFAULT_CLIP_FPS);
+                clip::encode(&frames, fps, led_count)
+            },
+        );
+        let data = match data {
+            Ok(data) => data,
+            Err(err) => {
+                self.clip_error_message = Some(ClipErrorMessage::Export(err));
+                return Task::none();
+            }
+        };
+        if data.len() > clip_list.max_length as usize {
+            self.clip_error_message = Some(ClipErrorMessage::TooLong);
+            return Task::none();
+        }
+
+        self.clip_error_message = None;
+        self.clip_upload = Some(ClipUpload {
+            id,
+            name,
+            data,
+            offset: 0,
+        });
+        self.send_next_clip_chunk()
+    }
+
+    /// Sends the next chunk of the uploaded clip once the previous one has been acknowledged,
+    /// and finishes the upload after the last one
+    fn send_next_clip_chunk(&mut self) -> Task<Message> {
+        let Some(upload) = &mut self.clip_upload else {
+            return Task::none();
+        };
+
+        if upload.offset >= upload.data.len() {
+            let upload = self.clip_upload.take().unwrap();
+            self.clip_name_text.clear();
+            return self.update(Message::Request(Request::Set(SetRequest::FinishClip(
+                FinishedClip {
+                    id: upload.id,
+                    name: upload.name,
+                    length: upload.data.len() as u32,
+                },
+            ))));
+        }
+
+        let end = (upload.offset + CLIP_CHUNK_LENGTH).min(upload.data.len());
+        let request = SetRequest::UploadClipChunk {
+            id: upload.id,
+            offset: upload.offset as u32,
+            data: upload.data[upload.offset..end].to_vec(),
+        };
+        upload.offset = end;
+        self.update(Message::Request(Request::Set(request)))
+    }
+
+    fn handle_layout_editor_message(&mut self, message: LayoutEditorMessage) -> Task<Message> {
+        use LayoutEditorMessage as LEM;
+
+        if let LEM::Width(width) = message {
+            self.layout_width_text = width;
+            return Task::none();
+        }
+        if let LEM::Height(height) = message {
+            self.layout_height_text = height;
+            return Task::none();
+        }
+
+        let Some(layout) = &mut self.layout else {
+            return Task::none();
+        };
+
+        match message {
+            LEM::Width(_) | LEM::Height(_) => {}
+            LEM::Serpentine(serpentine) => layout.serpentine = serpentine,
+            LEM::Rotation(rotation) => layout.rotation = rotation.0,
+            LEM::FlipX(flip_x) => layout.flip_x = flip_x,
+            LEM::FlipY(flip_y) => layout.flip_y = flip_y,
+            LEM::Apply => {
+                let width = self.layout_width_text.trim().parse();
+                let height = self.layout_height_text.trim().parse();
+                match (width, height) {
+                    (Ok(width), Ok(height)) if width > 0 && height > 0 => {
+                        layout.width = width;
+                        layout.height = height;
+                        self.layout_error_message = None;
+                        let layout = *layout;
+                        return self
+                            .update(Message::Request(Request::Set(SetRequest::Layout(layout))));
+                    }
+                    _ => self.layout_error_message = Some(LayoutErrorMessage::InvalidSize),
+                }
+            }
+        }
+        Task::none()
+    }
+
+    fn set_layout(&mut self, layout: MatrixLayout) {
+        self.layout_width_text = layout.width.to_string();
+        self.layout_height_text = layout.height.to_string();
+        self.layout = Some(layout);
+        self.layout_error_message = None;
+    }
+
+    fn set_edited_playlist(&mut self, playlist: DevicePlaylist) {
+        self.playlist_duration_texts = playlist
+            .entries
+            .iter()
+            .map(|entry| entry.duration.to_string())
+            .collect();
+        self.edited_playlist = Some(playlist);
+        self.playlist_error_message = None;
+    }
+
     fn handle_save(&mut self) -> Task<Message> {
         match self.handle_save_fallible() {
             Ok(task) => task,
@@ -360,23 +1368,170 @@ impl App {
         )
     }
 
-    fn handle_detect_device(&mut self) -> Task<Message> {
-        self.detector_error_message = None;
-        match self.handle_detect_device_fallible() {
-            Ok(task) => {
-                self.detected_devices = DetectedDevicesState::Loading;
-                task
-            }
-            Err(err) => {
-                match err {
-                    Error::IpNetworkParse(_) => {
-                        self.detector_error_message = Some(DetectorErrorMessage::SubnetParse)
-                    }
-                    _ => log::error!("{err}"),
-                }
-                Task::none()
-            }
-        }
+    fn handle_save_time_settings(&mut self) -> Task<Message> {
+        let Some(utc_offset) = parse_utc_offset(&self.utc_offset_text) else {
+            self.time_error_message = Some(TimeErrorMessage::InvalidUtcOffset);
+            return Task::none();
+        };
+        self.time_error_message = None;
+        self.update(Message::Request(Request::Set(SetRequest::TimeSettings(
+            TimeSettings {
+                utc_offset,
+                ntp_server: self.ntp_server_text.trim().to_string(),
+            },
+        ))))
+    }
+
+    /// Announces the size and the digest of the image to the device, the chunks are sent once it
+    /// has been acknowledged
+    fn handle_update_firmware(&mut self) -> Task<Message> {
+        let Some(info) = &self.firmware_info else {
+            return Task::none();
+        };
+
+        let data = match std::fs::read(self.firmware_path_text.trim()) {
+            Ok(data) => data,
+            Err(err) => {
+                self.firmware_error_message = Some(FirmwareErrorMessage::Read(err));
+                return Task::none();
+            }
+        };
+        if data.first() != Some(&FIRMWARE_IMAGE_MAGIC) {
+            self.firmware_error_message = Some(FirmwareErrorMessage::InvalidImage);
+            return Task::none();
+        }
+        if data.len() > info.max_size as usize {
+            self.firmware_error_message = Some(FirmwareErrorMessage::TooLarge);
+            return Task::none();
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(&data);
+        let request = FirmwareUpdateRequest {
+            size: data.len() as u32,
+            sha256: hasher.finalize(),
+        };
+
+        self.firmware_error_message = None;
+        self.firmware_message = None;
+        self.firmware_upload = Some(FirmwareUpload { data, offset: 0 });
+        self.update(Message::Request(Request::Set(
+            SetRequest::BeginFirmwareUpdate(request),
+        )))
+    }
+
+    /// Sends the next chunk of the firmware once the previous one has been acknowledged, and
+    /// finishes the update after the last one
+    fn send_next_firmware_chunk(&mut self) -> Task<Message> {
+        let Some(upload) = &mut self.firmware_upload else {
+            return Task::none();
+        };
+
+        if upload.offset >= upload.data.len() {
+            self.firmware_upload = None;
+            return self.update(Message::Request(Request::Set(
+                SetRequest::FinishFirmwareUpdate,
+            )));
+        }
+
+        let end = (upload.offset + FIRMWARE_CHUNK_LENGTH).min(upload.data.len());
+        let request = SetRequest::UploadFirmwareChunk {
+            offset: upload.offset as u32,
+            data: upload.data[upload.offset..end].to_vec(),
+        };
+        upload.offset = end;
+        self.update(Message::Request(Request::Set(request)))
+    }
+
+    fn handle_confirm_restart(&mut self) -> Task<Message> {
+        // The device only restarts once it gets back the token it has handed out
+        self.requested_restart = self.restart_confirmation.take();
+        self.update(Message::Request(Request::Get(GetRequest::ResetToken)))
+    }
+
+    fn handle_log_viewer_message(&mut self, message: LogViewerMessage) -> Task<Message> {
+        use LogViewerMessage as LVM;
+
+        match message {
+            LVM::Level(level) => self.set_log_settings(|settings| settings.level = level),
+            LVM::Collector(collector) => {
+                self.log_collector_text = collector;
+                Task::none()
+            }
+            LVM::SaveCollector => {
+                let collector_text = self.log_collector_text.trim();
+                let collector = match collector_text.is_empty() {
+                    true => None,
+                    false => match collector_text.parse::<SocketAddrV4>() {
+                        Ok(addr) => Some(LogCollector {
+                            address: addr.ip().octets(),
+                            port: addr.port(),
+                        }),
+                        Err(_) => {
+                            self.log_error_message = Some(LogErrorMessage::InvalidCollector);
+                            return Task::none();
+                        }
+                    },
+                };
+                self.log_error_message = None;
+                self.set_log_settings(|settings| settings.collector = collector)
+            }
+            LVM::Clear => {
+                self.log_entries.clear();
+                Task::none()
+            }
+        }
+    }
+
+    fn set_log_settings(&mut self, f: impl FnOnce(&mut LogSettings)) -> Task<Message> {
+        let Some(log_settings) = &mut self.log_settings else {
+            return Task::none();
+        };
+        f(log_settings);
+        let log_settings = *log_settings;
+        self.update(Message::Request(Request::Set(SetRequest::LogSettings(
+            log_settings,
+        ))))
+    }
+
+    fn set_boot_settings(&mut self, f: impl FnOnce(&mut BootSettings)) -> Task<Message> {
+        let Some(boot_settings) = &mut self.boot_settings else {
+            return Task::none();
+        };
+        f(boot_settings);
+        let boot_settings = *boot_settings;
+        self.update(Message::Request(Request::Set(SetRequest::BootSettings(
+            boot_settings,
+        ))))
+    }
+
+    fn handle_set_time_from_computer(&mut self) -> Task<Message> {
+        let unix_ms = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.update(Message::Request(Request::Set(SetRequest::Time(
+            ManualTime { unix_ms },
+        ))))
+    }
+
+    fn handle_detect_device(&mut self) -> Task<Message> {
+        self.detector_error_message = None;
+        match self.handle_detect_device_fallible() {
+            Ok(task) => {
+                self.detected_devices = DetectedDevicesState::Loading;
+                task
+            }
+            Err(err) => {
+                match err {
+                    Error::IpNetworkParse(_) => {
+                        self.detector_error_message = Some(DetectorErrorMessage::SubnetParse)
+                    }
+                    _ => log::error!("{err}"),
+                }
+                Task::none()
+            }
+        }
     }
 
     fn handle_detect_device_fallible(&mut self) -> Result<Task<Message>> {
@@ -406,12 +1561,20 @@ impl App {
         )))
     }
 
-    fn home_page(&self) -> Element<Message> {
+    fn home_page(&self) -> Element<'_, Message> {
         let page_title = text!("Smart Lights").size(30);
 
         let settings_button = button("Settings").on_press(Message::Page(Page::Settings));
-        let load_presets_button =
-            button("Load Presets").on_press(Message::Request(Request::Get(GetRequest::PresetInfo)));
+        let palettes_button = button("Palettes").on_press(Message::Page(Page::Palettes));
+        let playlists_button = button("Playlists").on_press(Message::Page(Page::Playlists));
+        let layers_button = button("Layers").on_press(Message::Page(Page::Layers));
+        let script_button = button("Script").on_press(Message::Page(Page::Script));
+        let clips_button = button("Clips").on_press(Message::Page(Page::Clips));
+        let schedules_button = button("Schedules").on_press(Message::Page(Page::Schedules));
+        let diagnostics_button = button("Diagnostics").on_press(Message::Page(Page::Diagnostics));
+        let logs_button = button("Logs").on_press(Message::Page(Page::Logs));
+        let load_presets_button = button("Load Presets")
+            .on_press(Message::Request(Request::Get(GetRequest::PresetInfo(0))));
         let preset_info_message = match &self.preset_info_message {
             Some(msg) => text!("{msg}").color(self.theme().palette().success),
             None => text!(""),
@@ -422,6 +1585,14 @@ impl App {
 
         let top_row = row![
             settings_button,
+            palettes_button,
+            playlists_button,
+            layers_button,
+            script_button,
+            clips_button,
+            schedules_button,
+            diagnostics_button,
+            logs_button,
             load_presets_button,
             device_save_settings_button,
             preset_info_message,
@@ -446,7 +1617,17 @@ impl App {
         }
         .on_press(Message::Request(Request::Set(SetRequest::Toggle)));
 
-        let control_row = row![preset_combo_box, toggle_button]
+        let active_playlist = self
+            .playlists
+            .iter()
+            .find(|p| Some(p.id()) == self.active_playlist);
+        let playlist_pick_list =
+            pick_list(self.playlists.as_slice(), active_playlist, |playlist| {
+                Message::Request(Request::Set(SetRequest::Playlist(playlist.id())))
+            })
+            .placeholder("Playlist");
+
+        let control_row = row![preset_combo_box, playlist_pick_list, toggle_button]
             .padding(5)
             .spacing(10)
             .align_y(Center);
@@ -456,14 +1637,142 @@ impl App {
                 top_row,
                 row![page_title].padding(5),
                 control_row,
-                self.slider_controls()
+                self.view_favorites_bar(),
+                self.slider_controls(),
+                self.view_timers(),
+                self.view_stats(),
             ]
             .padding(10),
         )
         .into()
     }
 
-    fn settings_page(&self) -> Element<Message> {
+    fn view_timers(&self) -> Element<'_, Message> {
+        let (sleep_status, sunrise_status) = match &self.timers {
+            Some((timers, received_at)) => {
+                let sleep_status = match timers.sleep_remaining {
+                    Some(remaining) => {
+                        let remaining = remaining.saturating_sub(received_at.elapsed().as_secs());
+                        format!("Lights off in {}:{:02}", remaining / 60, remaining % 60)
+                    }
+                    None => String::from("Sleep timer is not set"),
+                };
+                let sunrise_status = match &timers.sunrise {
+                    Some(sunrise) => {
+                        let utc_offset = self
+                            .device_time
+                            .as_ref()
+                            .map_or(0, |(time, _)| time.settings.utc_offset);
+                        let target = DateTime::from_unix(sunrise.target as i64, utc_offset);
+                        let state = match sunrise.started {
+                            true => "in progress",
+                            false => "set",
+                        };
+                        format!(
+                            "Sunrise {state}, ends at {:02}:{:02} after {} min",
+                            target.hour, target.minute, sunrise.duration
+                        )
+                    }
+                    None => String::from("Sunrise is not set"),
+                };
+                (sleep_status, sunrise_status)
+            }
+            None => (String::new(), String::new()),
+        };
+
+        let sleep_minutes_input = text_input("Minutes", &self.sleep_minutes_text)
+            .on_input(|input| Message::UI(UIMessage::SleepMinutes(input)))
+            .on_submit(Message::UI(UIMessage::StartSleepTimer))
+            .width(80);
+        let start_sleep_button =
+            button("Start Sleep Timer").on_press(Message::UI(UIMessage::StartSleepTimer));
+        let cancel_sleep_button =
+            button("Cancel").on_press(Message::Request(Request::Set(SetRequest::CancelSleepTimer)));
+
+        let sunrise_time_input = text_input("HH:MM", &self.sunrise_time_text)
+            .on_input(|input| Message::UI(UIMessage::SunriseTime(input)))
+            .width(80);
+        let sunrise_duration_input = text_input("Minutes", &self.sunrise_duration_text)
+            .on_input(|input| Message::UI(UIMessage::SunriseDuration(input)))
+            .width(80);
+        let set_sunrise_button = button("Set Sunrise").on_press(Message::UI(UIMessage::SetSunrise));
+        let cancel_sunrise_button =
+            button("Cancel").on_press(Message::Request(Request::Set(SetRequest::CancelSunrise)));
+
+        let error_message = match &self.timer_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            row![
+                text!("Fade out over"),
+                sleep_minutes_input,
+                text!("min"),
+                start_sleep_button,
+                cancel_sleep_button,
+                text(sleep_status)
+            ]
+            .spacing(10)
+            .align_y(Center),
+            row![
+                text!("Sunrise at"),
+                sunrise_time_input,
+                text!("over"),
+                sunrise_duration_input,
+                text!("min"),
+                set_sunrise_button,
+                cancel_sunrise_button,
+                text(sunrise_status)
+            ]
+            .spacing(10)
+            .align_y(Center),
+            error_message,
+        ]
+        .padding(5)
+        .spacing(10)
+        .into()
+    }
+
+    fn view_favorites_bar(&self) -> Element<'_, Message> {
+        let favorite_buttons = row(self.favorites.iter().map(|favorite| {
+            row![
+                button(text(favorite.name())).on_press(Message::Request(Request::Set(
+                    SetRequest::RecallFavorite(favorite.id())
+                ))),
+                button("x")
+                    .style(button::danger)
+                    .on_press(Message::Request(Request::Set(SetRequest::DeleteFavorite(
+                        favorite.id()
+                    )))),
+            ]
+            .into()
+        }))
+        .spacing(10)
+        .wrap();
+
+        let name_input = text_input("Favorite name", &self.favorite_name_text)
+            .on_input(|input| Message::UI(UIMessage::FavoriteName(input)))
+            .on_submit(Message::UI(UIMessage::SaveFavorite))
+            .width(200);
+        let save_button = button("Save Favorite").on_press(Message::UI(UIMessage::SaveFavorite));
+        let error_message = match &self.favorite_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            favorite_buttons,
+            row![name_input, save_button, error_message]
+                .spacing(10)
+                .align_y(Center),
+        ]
+        .padding(5)
+        .spacing(10)
+        .into()
+    }
+
+    fn settings_page(&self) -> Element<'_, Message> {
         let page_title = text!("Device Settings").size(30);
         let device_save_settings_button = self.device_save_settings_button();
 
@@ -481,6 +1790,12 @@ impl App {
                 row![page_title].padding(5),
                 self.view_device_detector_settings(),
                 self.view_ip_port_settings(),
+                self.view_renderer_settings(),
+                self.view_layout_settings(),
+                self.view_time_settings(),
+                self.view_boot_settings(),
+                self.view_firmware_settings(),
+                self.view_restart_settings(),
                 self.view_device_settings(),
             ]
             .spacing(10)
@@ -489,6 +1804,641 @@ impl App {
         .into()
     }
 
+    fn palettes_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Palettes").size(30);
+
+        let palette_list = column(self.palettes.iter().map(|palette| {
+            button(text(palette.to_string()))
+                .on_press(Message::Request(Request::Get(GetRequest::Palette(
+                    palette.id(),
+                ))))
+                .style(Self::detected_devices_button_style)
+                .into()
+        }));
+        let new_button = button("New").on_press(Message::PaletteEditor(PaletteEditorMessage::New));
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::Palettes)));
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                row![palette_list].padding(5),
+                row![new_button, refresh_button].spacing(10).padding(5),
+                self.view_palette_editor(),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn playlists_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Playlists").size(30);
+
+        let playlist_list = column(self.playlists.iter().map(|playlist| {
+            button(text(playlist.to_string()))
+                .on_press(Message::Request(Request::Get(GetRequest::Playlist(
+                    playlist.id(),
+                ))))
+                .style(Self::detected_devices_button_style)
+                .into()
+        }));
+        let new_button =
+            button("New").on_press(Message::PlaylistEditor(PlaylistEditorMessage::New));
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::Playlists)));
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                row![playlist_list].padding(5),
+                row![new_button, refresh_button].spacing(10).padding(5),
+                self.view_playlist_editor(),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn layers_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Layers").size(30);
+        let device_save_settings_button = self.device_save_settings_button();
+        let base_color = self.color;
+
+        let layer_list = column(self.layers.iter().enumerate().map(|(idx, layer)| {
+            let preset_name = match self
+                .preset
+                .options()
+                .iter()
+                .find(|p| p.id() == layer.preset_id)
+            {
+                Some(preset) => preset.to_string(),
+                None => format!("{}: Unknown preset", layer.preset_id),
+            };
+            let blend_mode_pick_list =
+                pick_list(BlendMode::ALL, Some(layer.blend_mode), move |blend_mode| {
+                    Message::LayerEditor(LayerEditorMessage::BlendMode(idx, blend_mode))
+                });
+            let opacity_slider = SliderBuilder::new("Opacity:", layer.opacity)
+                .on_change(move |val| Message::LayerEditor(LayerEditorMessage::Opacity(idx, val)))
+                .on_release(|_| Message::LayerEditor(LayerEditorMessage::SubmitOpacity))
+                .build();
+            // Solid color preview of the layer color blended over the current preset color
+            let preview = color_swatch(
+                layer
+                    .blend_mode
+                    .blend(base_color, layer.preset_settings.color(), layer.opacity),
+                30,
+            );
+            let up_button =
+                button("Up").on_press(Message::LayerEditor(LayerEditorMessage::MoveUp(idx)));
+            let remove_button = button("Remove")
+                .style(button::danger)
+                .on_press(Message::LayerEditor(LayerEditorMessage::Remove(idx)));
+
+            column![
+                row![
+                    text(preset_name),
+                    horizontal_space(),
+                    blend_mode_pick_list,
+                    preview,
+                    up_button,
+                    remove_button
+                ]
+                .spacing(10)
+                .align_y(Center),
+                opacity_slider,
+            ]
+            .into()
+        }));
+
+        let add_button = button("Add Current Preset")
+            .on_press(Message::LayerEditor(LayerEditorMessage::AddCurrentPreset));
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::Layers)));
+        let error_message = match &self.layer_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    device_save_settings_button,
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                text!(
+                    "Layers are drawn over the current preset in order and previewed on the \
+                     device right away, save the settings to keep them."
+                ),
+                layer_list.spacing(10).padding(5),
+                row![add_button, refresh_button, error_message]
+                    .spacing(10)
+                    .padding(5)
+                    .align_y(Center),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn schedules_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Schedules").size(30);
+
+        // Local time of the device, used to show when the schedules run next
+        let device_now = self.device_time.as_ref().and_then(|(time, received_at)| {
+            let unix_ms = time.unix_ms? + received_at.elapsed().as_millis() as u64;
+            Some(((unix_ms / 1000) as i64, time.settings.utc_offset))
+        });
+
+        let schedule_list = column(self.schedules.iter().enumerate().map(|(idx, schedule)| {
+            let enabled_checkbox = checkbox("", schedule.enabled).on_toggle(move |enabled| {
+                Message::ScheduleEditor(ScheduleEditorMessage::Enabled(idx, enabled))
+            });
+            let next_run_text = match device_now {
+                Some((now, utc_offset)) if schedule.enabled => {
+                    match schedule.trigger().next_after(now, utc_offset) {
+                        Some(next) => format!("next: {}", DateTime::from_unix(next, utc_offset)),
+                        None => String::new(),
+                    }
+                }
+                _ => String::new(),
+            };
+            let remove_button = button("Remove")
+                .style(button::danger)
+                .on_press(Message::ScheduleEditor(ScheduleEditorMessage::Remove(idx)));
+
+            row![
+                enabled_checkbox,
+                text!("{:02}:{:02}", schedule.minute / 60, schedule.minute % 60),
+                text(schedule.days_text()),
+                text(self.schedule_action_text(schedule.action)),
+                horizontal_space(),
+                text(next_run_text),
+                remove_button
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into()
+        }));
+
+        let time_input = text_input("HH:MM", &self.schedule_time_text)
+            .on_input(|input| Message::ScheduleEditor(ScheduleEditorMessage::Time(input)))
+            .width(80);
+        let day_checkboxes = row((0..7).map(|weekday| {
+            checkbox(
+                Schedule::weekday_name(weekday),
+                self.schedule_days & (1 << weekday) != 0,
+            )
+            .on_toggle(move |is_selected| {
+                Message::ScheduleEditor(ScheduleEditorMessage::Day(weekday, is_selected))
+            })
+            .into()
+        }))
+        .spacing(10);
+        let action_pick_list = pick_list(
+            ScheduleActionKind::ALL,
+            Some(self.schedule_action),
+            |action| Message::ScheduleEditor(ScheduleEditorMessage::Action(action)),
+        );
+        let action_value: Element<'_, Message> = match self.schedule_action {
+            ScheduleActionKind::Preset => match &self.selected_preset {
+                Some(preset) => text!("{preset}").into(),
+                None => text!("Select a preset on the home page").into(),
+            },
+            ScheduleActionKind::Favorite => pick_list(
+                self.favorites.as_slice(),
+                self.schedule_favorite.as_ref(),
+                |favorite| Message::ScheduleEditor(ScheduleEditorMessage::Favorite(favorite)),
+            )
+            .into(),
+            ScheduleActionKind::Brightness => {
+                SliderBuilder::new("Brightness:", self.schedule_brightness)
+                    .on_change(|val| {
+                        Message::ScheduleEditor(ScheduleEditorMessage::Brightness(val))
+                    })
+                    .on_release(|val| {
+                        Message::ScheduleEditor(ScheduleEditorMessage::Brightness(val))
+                    })
+                    .build()
+            }
+            ScheduleActionKind::TurnOn | ScheduleActionKind::TurnOff => Space::new(0, 0).into(),
+        };
+        let add_button =
+            button("Add Schedule").on_press(Message::ScheduleEditor(ScheduleEditorMessage::Add));
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::Schedules)));
+        let error_message = match &self.schedule_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                text!(
+                    "Schedules are stored and run by the device in its local time, which is set \
+                     on the settings page."
+                ),
+                schedule_list.spacing(10).padding(5),
+                row![text!("Time:"), time_input, day_checkboxes]
+                    .spacing(10)
+                    .padding(5)
+                    .align_y(Center),
+                row![text!("Action:"), action_pick_list, action_value]
+                    .spacing(10)
+                    .padding(5)
+                    .align_y(Center),
+                row![add_button, refresh_button, error_message]
+                    .spacing(10)
+                    .padding(5)
+                    .align_y(Center),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn schedule_action_text(&self, action: ScheduleAction) -> String {
+        match action {
+            ScheduleAction::TurnOn => "Turn on".to_string(),
+            ScheduleAction::TurnOff => "Turn off".to_string(),
+            ScheduleAction::Preset(preset_id) => {
+                match self.preset.options().iter().find(|p| p.id() == preset_id) {
+                    Some(preset) => format!("Preset {preset}"),
+                    None => format!("Preset {preset_id}: Unknown preset"),
+                }
+            }
+            ScheduleAction::Favorite(favorite_id) => {
+                match self.favorites.iter().find(|f| f.id() == favorite_id) {
+                    Some(favorite) => format!("Favorite {}", favorite.name()),
+                    None => format!("Favorite {favorite_id}: Deleted favorite"),
+                }
+            }
+            ScheduleAction::Brightness(brightness) => format!("Brightness {brightness}"),
+        }
+    }
+
+    fn script_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Script").size(30);
+
+        let name_input = text_input("Name", &self.script_name)
+            .on_input(|input| Message::ScriptEditor(ScriptEditorMessage::Name(input)));
+        let editor = text_editor(&self.script_content)
+            .placeholder("palette(sin8(i * 8 + t / 16))")
+            .on_action(|action| Message::ScriptEditor(ScriptEditorMessage::Edit(action)));
+        let upload_button = button("Upload").on_press_maybe(
+            self.is_device_connected
+                .then_some(Message::ScriptEditor(ScriptEditorMessage::Upload)),
+        );
+        let device_script_text = match &self.device_script {
+            Some(script) => text!(
+                "Device script: {} ({} bytes)",
+                script.name,
+                script.code.len()
+            ),
+            None => text!(""),
+        };
+        let error_message = match &self.script_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                text!("{}", script::SCRIPT_HELP),
+                text!("Select the Script preset to show the uploaded script."),
+                column![text!("Name:"), name_input].padding(5),
+                editor.padding(5),
+                row![
+                    upload_button,
+                    device_script_text,
+                    horizontal_space(),
+                    error_message
+                ]
+                .spacing(10)
+                .padding(5)
+                .align_y(Center),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn clips_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Clips").size(30);
+
+        let clip_list = match &self.clip_list {
+            Some(clip_list) => column(clip_list.clips.iter().map(|clip| {
+                let play_button = match self.active_clip == Some(clip.id) {
+                    true => button("Playing").style(button::success),
+                    false => button("Play").on_press(Message::Request(Request::Set(
+                        SetRequest::PlayClip(clip.id),
+                    ))),
+                };
+                row![
+                    text!("{clip}"),
+                    horizontal_space(),
+                    play_button,
+                    button("Delete")
+                        .style(button::danger)
+                        .on_press(Message::Request(Request::Set(SetRequest::DeleteClip(
+                            clip.id
+                        )))),
+                ]
+                .spacing(10)
+                .align_y(Center)
+                .into()
+            })),
+            None => column![text!("Clips have not been loaded from the device")],
+        };
+        let stop_button = button("Stop").on_press_maybe(
+            self.active_clip
+                .and(self.selected_preset.as_ref())
+                .map(|preset| Message::Request(Request::Set(SetRequest::Preset(preset.id())))),
+        );
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::Clips)));
+
+        let path_input = text_input("GIF, PNG or directory of PNGs", &self.clip_path_text)
+            .on_input(|input| Message::ClipEditor(ClipEditorMessage::Path(input)));
+        let fps_input = text_input("Frame rate", &self.clip_fps_text)
+            .on_input(|input| Message::ClipEditor(ClipEditorMessage::Fps(input)))
+            .width(120);
+        let name_input = text_input("Name", &self.clip_name_text)
+            .on_input(|input| Message::ClipEditor(ClipEditorMessage::Name(input)))
+            .on_submit(Message::ClipEditor(ClipEditorMessage::Export));
+        let export_button = button("Export & Upload").on_press_maybe(
+            (self.is_device_connected && self.clip_list.is_some())
+                .then_some(Message::ClipEditor(ClipEditorMessage::Export)),
+        );
+        let upload_progress = match &self.clip_upload {
+            Some(upload) => text!("Uploaded {} of {} bytes", upload.offset, upload.data.len()),
+            None => text!(""),
+        };
+        let error_message = match &self.clip_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                clip_list.spacing(10).padding(5),
+                row![stop_button, refresh_button].spacing(10).padding(5),
+                text!("Export").size(24),
+                text!(
+                    "Colors are sampled along the middle row of every frame, the frame rate of \
+                     a GIF is used unless another one is entered."
+                ),
+                column![text!("File:"), path_input].padding(5),
+                row![fps_input, name_input].spacing(10).padding(5),
+                row![
+                    export_button,
+                    upload_progress,
+                    horizontal_space(),
+                    error_message
+                ]
+                .spacing(10)
+                .padding(5)
+                .align_y(Center),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn view_playlist_editor(&self) -> Element<'_, Message> {
+        let Some(playlist) = &self.edited_playlist else {
+            return text!("Select a playlist to edit it").into();
+        };
+
+        let section_title = text!("Playlist {}", playlist.id).size(24);
+        let name_input = text_input("Name", &playlist.name)
+            .on_input(|input| Message::PlaylistEditor(PlaylistEditorMessage::Name(input)));
+        let shuffle_checkbox = checkbox("Shuffle", playlist.shuffle)
+            .on_toggle(|shuffle| Message::PlaylistEditor(PlaylistEditorMessage::Shuffle(shuffle)));
+
+        let entries = column(playlist.entries.iter().enumerate().map(|(idx, entry)| {
+            let preset_name = match self
+                .preset
+                .options()
+                .iter()
+                .find(|p| p.id() == entry.preset_id)
+            {
+                Some(preset) => preset.to_string(),
+                None => format!("{}: Unknown preset", entry.preset_id),
+            };
+            let duration_input = text_input("Seconds", &self.playlist_duration_texts[idx])
+                .on_input(move |input| {
+                    Message::PlaylistEditor(PlaylistEditorMessage::EntryDuration(idx, input))
+                })
+                .width(80);
+            let up_button = button("Up").on_press(Message::PlaylistEditor(
+                PlaylistEditorMessage::MoveEntryUp(idx),
+            ));
+            let remove_button =
+                button("Remove")
+                    .style(button::danger)
+                    .on_press(Message::PlaylistEditor(PlaylistEditorMessage::RemoveEntry(
+                        idx,
+                    )));
+
+            row![
+                text(preset_name),
+                horizontal_space(),
+                duration_input,
+                text!("s"),
+                up_button,
+                remove_button
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into()
+        }));
+
+        let add_button = button("Add Current Preset").on_press(Message::PlaylistEditor(
+            PlaylistEditorMessage::AddCurrentPreset,
+        ));
+        let upload_button = button("Upload").on_press_maybe(
+            (self.is_device_connected && !playlist.entries.is_empty()).then(|| {
+                Message::Request(Request::Set(SetRequest::UploadPlaylist(playlist.clone())))
+            }),
+        );
+        let play_button = button("Play").on_press_maybe(
+            self.is_device_connected
+                .then(|| Message::Request(Request::Set(SetRequest::Playlist(playlist.id)))),
+        );
+        let delete_button = button("Delete").style(button::danger).on_press_maybe(
+            self.is_device_connected
+                .then(|| Message::Request(Request::Set(SetRequest::DeletePlaylist(playlist.id)))),
+        );
+        let error_message = match &self.playlist_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            row![section_title].padding(5),
+            column![text!("Name:"), name_input].padding(5),
+            row![shuffle_checkbox].padding(5),
+            entries.spacing(5).padding(5),
+            row![
+                add_button,
+                upload_button,
+                play_button,
+                delete_button,
+                horizontal_space(),
+                error_message.align_y(Bottom)
+            ]
+            .spacing(10)
+            .padding(5),
+        ]
+        .into()
+    }
+
+    fn view_palette_editor(&self) -> Element<'_, Message> {
+        let Some(palette) = &self.edited_palette else {
+            return text!("Select a palette to edit it").into();
+        };
+
+        let section_title = text!("Palette {}", palette.id).size(24);
+        let name_input = text_input("Name", &palette.name)
+            .on_input(|input| Message::PaletteEditor(PaletteEditorMessage::Name(input)));
+
+        let stops = column(palette.stops.iter().enumerate().map(|(idx, stop)| {
+            let position_slider = SliderBuilder::new("Position:", stop[0])
+                .on_change(move |val| {
+                    Message::PaletteEditor(PaletteEditorMessage::StopPosition(idx, val))
+                })
+                .on_release(move |val| {
+                    Message::PaletteEditor(PaletteEditorMessage::StopPosition(idx, val))
+                })
+                .build();
+            let hex_input = text_input("#RRGGBB", &self.palette_stop_hex_texts[idx])
+                .on_input(move |input| {
+                    Message::PaletteEditor(PaletteEditorMessage::StopHex(idx, input))
+                })
+                .on_submit(Message::PaletteEditor(PaletteEditorMessage::SubmitStopHex(
+                    idx,
+                )))
+                .width(100);
+            let remove_button =
+                button("Remove")
+                    .style(button::danger)
+                    .on_press(Message::PaletteEditor(PaletteEditorMessage::RemoveStop(
+                        idx,
+                    )));
+
+            row![
+                color_swatch([stop[1], stop[2], stop[3]], 30),
+                hex_input,
+                position_slider,
+                remove_button
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into()
+        }));
+
+        let preview = row((0..PALETTE_PREVIEW_STEPS).map(|step| {
+            let position = (step * 255 / (PALETTE_PREVIEW_STEPS - 1)) as u8;
+            let [r, g, b] = palette.color_at(position);
+            container(Space::new(iced::Length::Fill, 30))
+                .style(move |_| container::Style {
+                    background: Some(iced::Color::from_rgb8(r, g, b).into()),
+                    ..Default::default()
+                })
+                .into()
+        }));
+
+        let add_stop_button =
+            button("Add Stop").on_press(Message::PaletteEditor(PaletteEditorMessage::AddStop));
+        let upload_button =
+            button("Upload").on_press_maybe(self.is_device_connected.then(|| {
+                Message::Request(Request::Set(SetRequest::UploadPalette(palette.clone())))
+            }));
+        let delete_button =
+            button("Delete")
+                .style(button::danger)
+                .on_press_maybe(self.is_device_connected.then(|| {
+                    Message::Request(Request::Set(SetRequest::DeletePalette(palette.id)))
+                }));
+        let error_message = match &self.palette_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            row![section_title].padding(5),
+            column![text!("Name:"), name_input].padding(5),
+            row![preview].padding(5),
+            stops.spacing(5).padding(5),
+            row![
+                add_stop_button,
+                upload_button,
+                delete_button,
+                horizontal_space(),
+                error_message.align_y(Bottom)
+            ]
+            .spacing(10)
+            .padding(5),
+        ]
+        .into()
+    }
+
     fn detected_devices_button_style(
         theme: &Theme,
         status: widget::button::Status,
@@ -516,7 +2466,7 @@ impl App {
         }
     }
 
-    fn view_device_detector_settings(&self) -> Element<Message> {
+    fn view_device_detector_settings(&self) -> Element<'_, Message> {
         let devices = &self.detected_devices;
         let section_title = text!("Detect devices in network").size(24);
 
@@ -562,7 +2512,7 @@ impl App {
         .into()
     }
 
-    fn view_ip_port_settings(&self) -> Element<Message> {
+    fn view_ip_port_settings(&self) -> Element<'_, Message> {
         let settings = &self.config;
         let section_title = text!("Device IP/Port Settings").size(24);
         let ip_input = text_input("IP", &self.ip_text)
@@ -596,7 +2546,297 @@ impl App {
         .into()
     }
 
-    fn view_device_settings(&self) -> Element<Message> {
+    fn view_renderer_settings(&self) -> Element<'_, Message> {
+        let section_title = text!("Renderer Settings").size(24);
+        let frame_rate = self.frame_rate;
+        let frame_rate_slider = SliderBuilder::new("Frame rate:", frame_rate)
+            .range(1..=100)
+            .on_change(|val| Message::UI(UIMessage::FrameRate(val)))
+            .on_release(move |_| Message::Request(Request::Set(SetRequest::FrameRate(frame_rate))))
+            .build();
+
+        column![row![section_title].padding(5), frame_rate_slider].into()
+    }
+
+    fn view_layout_settings(&self) -> Element<'_, Message> {
+        let section_title = text!("Panel Layout").size(24);
+        let Some(layout) = &self.layout else {
+            return column![
+                row![section_title].padding(5),
+                text!("Layout has not been loaded from the device"),
+            ]
+            .into();
+        };
+
+        let width_input = text_input("Width", &self.layout_width_text)
+            .on_input(|input| Message::LayoutEditor(LayoutEditorMessage::Width(input)))
+            .width(80);
+        let height_input = text_input("Height", &self.layout_height_text)
+            .on_input(|input| Message::LayoutEditor(LayoutEditorMessage::Height(input)))
+            .width(80);
+        let serpentine_checkbox = checkbox("Serpentine", layout.serpentine)
+            .on_toggle(|value| Message::LayoutEditor(LayoutEditorMessage::Serpentine(value)));
+        let rotation_pick_list = pick_list(
+            LayoutRotation::ALL,
+            Some(LayoutRotation(layout.rotation)),
+            |rotation| Message::LayoutEditor(LayoutEditorMessage::Rotation(rotation)),
+        );
+        let flip_x_checkbox = checkbox("Flip horizontally", layout.flip_x)
+            .on_toggle(|value| Message::LayoutEditor(LayoutEditorMessage::FlipX(value)));
+        let flip_y_checkbox = checkbox("Flip vertically", layout.flip_y)
+            .on_toggle(|value| Message::LayoutEditor(LayoutEditorMessage::FlipY(value)));
+        let apply_button = button("Apply").on_press_maybe(
+            self.is_device_connected
+                .then_some(Message::LayoutEditor(LayoutEditorMessage::Apply)),
+        );
+        let error_message = match &self.layout_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            row![section_title].padding(5),
+            text!(
+                "Used by the 2-D presets, the first LED of the strip is in the top left corner \
+                 before the panel is rotated. Save the settings to keep the layout."
+            ),
+            row![
+                text!("Size:"),
+                width_input,
+                text!("x"),
+                height_input,
+                serpentine_checkbox
+            ]
+            .spacing(10)
+            .padding(5)
+            .align_y(Center),
+            row![
+                text!("Rotation:"),
+                rotation_pick_list,
+                flip_x_checkbox,
+                flip_y_checkbox
+            ]
+            .spacing(10)
+            .padding(5)
+            .align_y(Center),
+            row![apply_button, horizontal_space(), error_message]
+                .spacing(10)
+                .padding(5)
+                .align_y(Center),
+        ]
+        .into()
+    }
+
+    fn view_boot_settings(&self) -> Element<'_, Message> {
+        let section_title = text!("Power On").size(24);
+
+        let content: Element<'_, Message> = match &self.boot_settings {
+            Some(boot_settings) => {
+                let power_on_pick_list = pick_list(
+                    PowerOnBehavior::ALL,
+                    Some(boot_settings.power_on),
+                    |power_on| Message::Settings(SettingsMessage::PowerOn(power_on)),
+                );
+                let boot_animation_checkbox =
+                    checkbox("Play boot animation", boot_settings.boot_animation).on_toggle(
+                        |boot_animation| {
+                            Message::Settings(SettingsMessage::BootAnimation(boot_animation))
+                        },
+                    );
+                column![
+                    row![
+                        text!("After power on:"),
+                        power_on_pick_list,
+                        boot_animation_checkbox
+                    ]
+                    .spacing(10)
+                    .align_y(Center),
+                    text!(
+                        "The on/off state and the preset are saved automatically a few seconds \
+                         after they change."
+                    ),
+                ]
+                .spacing(10)
+                .into()
+            }
+            None => text!("Power on settings have not been loaded from the device").into(),
+        };
+
+        column![
+            row![section_title].padding(5),
+            container(content).padding(5)
+        ]
+        .into()
+    }
+
+    fn view_firmware_settings(&self) -> Element<'_, Message> {
+        let section_title = text!("Firmware").size(24);
+
+        let info_text = match &self.firmware_info {
+            Some(info) if info.pending_verify => text!(
+                "Version {} (slot {}), waiting for the firmware to confirm itself healthy",
+                info.version,
+                info.slot
+            ),
+            Some(info) => text!("Version {} (slot {})", info.version, info.slot),
+            None => text!("Firmware info has not been loaded from the device"),
+        };
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::FirmwareInfo)));
+
+        let path_input = text_input("Firmware image (.bin)", &self.firmware_path_text)
+            .on_input(|input| Message::UI(UIMessage::FirmwarePath(input)));
+        let update_button = button("Update Firmware").on_press_maybe(
+            (self.is_device_connected
+                && self.firmware_info.is_some()
+                && self.firmware_upload.is_none())
+            .then_some(Message::Settings(SettingsMessage::UpdateFirmware)),
+        );
+        let message = match (&self.firmware_upload, &self.firmware_message) {
+            (Some(upload), _) => text!("Uploaded {} of {} bytes", upload.offset, upload.data.len()),
+            (None, Some(msg)) => text!("{msg}").color(self.theme().palette().success),
+            (None, None) => text!(""),
+        };
+        let error_message = match &self.firmware_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            row![section_title].padding(5),
+            row![info_text, horizontal_space(), refresh_button]
+                .spacing(10)
+                .padding(5)
+                .align_y(Center),
+            text!(
+                "Images are written by `espflash save-image`. The device rolls back to the \
+                 current firmware if the new one does not connect to the network after it boots."
+            ),
+            row![path_input, update_button].spacing(10).padding(5),
+            row![message, horizontal_space(), error_message]
+                .spacing(10)
+                .padding(5),
+        ]
+        .spacing(10)
+        .padding(5)
+        .into()
+    }
+
+    fn view_restart_settings(&self) -> Element<'_, Message> {
+        let section_title = text!("Maintenance").size(24);
+
+        let controls = match self.restart_confirmation {
+            Some(restart) => row![
+                text!("{restart}?"),
+                button("Confirm")
+                    .on_press_maybe(
+                        self.is_device_connected
+                            .then_some(Message::Settings(SettingsMessage::ConfirmRestart)),
+                    )
+                    .style(button::danger),
+                button("Cancel").on_press(Message::Settings(SettingsMessage::CancelRestart)),
+            ],
+            None => row![
+                button("Reboot").on_press(Message::Settings(SettingsMessage::Restart(
+                    DeviceRestart::Reboot
+                ))),
+                button("Factory Reset")
+                    .on_press(Message::Settings(SettingsMessage::Restart(
+                        DeviceRestart::FactoryReset
+                    )))
+                    .style(button::danger),
+            ],
+        };
+        let message = match (&self.restart_message, &self.restart_error_message) {
+            (_, Some(msg)) => text!("{msg}").color(self.theme().palette().danger),
+            (Some(msg), None) => text!("{msg}").color(self.theme().palette().success),
+            (None, None) => text!(""),
+        };
+
+        column![
+            row![section_title].padding(5),
+            row![
+                controls.spacing(10).align_y(Center),
+                horizontal_space(),
+                message
+            ]
+            .spacing(10)
+            .padding(5)
+            .align_y(Center),
+            text!(
+                "Factory reset erases all of the settings, palettes, playlists, favorites and \
+                 schedules. Clips are kept. Holding the boot button for 5 seconds resets the \
+                 device as well."
+            ),
+        ]
+        .spacing(10)
+        .padding(5)
+        .into()
+    }
+
+    fn view_time_settings(&self) -> Element<'_, Message> {
+        let section_title = text!("Time").size(24);
+
+        let device_time_text = match &self.device_time {
+            Some((time, received_at)) => match time.unix_ms {
+                Some(unix_ms) => {
+                    let unix_ms = unix_ms + received_at.elapsed().as_millis() as u64;
+                    let date_time =
+                        DateTime::from_unix((unix_ms / 1000) as i64, time.settings.utc_offset);
+                    text!("Device time: {date_time} (source: {})", time.source)
+                }
+                None => text!("Device time has not been set"),
+            },
+            None => text!("Time has not been loaded from the device"),
+        };
+        let refresh_button =
+            button("Refresh").on_press(Message::Request(Request::Get(GetRequest::Time)));
+        let set_time_button = button("Set From This Computer").on_press_maybe(
+            self.is_device_connected
+                .then_some(Message::Settings(SettingsMessage::SetTimeFromComputer)),
+        );
+
+        let utc_offset_input = text_input("+00:00", &self.utc_offset_text)
+            .on_input(|input| Message::UI(UIMessage::UtcOffset(input)))
+            .width(100);
+        let ntp_server_input = text_input("NTP server, empty to disable", &self.ntp_server_text)
+            .on_input(|input| Message::UI(UIMessage::NtpServer(input)));
+        let save_button = button("Save").on_press_maybe(
+            self.is_device_connected
+                .then_some(Message::Settings(SettingsMessage::SaveTimeSettings)),
+        );
+        let error_message = match &self.time_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        column![
+            row![section_title].padding(5),
+            row![
+                device_time_text,
+                horizontal_space(),
+                refresh_button,
+                set_time_button
+            ]
+            .spacing(10)
+            .padding(5)
+            .align_y(Center),
+            row![
+                text!("UTC offset:"),
+                utc_offset_input,
+                text!("NTP server:"),
+                ntp_server_input,
+                save_button
+            ]
+            .spacing(10)
+            .padding(5)
+            .align_y(Center),
+            row![horizontal_space(), error_message].padding(5),
+        ]
+        .into()
+    }
+
+    fn view_device_settings(&self) -> Element<'_, Message> {
         let section_title = text!("Import/Export Settings").size(24);
         let editor = text_editor(&self.device_settings_content)
             .placeholder("Device settings")
@@ -628,38 +2868,298 @@ impl App {
         .into()
     }
 
-    fn slider_controls(&self) -> Element<Message> {
+    fn slider_controls(&self) -> Element<'_, Message> {
+        let params = match &self.selected_preset {
+            Some(preset) if !preset.params().is_empty() => preset.params().to_vec(),
+            _ => PresetParam::generic(),
+        };
+
+        column(params.iter().map(|param| self.param_control(param)))
+            .padding(5)
+            .into()
+    }
+
+    fn param_control(&self, param: &PresetParam) -> Element<'_, Message> {
+        let id = param.id();
+        let value = match id {
+            ParamId::Brightness => self.brightness,
+            ParamId::Speed => self.speed,
+            ParamId::Scale => self.scale,
+            ParamId::Color => return self.color_picker(param),
+            ParamId::Palette => return self.palette_picker(param),
+        };
+
+        SliderBuilder::new(&format!("{}:", param.label()), value)
+            .range(param.range())
+            .on_change(move |val| Message::UI(UIMessage::Param(id, val)))
+            .on_release(move |_| Message::Request(Request::Set(SetRequest::param(id, value))))
+            .build()
+    }
+
+    fn color_picker(&self, param: &PresetParam) -> Element<'_, Message> {
+        let color = self.color;
+        let [r, g, b] = color;
+
+        let swatch = color_swatch([r, g, b], 40);
+        let hex_input = text_input("#RRGGBB", &self.color_hex_text)
+            .on_input(|input| Message::UI(UIMessage::ColorHex(input)))
+            .on_submit(Message::UI(UIMessage::SubmitColorHex))
+            .width(100);
+        let error_message = match &self.color_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        let channel_slider = |label: &str, channel: usize| {
+            SliderBuilder::new(label, color[channel])
+                .on_change(move |val| {
+                    let mut color = color;
+                    color[channel] = val;
+                    Message::UI(UIMessage::Color(color))
+                })
+                .on_release(move |_| Message::Request(Request::Set(SetRequest::Color(color))))
+                .build()
+        };
+
+        column![
+            row![
+                text!("{}:", param.label()),
+                swatch,
+                hex_input,
+                error_message
+            ]
+            .padding(5)
+            .spacing(20)
+            .align_y(Center),
+            channel_slider("Red:", 0),
+            channel_slider("Green:", 1),
+            channel_slider("Blue:", 2),
+        ]
+        .into()
+    }
+
+    fn palette_picker(&self, param: &PresetParam) -> Element<'_, Message> {
+        let selected = self.palettes.iter().find(|p| p.id() == self.palette);
+        let picker = pick_list(self.palettes.as_slice(), selected, |palette| {
+            Message::Request(Request::Set(SetRequest::Palette(palette.id())))
+        })
+        .placeholder("No palettes received from device");
+
+        row![text!("{}:", param.label()), picker]
+            .padding(5)
+            .spacing(20)
+            .align_y(Center)
+            .into()
+    }
+
+    fn diagnostics_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Diagnostics").size(30);
+
+        let summary: Element<'_, Message> = match &self.stats {
+            Some(stats) => {
+                let rssi = match stats.rssi {
+                    Some(rssi) => format!("{rssi} dBm"),
+                    None => "not connected".to_string(),
+                };
+                row![
+                    column![
+                        text!("Uptime: {}", format_uptime(stats.uptime_s)),
+                        text!(
+                            "Heap: {} of {} KiB used",
+                            stats.heap_used / 1024,
+                            (stats.heap_used + stats.heap_free) / 1024
+                        ),
+                        text!("Wi-Fi signal: {rssi}"),
+                        text!("Wi-Fi reconnects: {}", stats.wifi_reconnects),
+                    ]
+                    .spacing(5),
+                    column![
+                        text!("Messages received: {}", stats.messages_received),
+                        text!("Protocol errors: {}", stats.protocol_errors),
+                        text!("Frame rate: {} FPS", stats.frame_rate),
+                        text!("Dropped frames: {}", stats.dropped_frames),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(40)
+                .into()
+            }
+            None => text!("No stats received from device").into(),
+        };
+
+        let last = self.stats.unwrap_or_default();
+        let graphs = column![
+            self.view_graph(
+                format!(
+                    "Frame time: {} us (render {} us, write {} us)",
+                    last.render_time_us + last.write_time_us,
+                    last.render_time_us,
+                    last.write_time_us
+                ),
+                |stats| (stats.render_time_us + stats.write_time_us) as f32,
+            ),
+            self.view_graph(
+                format!("Longest frame: {} us", last.max_frame_time_us),
+                |stats| stats.max_frame_time_us as f32,
+            ),
+            self.view_graph(
+                format!("Heap used: {} KiB", last.heap_used / 1024),
+                |stats| stats.heap_used as f32,
+            ),
+            // Signal strength is drawn above -100 dBm, which is about the weakest usable signal
+            self.view_graph(
+                format!(
+                    "Wi-Fi signal: {}",
+                    last.rssi
+                        .map(|rssi| format!("{rssi} dBm"))
+                        .unwrap_or_default()
+                ),
+                |stats| stats.rssi.map(|rssi| rssi as f32 + 100.0).unwrap_or(0.0),
+            ),
+            self.view_graph(
+                format!(
+                    "Power: {} mA{}",
+                    last.power_ma,
+                    if last.power_limited { " (limited)" } else { "" }
+                ),
+                |stats| stats.power_ma as f32,
+            ),
+        ]
+        .spacing(15);
+
+        scrollable(
+            column![
+                row![
+                    button("Back").on_press(Message::Page(Page::Home)),
+                    horizontal_space(),
+                    self.device_connection_state()
+                ]
+                .align_y(Center)
+                .spacing(10)
+                .padding(5),
+                row![page_title].padding(5),
+                container(summary).padding(5),
+                container(graphs).padding(5),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .into()
+    }
+
+    fn logs_page(&self) -> Element<'_, Message> {
+        let page_title = text!("Device Logs").size(30);
+
+        let level_pick_list = pick_list(
+            LogFilter::ALL,
+            self.log_settings.map(|settings| settings.level),
+            |level| Message::LogViewer(LogViewerMessage::Level(level)),
+        )
+        .placeholder("Not loaded");
+        let collector_input = text_input("Collector address:port", &self.log_collector_text)
+            .on_input(|input| Message::LogViewer(LogViewerMessage::Collector(input)))
+            .on_submit(Message::LogViewer(LogViewerMessage::SaveCollector))
+            .width(250);
+        let save_collector_button = button("Save").on_press_maybe(
+            (self.is_device_connected && self.log_settings.is_some())
+                .then_some(Message::LogViewer(LogViewerMessage::SaveCollector)),
+        );
+        let clear_button = button("Clear").on_press(Message::LogViewer(LogViewerMessage::Clear));
+        let error_message = match &self.log_error_message {
+            Some(msg) => text!("{msg}").color(self.theme().palette().danger),
+            None => text!(""),
+        };
+
+        let palette = self.theme().palette();
+        let entries = column(self.log_entries.iter().map(|entry| {
+            let color = match entry.level {
+                LogLevel::Error => palette.danger,
+                LogLevel::Warn => palette.primary,
+                LogLevel::Info => palette.text,
+                LogLevel::Debug | LogLevel::Trace => palette.text.scale_alpha(0.6),
+            };
+            text!(
+                "{:>10.3} {:<5} {}: {}",
+                entry.uptime_ms as f64 / 1000.0,
+                entry.level,
+                entry.target,
+                entry.message
+            )
+            .font(iced::Font::MONOSPACE)
+            .color(color)
+            .into()
+        }))
+        .spacing(2);
+
         column![
-            self.brightness_slider(),
-            self.speed_slider(),
-            self.scale_slider(),
+            row![
+                button("Back").on_press(Message::Page(Page::Home)),
+                horizontal_space(),
+                self.device_connection_state()
+            ]
+            .align_y(Center)
+            .spacing(10)
+            .padding(5),
+            row![page_title].padding(5),
+            row![
+                text!("Level:"),
+                level_pick_list,
+                text!("Collector:"),
+                collector_input,
+                save_collector_button,
+                clear_button,
+                horizontal_space(),
+                error_message
+            ]
+            .spacing(10)
+            .padding(5)
+            .align_y(Center),
+            text!(
+                "Records are streamed while this page is open. The collector receives them \
+                 whether or not the app is running, leave it empty to disable it."
+            ),
+            // Anchored to the bottom, so that it follows the newest records
+            scrollable(container(entries).padding(5))
+                .anchor_bottom()
+                .width(iced::Length::Fill)
+                .height(iced::Length::Fill),
         ]
-        .padding(5)
+        .spacing(10)
+        .padding(10)
         .into()
     }
 
-    fn brightness_slider(&self) -> Element<Message> {
-        let brightness = self.brightness;
-        SliderBuilder::new("Brightness:", brightness)
-            .on_change(|val| Message::UI(UIMessage::Brightness(val)))
-            .on_release(move |_| Message::Request(Request::Set(SetRequest::Brightness(brightness))))
-            .build()
-    }
-
-    fn speed_slider(&self) -> Element<Message> {
-        let speed = self.speed;
-        SliderBuilder::new("Speed:", self.speed)
-            .on_change(|val| Message::UI(UIMessage::Speed(val)))
-            .on_release(move |_| Message::Request(Request::Set(SetRequest::Speed(speed))))
-            .build()
+    /// Bar graph of the stats history, scaled to its largest sample
+    fn view_graph(&self, title: String, sample: fn(&DeviceStats) -> f32) -> Element<'_, Message> {
+        let samples: Vec<f32> = self.stats_history.iter().map(sample).collect();
+        let max = samples.iter().copied().fold(1.0, f32::max);
+        let color = self.theme().palette().primary;
+
+        let padding = STATS_HISTORY_LENGTH - samples.len();
+        let bars = row(std::iter::repeat_n(0.0, padding)
+            .chain(samples)
+            .map(|value| {
+                let height = (value.max(0.0) / max * GRAPH_HEIGHT).max(1.0);
+                container(Space::new(iced::Length::Fill, height))
+                    .style(move |_| container::Style {
+                        background: Some(color.into()),
+                        ..Default::default()
+                    })
+                    .into()
+            }))
+        .spacing(1)
+        .height(GRAPH_HEIGHT)
+        .align_y(Bottom);
+
+        column![text(title), bars].spacing(5).into()
     }
 
-    fn scale_slider(&self) -> Element<Message> {
-        let scale = self.scale;
-        SliderBuilder::new("Scale:", self.scale)
-            .on_change(|val| Message::UI(UIMessage::Scale(val)))
-            .on_release(move |_| Message::Request(Request::Set(SetRequest::Scale(scale))))
-            .build()
+    fn view_stats(&self) -> Element<'_, Message> {
+        let stats = match &self.stats {
+            Some(stats) => text!("{stats}"),
+            None => text!("No stats received from device"),
+        };
+        row![stats].padding(10).into()
     }
 
     fn device_connection_state(&self) -> Element<'_, Message> {
@@ -673,7 +3173,7 @@ impl App {
         }
     }
 
-    fn device_save_settings_button(&self) -> Element<Message> {
+    fn device_save_settings_button(&self) -> Element<'_, Message> {
         let message = match self.is_device_connected {
             true => Some(Message::Request(Request::Set(SetRequest::SaveSettings))),
             false => None,
@@ -689,13 +3189,33 @@ enum Page {
     #[default]
     Home,
     Settings,
+    Palettes,
+    Playlists,
+    Layers,
+    Script,
+    Clips,
+    Schedules,
+    Diagnostics,
+    Logs,
 }
 
 #[derive(Debug, Clone)]
 enum UIMessage {
-    Brightness(u8),
-    Speed(u8),
-    Scale(u8),
+    Param(ParamId, u8),
+    Color([u8; 3]),
+    ColorHex(String),
+    SubmitColorHex,
+    FavoriteName(String),
+    SaveFavorite,
+    FrameRate(u8),
+    UtcOffset(String),
+    NtpServer(String),
+    FirmwarePath(String),
+    SleepMinutes(String),
+    SunriseTime(String),
+    SunriseDuration(String),
+    StartSleepTimer,
+    SetSunrise,
     Ip(String),
     Port(String),
     Subnet(String),
@@ -713,6 +3233,157 @@ enum SettingsMessage {
     DetectDevice,
     DetectorOutput(Vec<Device>),
     SetDetectedDevice(Device),
+    SaveTimeSettings,
+    SetTimeFromComputer,
+    PowerOn(PowerOnBehavior),
+    BootAnimation(bool),
+    Restart(DeviceRestart),
+    ConfirmRestart,
+    CancelRestart,
+    UpdateFirmware,
+}
+
+#[derive(Debug, Clone)]
+enum PaletteEditorMessage {
+    New,
+    Name(String),
+    StopPosition(usize, u8),
+    StopHex(usize, String),
+    SubmitStopHex(usize),
+    AddStop,
+    RemoveStop(usize),
+}
+
+#[derive(Debug, Clone)]
+enum LayerEditorMessage {
+    AddCurrentPreset,
+    BlendMode(usize, BlendMode),
+    Opacity(usize, u8),
+    SubmitOpacity,
+    MoveUp(usize),
+    Remove(usize),
+}
+
+#[derive(Debug, Clone)]
+enum ScheduleEditorMessage {
+    Time(String),
+    Day(usize, bool),
+    Action(ScheduleActionKind),
+    Favorite(ItemSummary),
+    Brightness(u8),
+    Add,
+    Enabled(usize, bool),
+    Remove(usize),
+}
+
+#[derive(Debug, Clone)]
+enum LogViewerMessage {
+    Level(LogFilter),
+    Collector(String),
+    SaveCollector,
+    Clear,
+}
+
+/// Action of a new schedule, the value of the action is picked separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScheduleActionKind {
+    TurnOn,
+    TurnOff,
+    Preset,
+    Favorite,
+    Brightness,
+}
+
+impl ScheduleActionKind {
+    const ALL: [ScheduleActionKind; 5] = [
+        ScheduleActionKind::TurnOn,
+        ScheduleActionKind::TurnOff,
+        ScheduleActionKind::Preset,
+        ScheduleActionKind::Favorite,
+        ScheduleActionKind::Brightness,
+    ];
+}
+
+impl std::fmt::Display for ScheduleActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ScheduleActionKind::TurnOn => "Turn On",
+            ScheduleActionKind::TurnOff => "Turn Off",
+            ScheduleActionKind::Preset => "Current Preset",
+            ScheduleActionKind::Favorite => "Favorite",
+            ScheduleActionKind::Brightness => "Brightness",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ScriptEditorMessage {
+    Edit(text_editor::Action),
+    Name(String),
+    Upload,
+}
+
+#[derive(Debug, Clone)]
+enum ClipEditorMessage {
+    Path(String),
+    Fps(String),
+    Name(String),
+    Export,
+}
+
+#[derive(Debug, Clone)]
+enum LayoutEditorMessage {
+    Width(String),
+    Height(String),
+    Serpentine(bool),
+    Rotation(LayoutRotation),
+    FlipX(bool),
+    FlipY(bool),
+    Apply,
+}
+
+/// Clockwise rotation of the panel in quarter turns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LayoutRotation(u8);
+
+impl LayoutRotation {
+    const ALL: [LayoutRotation; 4] = [Self(0), Self(1), Self(2), Self(3)];
+}
+
+impl std::fmt::Display for LayoutRotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}°", self.0 as u16 * 90)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PlaylistEditorMessage {
+    New,
+    Name(String),
+    Shuffle(bool),
+    AddCurrentPreset,
+    EntryDuration(usize, String),
+    MoveEntryUp(usize),
+    RemoveEntry(usize),
+}
+
+/// Firmware image being uploaded to the device one chunk at a time
+#[derive(Debug)]
+struct FirmwareUpload {
+    data: Vec<u8>,
+    /// Offset of the next chunk
+    offset: usize,
+}
+
+/// Clip being uploaded to the device one chunk at a time
+#[derive(Debug)]
+struct ClipUpload {
+    id: ClipId,
+    name: String,
+    data: Vec<u8>,
+    /// Offset of the next chunk
+    offset: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -768,6 +3439,302 @@ impl std::fmt::Display for DetectorErrorMessage {
     }
 }
 
+#[derive(Debug, Clone)]
+enum ColorErrorMessage {
+    InvalidHex,
+}
+
+impl std::fmt::Display for ColorErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            ColorErrorMessage::InvalidHex => "Invalid hex color has been entered!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PaletteErrorMessage {
+    InvalidHex,
+    TooManyStops,
+    NoFreeSlot,
+}
+
+impl std::fmt::Display for PaletteErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            PaletteErrorMessage::InvalidHex => "Invalid hex color has been entered!",
+            PaletteErrorMessage::TooManyStops => "Palette cannot have any more stops!",
+            PaletteErrorMessage::NoFreeSlot => "Device cannot store any more palettes!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PlaylistErrorMessage {
+    InvalidDuration,
+    TooManyEntries,
+    NoPresetSelected,
+    NoFreeSlot,
+}
+
+impl std::fmt::Display for PlaylistErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            PlaylistErrorMessage::InvalidDuration => "Invalid duration has been entered!",
+            PlaylistErrorMessage::TooManyEntries => "Playlist cannot have any more entries!",
+            PlaylistErrorMessage::NoPresetSelected => "No preset is selected!",
+            PlaylistErrorMessage::NoFreeSlot => "Device cannot store any more playlists!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum FavoriteErrorMessage {
+    EmptyName,
+    NoFreeSlot,
+}
+
+impl std::fmt::Display for FavoriteErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            FavoriteErrorMessage::EmptyName => "Favorite name cannot be empty!",
+            FavoriteErrorMessage::NoFreeSlot => "Device cannot store any more favorites!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum LayerErrorMessage {
+    TooManyLayers,
+    NoPresetSelected,
+}
+
+impl std::fmt::Display for LayerErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            LayerErrorMessage::TooManyLayers => "Device cannot show any more layers!",
+            LayerErrorMessage::NoPresetSelected => "No preset is selected!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug)]
+enum ScriptErrorMessage {
+    Compile(Error),
+}
+
+impl std::fmt::Display for ScriptErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptErrorMessage::Compile(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ClipErrorMessage {
+    EmptyName,
+    InvalidFps,
+    NoFreeSlot,
+    TooLong,
+    UploadFailed,
+    Export(Error),
+}
+
+impl std::fmt::Display for ClipErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            ClipErrorMessage::EmptyName => "Clip name cannot be empty!",
+            ClipErrorMessage::InvalidFps => "Invalid frame rate has been entered!",
+            ClipErrorMessage::NoFreeSlot => "Device cannot store any more clips!",
+            ClipErrorMessage::TooLong => "Clip does not fit into the storage of the device!",
+            ClipErrorMessage::UploadFailed => "Device rejected the clip!",
+            ClipErrorMessage::Export(err) => return write!(f, "{err}"),
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum LayoutErrorMessage {
+    InvalidSize,
+}
+
+impl std::fmt::Display for LayoutErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            LayoutErrorMessage::InvalidSize => "Invalid panel size has been entered!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TimeErrorMessage {
+    InvalidUtcOffset,
+}
+
+impl std::fmt::Display for TimeErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            TimeErrorMessage::InvalidUtcOffset => "Invalid UTC offset has been entered!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ScheduleErrorMessage {
+    InvalidTime,
+    TooManySchedules,
+    NoPresetSelected,
+    NoFavoriteSelected,
+}
+
+impl std::fmt::Display for ScheduleErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            ScheduleErrorMessage::InvalidTime => "Invalid time has been entered!",
+            ScheduleErrorMessage::TooManySchedules => "Device cannot store any more schedules!",
+            ScheduleErrorMessage::NoPresetSelected => "No preset is selected!",
+            ScheduleErrorMessage::NoFavoriteSelected => "No favorite is selected!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum LogErrorMessage {
+    InvalidCollector,
+}
+
+impl std::fmt::Display for LogErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            LogErrorMessage::InvalidCollector => "Collector must be an IPv4 address and a port!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TimerErrorMessage {
+    InvalidSleepMinutes,
+    InvalidSunriseTime,
+    InvalidSunriseDuration,
+    DeviceTimeNotSet,
+}
+
+impl std::fmt::Display for TimerErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            TimerErrorMessage::InvalidSleepMinutes => "Sleep timer must last 1 to 255 minutes!",
+            TimerErrorMessage::InvalidSunriseTime => "Invalid sunrise time has been entered!",
+            TimerErrorMessage::InvalidSunriseDuration => "Sunrise must last 1 to 120 minutes!",
+            TimerErrorMessage::DeviceTimeNotSet => {
+                "Device time has to be set on the settings page first!"
+            }
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DeviceRestart {
+    Reboot,
+    FactoryReset,
+}
+
+impl std::fmt::Display for DeviceRestart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            DeviceRestart::Reboot => "Reboot the device",
+            DeviceRestart::FactoryReset => "Reset the device to factory settings",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum RestartMessage {
+    Rebooting,
+    FactoryReset,
+}
+
+impl From<DeviceRestart> for RestartMessage {
+    fn from(restart: DeviceRestart) -> Self {
+        match restart {
+            DeviceRestart::Reboot => RestartMessage::Rebooting,
+            DeviceRestart::FactoryReset => RestartMessage::FactoryReset,
+        }
+    }
+}
+
+impl std::fmt::Display for RestartMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            RestartMessage::Rebooting => "Device is rebooting!",
+            RestartMessage::FactoryReset => {
+                "Device has been reset, it connects to the default Wi-Fi network!"
+            }
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum FirmwareMessage {
+    Restarting,
+}
+
+impl std::fmt::Display for FirmwareMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            FirmwareMessage::Restarting => "Firmware uploaded, device is restarting!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug)]
+enum FirmwareErrorMessage {
+    Read(std::io::Error),
+    InvalidImage,
+    TooLarge,
+    UploadFailed,
+}
+
+impl std::fmt::Display for FirmwareErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            FirmwareErrorMessage::Read(err) => return write!(f, "{err}"),
+            FirmwareErrorMessage::InvalidImage => "File is not a firmware image!",
+            FirmwareErrorMessage::TooLarge => "Firmware does not fit into the device!",
+            FirmwareErrorMessage::UploadFailed => "Device rejected the firmware!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum RestartErrorMessage {
+    Rejected,
+}
+
+impl std::fmt::Display for RestartErrorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            RestartErrorMessage::Rejected => "Device rejected the request, try again!",
+        };
+        write!(f, "{msg}")
+    }
+}
+
 #[derive(Debug, Clone)]
 enum PresetInfoMessage {
     PresetInfoLoaded,
@@ -785,6 +3752,7 @@ impl std::fmt::Display for PresetInfoMessage {
 struct SliderBuilder {
     label: String,
     value: u8,
+    range: RangeInclusive<u8>,
     on_change: Box<dyn Fn(u8) -> Message>,
     on_release: Box<dyn Fn(u8) -> Message>,
 }
@@ -794,11 +3762,17 @@ impl SliderBuilder {
         Self {
             label: label.to_string(),
             value,
-            on_change: Box::new(|_| Message::UI(UIMessage::Brightness(0))),
+            range: 0..=255,
+            on_change: Box::new(|_| Message::UI(UIMessage::Param(ParamId::Brightness, 0))),
             on_release: Box::new(|_| Message::Request(Request::Set(SetRequest::Brightness(0)))),
         }
     }
 
+    fn range(mut self, range: RangeInclusive<u8>) -> Self {
+        self.range = range;
+        self
+    }
+
     fn on_change<F>(mut self, f: F) -> Self
     where
         F: Fn(u8) -> Message + 'static,
@@ -820,8 +3794,7 @@ impl SliderBuilder {
         let on_release = self.on_release;
         row![
             text(self.label),
-            slider(0..=255, self.value, move |val| on_change(val))
-                .on_release(on_release(self.value)),
+            slider(self.range, self.value, on_change).on_release(on_release(self.value)),
             text!("{}", self.value).width(40),
         ]
         .padding(5)
@@ -829,3 +3802,74 @@ impl SliderBuilder {
         .into()
     }
 }
+
+fn color_swatch(color: [u8; 3], size: u16) -> Element<'static, Message> {
+    let [r, g, b] = color;
+    container(Space::new(size, size))
+        .style(move |_| container::Style {
+            background: Some(iced::Color::from_rgb8(r, g, b).into()),
+            border: iced::Border {
+                radius: iced::border::radius(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+fn hex_color(color: &[u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", color[0], color[1], color[2])
+}
+
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Formats the time since boot as `[days d ]hh:mm:ss`
+fn format_uptime(seconds: u64) -> String {
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match days {
+        0 => time,
+        days => format!("{days} d {time}"),
+    }
+}
+
+fn format_utc_offset(offset: i16) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    format!("{sign}{:02}:{:02}", offset / 60, offset % 60)
+}
+
+/// Parses offsets formatted as `+HH:MM`, `-HH:MM` or `HH`
+fn parse_utc_offset(offset: &str) -> Option<i16> {
+    let offset = offset.trim();
+    let (sign, offset) = match offset.strip_prefix('-') {
+        Some(offset) => (-1, offset),
+        None => (1, offset.strip_prefix('+').unwrap_or(offset)),
+    };
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let (hours, minutes): (i16, i16) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+/// Parses times of the day formatted as `HH:MM` into minutes since midnight
+fn parse_time_of_day(time: &str) -> Option<u16> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u16, u16) = (hours.parse().ok()?, minutes.parse().ok()?);
+    let minute = hours.checked_mul(60)?.checked_add(minutes)?;
+    (minutes < 60 && minute < MINUTES_PER_DAY).then_some(minute)
+}
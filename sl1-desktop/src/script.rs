@@ -0,0 +1,403 @@
+//! Compiler of the effect scripts run by the script preset of the device.
+//!
+//! A script is a single expression that is evaluated for every pixel. It can read the pixel index
+//! `i`, the pixel count `n`, the time in milliseconds `t` and the `speed` and `scale` parameters
+//! of the preset, and call `sin8`, `noise`, `min`, `max` and `abs`. The value of the expression is
+//! looked up in the palette of the preset, unless the whole expression is an `rgb(r, g, b)` call.
+//! Wrapping the whole expression in `palette(x)` is allowed, but does not change its meaning.
+//! Integer operators `+ - * / % & | ^ << >> < > ==` and `c ? a : b` are supported.
+
+use sl1_protocol::script::{MAX_SCRIPT_LENGTH, Op, validate};
+
+use crate::{Error, Result};
+
+pub const SCRIPT_HELP: &str = "Variables: i, n, t, speed, scale. Functions: sin8(x), noise(x, y), \
+     min(a, b), max(a, b), abs(x). The result is a palette position, or a color when the whole \
+     script is rgb(r, g, b).";
+
+pub fn compile(source: &str) -> Result<Vec<u8>> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        depth: 0,
+        code: Vec::new(),
+    };
+
+    if parser.is_output_call("rgb") {
+        parser.position += 2;
+        parser.call_args("rgb", 3)?;
+        parser.emit(Op::Rgb);
+    } else if parser.is_output_call("palette") {
+        parser.position += 2;
+        parser.call_args("palette", 1)?;
+        parser.emit(Op::Palette);
+    } else {
+        parser.expression()?;
+        parser.emit(Op::Palette);
+    }
+
+    if let Some(token) = parser.peek() {
+        return Err(compile_error(format!("unexpected {token:?}")));
+    }
+    if parser.code.len() > MAX_SCRIPT_LENGTH {
+        return Err(compile_error(format!(
+            "script is {} bytes long, at most {MAX_SCRIPT_LENGTH} bytes fit on the device",
+            parser.code.len()
+        )));
+    }
+    validate(&parser.code).map_err(|err| compile_error(err.to_string()))?;
+
+    Ok(parser.code)
+}
+
+fn compile_error(message: String) -> Error {
+    Error::ScriptCompile(message)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i32),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "<<", ">>", "==", "+", "-", "*", "/", "%", "&", "|", "^", "<", ">", "?", ":", "(", ")", ",",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let number = rest[..len]
+                .parse()
+                .map_err(|_| compile_error(format!("invalid number {}", &rest[..len])))?;
+            tokens.push(Token::Number(number));
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..len].to_string()));
+            len
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| compile_error(format!("unexpected character '{c}'")))?;
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        };
+        rest = rest[len..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// Binary operators from the loosest to the tightest binding
+const BINARY_OPERATORS: &[&[(&str, Op)]] = &[
+    &[("<", Op::Lt), (">", Op::Gt), ("==", Op::Eq)],
+    &[("|", Op::Or)],
+    &[("^", Op::Xor)],
+    &[("&", Op::And)],
+    &[("<<", Op::Shl), (">>", Op::Shr)],
+    &[("+", Op::Add), ("-", Op::Sub)],
+    &[("*", Op::Mul), ("/", Op::Div), ("%", Op::Mod)],
+];
+
+/// Deepest nesting of parentheses, calls, conditions and negations, so that the recursive parser
+/// cannot overflow the stack of the app
+const MAX_NESTING_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// Nesting depth of the expression being parsed
+    depth: usize,
+    code: Vec<u8>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| compile_error("unexpected end of script".to_string()))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, symbol: &'static str) -> bool {
+        let matches = self.peek() == Some(&Token::Symbol(symbol));
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<()> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => Err(compile_error(format!("expected '{symbol}'"))),
+        }
+    }
+
+    fn is_output_call(&self, name: &str) -> bool {
+        self.peek() == Some(&Token::Ident(name.to_string()))
+            && self.tokens.get(self.position + 1) == Some(&Token::Symbol("("))
+    }
+
+    fn emit(&mut self, op: Op) {
+        self.code.push(op as u8);
+    }
+
+    /// Runs `parse` one nesting level deeper
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if self.depth == MAX_NESTING_DEPTH {
+            return Err(compile_error(format!(
+                "script is nested more than {MAX_NESTING_DEPTH} levels deep"
+            )));
+        }
+        self.depth += 1;
+        parse(self)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn expression(&mut self) -> Result<()> {
+        self.nested(|parser| {
+            parser.binary(0)?;
+            if parser.eat("?") {
+                parser.expression()?;
+                parser.expect(":")?;
+                parser.expression()?;
+                parser.emit(Op::Select);
+            }
+            Ok(())
+        })
+    }
+
+    fn binary(&mut self, level: usize) -> Result<()> {
+        let Some(operators) = BINARY_OPERATORS.get(level) else {
+            return self.unary();
+        };
+
+        self.binary(level + 1)?;
+        'outer: loop {
+            for (symbol, op) in *operators {
+                if self.eat(symbol) {
+                    self.binary(level + 1)?;
+                    self.emit(*op);
+                    continue 'outer;
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    fn unary(&mut self) -> Result<()> {
+        if self.eat("-") {
+            return self.nested(|parser| {
+                parser.unary()?;
+                parser.emit(Op::Neg);
+                Ok(())
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<()> {
+        match self.next()? {
+            Token::Number(number) => {
+                let immediate = i16::try_from(number)
+                    .map_err(|_| compile_error(format!("number {number} is too large")))?;
+                self.emit(Op::Push);
+                self.code.extend_from_slice(&immediate.to_le_bytes());
+            }
+            Token::Symbol("(") => {
+                self.expression()?;
+                self.expect(")")?;
+            }
+            Token::Ident(name) if self.eat("(") => {
+                let (op, arg_count) = match name.as_str() {
+                    "sin8" => (Op::Sin8, 1),
+                    "abs" => (Op::Abs, 1),
+                    "noise" => (Op::Noise, 2),
+                    "min" => (Op::Min, 2),
+                    "max" => (Op::Max, 2),
+                    "rgb" | "palette" => {
+                        return Err(compile_error(format!(
+                            "{name} can only be used for the whole script"
+                        )));
+                    }
+                    _ => return Err(compile_error(format!("unknown function {name}"))),
+                };
+                self.call_args(&name, arg_count)?;
+                self.emit(op);
+            }
+            Token::Ident(name) => {
+                let op = match name.as_str() {
+                    "i" => Op::Index,
+                    "n" => Op::Count,
+                    "t" => Op::Time,
+                    "speed" => Op::Speed,
+                    "scale" => Op::Scale,
+                    _ => return Err(compile_error(format!("unknown variable {name}"))),
+                };
+                self.emit(op);
+            }
+            token => return Err(compile_error(format!("unexpected {token:?}"))),
+        }
+        Ok(())
+    }
+
+    /// Compiles the arguments of a call whose opening parenthesis has already been consumed
+    fn call_args(&mut self, name: &str, count: usize) -> Result<()> {
+        let arg_count_error = || compile_error(format!("{name} takes {count} arguments"));
+        for idx in 0..count {
+            if idx > 0 && !self.eat(",") {
+                return Err(arg_count_error());
+            }
+            self.expression()?;
+        }
+        match self.eat(")") {
+            true => Ok(()),
+            false => Err(arg_count_error()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sl1_protocol::script::{Environment, Inputs, MAX_STACK_DEPTH, run, sin8};
+
+    use super::*;
+
+    /// Palette that gives back the position in the red channel
+    struct TestEnvironment;
+
+    impl Environment for TestEnvironment {
+        fn noise(&self, x: u16, y: u16) -> u8 {
+            (x ^ y) as u8
+        }
+
+        fn palette(&self, position: u8) -> [u8; 3] {
+            [position, 0, 0]
+        }
+    }
+
+    const INPUTS: Inputs = Inputs {
+        index: 3,
+        count: 79,
+        time: 1000,
+        speed: 128,
+        scale: 64,
+    };
+
+    fn evaluate(source: &str) -> [u8; 3] {
+        let code = compile(source).unwrap_or_else(|err| panic!("{source}: {err}"));
+        let mut budget = u32::MAX;
+        run(&code, &INPUTS, &TestEnvironment, &mut budget).unwrap()
+    }
+
+    fn assert_evaluates(source: &str, expected: i32) {
+        assert_eq!(evaluate(source), [expected as u8, 0, 0], "{source}");
+    }
+
+    fn assert_fails(source: &str, message: &str) {
+        match compile(source) {
+            Err(Error::ScriptCompile(err)) => assert!(err.contains(message), "{source}: {err}"),
+            result => panic!("{source}: expected an error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn operators_bind_by_precedence() {
+        assert_evaluates("1 + 2 * 3", 7);
+        assert_evaluates("(1 + 2) * 3", 9);
+        assert_evaluates("1 << 2 + 1", 8);
+        assert_evaluates("6 & 3 << 1", 6);
+        assert_evaluates("1 | 2 ^ 3 & 1", 3);
+        assert_evaluates("1 + 1 == 2", 1);
+        assert_evaluates("-2 * 3", -6);
+        assert_evaluates("7 % 4 * 2", 6);
+    }
+
+    #[test]
+    fn operators_are_left_associative() {
+        assert_evaluates("10 - 3 - 2", 5);
+        assert_evaluates("100 / 10 / 5", 2);
+        assert_evaluates("256 >> 2 >> 1", 32);
+    }
+
+    #[test]
+    fn conditions_nest_to_the_right() {
+        assert_evaluates("i > 2 ? 10 : 20", 10);
+        assert_evaluates("i < 2 ? 10 : i == 3 ? 30 : 40", 30);
+        assert_evaluates("i == 3 ? 1 + 1 : 0", 2);
+    }
+
+    #[test]
+    fn variables_and_functions() {
+        assert_evaluates("i + n", 82);
+        assert_evaluates("t / 100 + speed / 64 + scale / 64", 13);
+        assert_evaluates("sin8(64)", sin8(64) as i32);
+        assert_evaluates("noise(5, 3)", 6);
+        assert_evaluates("min(3, 9) + max(3, 9)", 12);
+        assert_evaluates("abs(-5)", 5);
+    }
+
+    #[test]
+    fn whole_script_outputs() {
+        assert_eq!(evaluate("rgb(1, 2, 3)"), [1, 2, 3]);
+        assert_eq!(evaluate("rgb(300, -5, i)"), [255, 0, 3]);
+        assert_evaluates("palette(i * 2)", 6);
+    }
+
+    #[test]
+    fn invalid_scripts_are_rejected() {
+        assert_fails("", "unexpected end");
+        assert_fails("1 2", "unexpected");
+        assert_fails("(1 + 2", "expected ')'");
+        assert_fails("1 $ 2", "unexpected character '$'");
+        assert_fails("40000", "too large");
+        assert_fails("foo", "unknown variable foo");
+        assert_fails("foo(1)", "unknown function foo");
+        assert_fails("min(1)", "min takes 2 arguments");
+        assert_fails("abs(1, 2)", "abs takes 1 arguments");
+        assert_fails(
+            "1 + rgb(1, 2, 3)",
+            "rgb can only be used for the whole script",
+        );
+        assert_fails("i ? 1", "expected ':'");
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_evaluates(&nested(MAX_NESTING_DEPTH - 1), 1);
+        assert_fails(&nested(MAX_NESTING_DEPTH), "nested");
+        assert_fails(&nested(100_000), "nested");
+        assert_fails(&format!("{}1", "-".repeat(100_000)), "nested");
+    }
+
+    #[test]
+    fn code_has_to_fit_on_the_device() {
+        let terms = vec!["1"; MAX_SCRIPT_LENGTH / 4 + 1];
+        assert_fails(&terms.join(" + "), "bytes long");
+
+        let depth = MAX_STACK_DEPTH + 1;
+        let deep = format!("{}1{}", "1 + (".repeat(depth), ")".repeat(depth));
+        assert_fails(&deep, "StackOverflow");
+    }
+}
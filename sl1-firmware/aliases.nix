@@ -24,11 +24,20 @@ in [
       --release --target riscv32imc-unknown-none-elf --no-default-features --features esp32c3
   '')
 
-  (alias "flash-esp32" ''espflash flash ./target/xtensa-esp32-none-elf/debug/sl1-firmware'')
-  (alias "flash-esp32c3" ''espflash flash ./target/riscv32imc-unknown-none-elf/debug/sl1-firmware'')
+  (alias "flash-esp32" ''espflash flash --partition-table ./partition-table.csv --erase-parts otadata ./target/xtensa-esp32-none-elf/debug/sl1-firmware'')
+  (alias "flash-esp32c3" ''espflash flash --partition-table ./partition-table.csv --erase-parts otadata ./target/riscv32imc-unknown-none-elf/debug/sl1-firmware'')
 
-  (alias "flash-release-esp32" ''espflash flash ./target/xtensa-esp32-none-elf/release/sl1-firmware'')
-  (alias "flash-release-esp32c3" ''espflash flash ./target/riscv32imc-unknown-none-elf/release/sl1-firmware'')
+  (alias "flash-release-esp32" ''espflash flash --partition-table ./partition-table.csv --erase-parts otadata ./target/xtensa-esp32-none-elf/release/sl1-firmware'')
+  (alias "flash-release-esp32c3" ''espflash flash --partition-table ./partition-table.csv --erase-parts otadata ./target/riscv32imc-unknown-none-elf/release/sl1-firmware'')
+
+  (alias "ota-image-esp32" ''
+    espflash save-image --chip esp32 --partition-table ./partition-table.csv \
+      ./target/xtensa-esp32-none-elf/release/sl1-firmware ./sl1-firmware-esp32.bin
+  '')
+  (alias "ota-image-esp32c3" ''
+    espflash save-image --chip esp32c3 --partition-table ./partition-table.csv \
+      ./target/riscv32imc-unknown-none-elf/release/sl1-firmware ./sl1-firmware-esp32c3.bin
+  '')
 
   (alias "erase-flash" ''espflash erase-flash'')
 
@@ -0,0 +1,111 @@
+use embassy_time::{Instant, Ticker};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{PresetId, Settings};
+use crate::storage::Persistent;
+use crate::{
+    BOOT_SETTINGS_STORAGE_OFFSET, BOOT_SETTINGS_VERSION, POWER_STATE_CHECK_INTERVAL,
+    POWER_STATE_SAVE_DELAY, POWER_STATE_STORAGE_OFFSET, POWER_STATE_VERSION, SETTINGS,
+};
+
+/// Whether the lights are on after the device is powered on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerOnBehavior {
+    On,
+    Off,
+    /// The lights are on if they were on when the device was powered off
+    #[default]
+    Restore,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BootSettings {
+    pub power_on: PowerOnBehavior,
+    /// Short animation played before the first frame is rendered
+    pub boot_animation: bool,
+}
+
+impl Persistent for BootSettings {
+    const STORAGE_OFFSET: u32 = BOOT_SETTINGS_STORAGE_OFFSET;
+    const VERSION: u32 = BOOT_SETTINGS_VERSION;
+}
+
+impl BootSettings {
+    /// Restores what was shown when the device was powered off, the settings are left as they
+    /// were saved if the state has never been saved
+    pub fn apply(&self, power_state: &PowerState, settings: &mut Settings) {
+        if power_state.is_saved {
+            if let Ok(preset_id) = PresetId::new_fallible(power_state.preset_id) {
+                settings.current_preset_id = preset_id;
+            }
+            settings.active_playlist = power_state.active_playlist;
+            settings.active_clip = power_state.active_clip;
+            settings.is_on = power_state.is_on;
+        }
+        match self.power_on {
+            PowerOnBehavior::On => settings.is_on = true,
+            PowerOnBehavior::Off => settings.is_on = false,
+            PowerOnBehavior::Restore => {}
+        }
+    }
+}
+
+/// Part of the settings saved automatically after it changes, so that it survives a power cut
+/// without the settings being saved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerState {
+    is_on: bool,
+    preset_id: u8,
+    active_playlist: Option<u8>,
+    active_clip: Option<u8>,
+    is_saved: bool,
+}
+
+impl Persistent for PowerState {
+    const STORAGE_OFFSET: u32 = POWER_STATE_STORAGE_OFFSET;
+    const VERSION: u32 = POWER_STATE_VERSION;
+}
+
+impl PowerState {
+    fn from_settings(settings: &Settings) -> Self {
+        Self {
+            is_on: settings.is_on,
+            preset_id: settings.current_preset_id.id(),
+            active_playlist: settings.active_playlist,
+            active_clip: settings.active_clip,
+            is_saved: true,
+        }
+    }
+}
+
+/// Saves the power state once it has not changed for a few seconds, so that flash is not
+/// written on every step of a slider or a toggle
+#[embassy_executor::task]
+pub async fn power_state_task(mut saved: PowerState) -> ! {
+    let mut ticker = Ticker::every(POWER_STATE_CHECK_INTERVAL);
+    let mut pending: Option<(PowerState, Instant)> = None;
+
+    loop {
+        ticker.next().await;
+
+        let current = PowerState::from_settings(&*SETTINGS.get().lock().await);
+        if current == saved {
+            pending = None;
+            continue;
+        }
+        match pending {
+            Some((state, changed_at)) if state == current => {
+                if changed_at.elapsed() < POWER_STATE_SAVE_DELAY {
+                    continue;
+                }
+                match current.save().await {
+                    Ok(()) => saved = current,
+                    Err(err) => log::error!("Error saving power state: {err}"),
+                }
+                pending = None;
+            }
+            _ => pending = Some((current, Instant::now())),
+        }
+    }
+}
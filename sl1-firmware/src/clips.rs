@@ -0,0 +1,142 @@
+use core::str::FromStr;
+
+use embedded_storage::{ReadStorage, Storage};
+use sl1_protocol::clip::{CLIP_HEADER_LENGTH, ClipHeader, FRAME_LENGTH_PREFIX, MAX_FRAME_LENGTH};
+
+use crate::{
+    CLIP_SLOT_SIZE, CLIPS_STORAGE_OFFSET, CLIPS_VERSION, Error, MAX_CLIPS, Result, STORAGE,
+};
+
+const NAME_LENGTH: usize = 16;
+/// Every clip slot starts with the layout version, the length of the clip and its name, the
+/// version is only written once the whole clip has been uploaded and checked
+const SLOT_HEADER_SIZE: usize = 4 + 4 + NAME_LENGTH;
+/// Largest clip that fits into a slot
+pub const MAX_CLIP_LENGTH: u32 = CLIP_SLOT_SIZE - SLOT_HEADER_SIZE as u32;
+
+/// Clip stored in one of the slots of the clips partition
+#[derive(Debug, Clone)]
+pub struct StoredClip {
+    pub name: heapless::String<NAME_LENGTH>,
+    pub length: u32,
+}
+
+fn slot_offset(id: u8) -> Result<u32> {
+    if id as usize >= MAX_CLIPS {
+        return Err(Error::ClipIdOutOfBounds);
+    }
+    Ok(CLIPS_STORAGE_OFFSET + id as u32 * CLIP_SLOT_SIZE)
+}
+
+/// Flash address of `offset` bytes into the clip stored in the slot
+fn clip_address(id: u8, offset: u32) -> Result<u32> {
+    (slot_offset(id)? + SLOT_HEADER_SIZE as u32)
+        .checked_add(offset)
+        .ok_or(Error::InvalidClip)
+}
+
+pub async fn get(id: u8) -> Result<Option<StoredClip>> {
+    let mut header = [0u8; SLOT_HEADER_SIZE];
+    STORAGE
+        .get()
+        .lock()
+        .await
+        .read(slot_offset(id)?, &mut header)
+        .map_err(Error::StorageRead)?;
+
+    if u32::from_le_bytes(header[..4].try_into().unwrap()) != CLIPS_VERSION {
+        return Ok(None);
+    }
+
+    let name_bytes = &header[8..];
+    let name_length = name_bytes
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(NAME_LENGTH);
+    let name = core::str::from_utf8(&name_bytes[..name_length]).unwrap_or_default();
+    Ok(Some(StoredClip {
+        name: heapless::String::from_str(name).unwrap_or_default(),
+        length: u32::from_le_bytes(header[4..8].try_into().unwrap()),
+    }))
+}
+
+/// Reads a part of the clip stored in the slot, `offset` is relative to the start of the clip
+pub async fn read(id: u8, offset: u32, buf: &mut [u8]) -> Result<()> {
+    STORAGE
+        .get()
+        .lock()
+        .await
+        .read(clip_address(id, offset)?, buf)
+        .map_err(Error::StorageRead)
+}
+
+/// Writes a part of an uploaded clip, the first chunk invalidates the clip previously stored in
+/// the slot
+pub async fn write_chunk(id: u8, offset: u32, data: &[u8]) -> Result<()> {
+    let end = offset
+        .checked_add(data.len() as u32)
+        .ok_or(Error::InvalidClip)?;
+    if end > MAX_CLIP_LENGTH {
+        return Err(Error::InvalidClip);
+    }
+    if offset == 0 {
+        delete(id).await?;
+    }
+    STORAGE
+        .get()
+        .lock()
+        .await
+        .write(clip_address(id, offset)?, data)
+        .map_err(Error::StorageWrite)
+}
+
+/// Checks the uploaded clip and marks the slot as holding a valid clip
+pub async fn finish(id: u8, name: &str, length: u32) -> Result<()> {
+    if length > MAX_CLIP_LENGTH {
+        return Err(Error::InvalidClip);
+    }
+
+    let mut header = [0u8; CLIP_HEADER_LENGTH];
+    read(id, 0, &mut header).await?;
+    let header = ClipHeader::from_bytes(&header).map_err(Error::Clip)?;
+
+    // Walk over the frame lengths, so that playback never reads past the end of the clip
+    let mut offset = CLIP_HEADER_LENGTH as u32;
+    for _ in 0..header.frame_count {
+        let mut prefix = [0u8; FRAME_LENGTH_PREFIX];
+        read(id, offset, &mut prefix).await?;
+        let frame_length = u16::from_le_bytes(prefix) as u32;
+        if frame_length == 0 || frame_length as usize > MAX_FRAME_LENGTH {
+            return Err(Error::InvalidClip);
+        }
+        offset += FRAME_LENGTH_PREFIX as u32 + frame_length;
+        if offset > length {
+            return Err(Error::InvalidClip);
+        }
+    }
+    if offset != length {
+        return Err(Error::InvalidClip);
+    }
+
+    let mut slot_header = [0u8; SLOT_HEADER_SIZE];
+    slot_header[..4].copy_from_slice(&CLIPS_VERSION.to_le_bytes());
+    slot_header[4..8].copy_from_slice(&length.to_le_bytes());
+    let name = &name.as_bytes()[..name.len().min(NAME_LENGTH)];
+    slot_header[8..8 + name.len()].copy_from_slice(name);
+
+    STORAGE
+        .get()
+        .lock()
+        .await
+        .write(slot_offset(id)?, &slot_header)
+        .map_err(Error::StorageWrite)
+}
+
+pub async fn delete(id: u8) -> Result<()> {
+    STORAGE
+        .get()
+        .lock()
+        .await
+        .write(slot_offset(id)?, &[0; 4])
+        .map_err(Error::StorageWrite)
+}
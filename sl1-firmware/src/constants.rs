@@ -1,14 +1,83 @@
 use embassy_time::Duration;
 
-pub const PRESET_COUNT: u8 = 4;
 pub const LED_COUNT: usize = 79;
 pub const LEDS_DATA_BUFFER_SIZE: usize = 12 * LED_COUNT + 40;
-pub const FRAME_TIME: Duration = Duration::from_millis(20);
+pub const DEFAULT_FRAME_RATE: u8 = 50;
+pub const MAX_FRAME_RATE: u8 = 100;
 pub const RANDOM_SEED: u64 = 0x0123_4567_89ab_cdef;
 pub const SERVER_PORT: u16 = 30462;
-pub const MESSAGE_BUFFER_LENGTH: usize = 1024;
+pub const MESSAGE_BUFFER_LENGTH: usize = sl1_protocol::MESSAGE_BUFFER_LENGTH;
 pub const MINIMAL_CLIENT_MESSAGE_LENGTH: usize = 2;
+/// Partition the bootloader reads the OTA slot to boot from, see `partition-table.csv`
+pub const OTA_DATA_OFFSET: u32 = 0xd000;
+pub const OTA_SLOT_OFFSETS: [u32; 2] = [0x10000, 0x190000];
+pub const OTA_SLOT_SIZE: u32 = 0x180000;
+/// Time the firmware has to stay connected to the network after an update to confirm itself
+/// healthy
+pub const OTA_CONFIRM_DELAY: Duration = Duration::from_secs(30);
+/// Start of the storage partition holding all of the settings, see `partition-table.csv`
+pub const STORAGE_PARTITION_OFFSET: u32 = 0x310000;
+pub const STORAGE_PARTITION_SIZE: u32 = 0x10000;
+pub const FLASH_SECTOR_SIZE: u32 = 0x1000;
 pub const SETTINGS_STORAGE_OFFSET: u32 = 0x310000;
-pub const PRESET_INFO: &str = r#"[{"id":0,"name":"Static Color"},{"id":1,"name":"Dynamic Color"},{"id":2,"name":"Running Rainbow"},{"id":3,"name":"Fire"}]"#;
+pub const SETTINGS_VERSION: u32 = 15;
+pub const PALETTES_STORAGE_OFFSET: u32 = 0x311000;
+pub const PALETTES_VERSION: u32 = 3;
+pub const MAX_PALETTES: usize = 16;
+pub const MAX_PALETTE_STOPS: usize = 16;
+pub const PLAYLISTS_STORAGE_OFFSET: u32 = 0x312000;
+pub const PLAYLISTS_VERSION: u32 = 2;
+pub const MAX_PLAYLISTS: usize = 8;
+pub const MAX_PLAYLIST_ENTRIES: usize = 16;
+pub const FAVORITES_STORAGE_OFFSET: u32 = 0x313000;
+pub const FAVORITES_VERSION: u32 = 2;
+pub const MAX_FAVORITES: usize = 16;
+pub const LAYERS_STORAGE_OFFSET: u32 = 0x314000;
+pub const LAYERS_VERSION: u32 = 2;
+pub const MAX_LAYERS: usize = 4;
+pub const SCRIPT_STORAGE_OFFSET: u32 = 0x315000;
+pub const SCRIPT_VERSION: u32 = 2;
+/// Instructions the script preset may execute per frame, pixels past the budget are black
+pub const SCRIPT_INSTRUCTION_BUDGET: u32 = 16384;
+/// Start of the clips partition, see `partition-table.csv`
+pub const CLIPS_STORAGE_OFFSET: u32 = 0x320000;
+pub const CLIPS_VERSION: u32 = 1;
+pub const MAX_CLIPS: usize = 4;
+pub const CLIP_SLOT_SIZE: u32 = 0x38000;
+pub const TIME_SETTINGS_STORAGE_OFFSET: u32 = 0x316000;
+pub const TIME_SETTINGS_VERSION: u32 = 2;
+pub const SNTP_SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
+pub const SNTP_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+pub const SNTP_TIMEOUT: Duration = Duration::from_secs(5);
+pub const SCHEDULES_STORAGE_OFFSET: u32 = 0x317000;
+pub const SCHEDULES_VERSION: u32 = 2;
+pub const MAX_SCHEDULES: usize = 16;
+pub const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+pub const TIMER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+pub const BOOT_SETTINGS_STORAGE_OFFSET: u32 = 0x318000;
+pub const BOOT_SETTINGS_VERSION: u32 = 2;
+pub const POWER_STATE_STORAGE_OFFSET: u32 = 0x319000;
+pub const POWER_STATE_VERSION: u32 = 2;
+pub const LOG_SETTINGS_STORAGE_OFFSET: u32 = 0x31a000;
+pub const LOG_SETTINGS_VERSION: u32 = 2;
+/// Log records that may wait to be sent over the network, newer records are dropped
+pub const LOG_QUEUE_LENGTH: usize = 16;
+pub const LOG_TARGET_LENGTH: usize = 32;
+pub const LOG_MESSAGE_LENGTH: usize = 192;
+pub const MAX_LOG_SUBSCRIBERS: usize = 4;
+pub const LOG_SUBSCRIPTION_LIFETIME: Duration =
+    Duration::from_secs(sl1_protocol::log::LOG_SUBSCRIPTION_LIFETIME_S);
+pub const POWER_STATE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Time the power state has to stay unchanged for before it is saved
+pub const POWER_STATE_SAVE_DELAY: Duration = Duration::from_secs(5);
+/// Time a client has to confirm a reboot or a factory reset with the token it was given
+pub const RESET_TOKEN_LIFETIME: Duration = Duration::from_secs(30);
+/// Time the boot button has to be held down for to reset the device to factory settings
+pub const FACTORY_RESET_HOLD_TIME: Duration = Duration::from_secs(5);
+/// Time left for the response to be sent before the device restarts
+pub const RESTART_DELAY: Duration = Duration::from_millis(200);
+/// Longest sunrise in minutes
+pub const MAX_SUNRISE_DURATION: u16 = 120;
+pub const LED_IDLE_CURRENT_MA: u32 = 1;
 pub const DEFAULT_WIFI_SSID: &str = env!("WIFI_SSID");
 pub const DEFAULT_WIFI_PASSWORD: &str = env!("WIFI_PASSWORD");
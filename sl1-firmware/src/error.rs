@@ -1,15 +1,46 @@
 #[derive(Debug)]
 pub enum Error {
     PresetIdOutOfBounds,
+    PaletteIdOutOfBounds,
+    PaletteNotFound,
+    InvalidPalette,
+    PlaylistIdOutOfBounds,
+    PlaylistNotFound,
+    InvalidPlaylist,
+    FavoriteIdOutOfBounds,
+    FavoriteNotFound,
+    ClipIdOutOfBounds,
+    ClipNotFound,
+    InvalidClip,
+    InvalidLayout,
+    InvalidUtcOffset,
+    InvalidSntpResponse,
+    SntpTimeout,
+    InvalidSchedule,
+    InvalidTimer,
+    ClockNotSet,
+    InvalidResetToken,
+    InvalidFirmwareUpdate,
+    InvalidFirmwareImage,
+    FirmwareUpdateNotStarted,
+    TooManyLogSubscribers,
     UnableToLockMutex,
     LedAdapterWrite,
     UnsupportedClientMessageMethod,
+    UnsupportedProtocolVersion,
+    InvalidMessageLength,
     ProtocolVersion(sl1_protocol::VersionError),
+    Script(sl1_protocol::script::ScriptError),
+    Clip(sl1_protocol::clip::ClipError),
     Serialization(serde_json::Error),
     Deserialization(serde_json::Error),
     SendError(embassy_net::udp::SendError),
+    RecvError(embassy_net::udp::RecvError),
+    DnsQuery(embassy_net::dns::Error),
     StorageWrite(esp_storage::FlashStorageError),
     StorageRead(esp_storage::FlashStorageError),
+    StorageSerialization(postcard::Error),
+    StorageTooLarge,
     Unspecified,
 }
 
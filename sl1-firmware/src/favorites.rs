@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{PresetId, PresetSettings, Settings};
+use crate::storage::Persistent;
+use crate::{Error, FAVORITES_STORAGE_OFFSET, FAVORITES_VERSION, MAX_FAVORITES, Result};
+
+/// Named look that can be recalled later, a preset along with all of its parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Favorite {
+    pub name: heapless::String<16>,
+    pub preset_id: PresetId,
+    pub preset_settings: PresetSettings,
+}
+
+impl Favorite {
+    /// Makes the favorite the current preset, replacing the parameters of its preset
+    pub fn recall(&self, settings: &mut Settings) -> Result<()> {
+        let preset_id = PresetId::new_fallible(self.preset_id.id())?;
+        settings.current_preset_id = preset_id;
+        settings.preset_settings[preset_id.id() as usize] = self.preset_settings;
+        settings.active_playlist = None;
+        settings.active_clip = None;
+        Ok(())
+    }
+}
+
+/// Favorites stored on the device, addressed by their slot index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Favorites {
+    favorites: [Option<Favorite>; MAX_FAVORITES],
+}
+
+impl Persistent for Favorites {
+    const STORAGE_OFFSET: u32 = FAVORITES_STORAGE_OFFSET;
+    const VERSION: u32 = FAVORITES_VERSION;
+}
+
+impl Favorites {
+    pub fn get(&self, id: u8) -> Option<&Favorite> {
+        self.favorites.get(id as usize)?.as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &Favorite)> {
+        self.favorites
+            .iter()
+            .enumerate()
+            .filter_map(|(id, favorite)| Some((id as u8, favorite.as_ref()?)))
+    }
+
+    pub fn insert(&mut self, id: u8, favorite: Favorite) -> Result<()> {
+        let slot = self
+            .favorites
+            .get_mut(id as usize)
+            .ok_or(Error::FavoriteIdOutOfBounds)?;
+        *slot = Some(favorite);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: u8) -> Result<()> {
+        self.favorites
+            .get_mut(id as usize)
+            .ok_or(Error::FavoriteIdOutOfBounds)?
+            .take()
+            .map(|_| ())
+            .ok_or(Error::FavoriteNotFound)
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{PresetId, PresetSettings};
+use crate::storage::Persistent;
+use crate::{LAYERS_STORAGE_OFFSET, LAYERS_VERSION, MAX_LAYERS, Result};
+
+/// How the colors of a layer are combined with the colors of the layers below it
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Add,
+    Multiply,
+    Screen,
+    Max,
+}
+
+impl BlendMode {
+    pub fn blend(self, below: u16, above: u16) -> u16 {
+        let (below, above) = (below as u32, above as u32);
+        let blended = match self {
+            BlendMode::Normal => above,
+            BlendMode::Add => (below + above).min(u16::MAX as u32),
+            BlendMode::Multiply => below * above / u16::MAX as u32,
+            BlendMode::Screen => {
+                u16::MAX as u32
+                    - (u16::MAX as u32 - below) * (u16::MAX as u32 - above) / u16::MAX as u32
+            }
+            BlendMode::Max => below.max(above),
+        };
+        blended as u16
+    }
+}
+
+/// Preset rendered on top of the current preset
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Layer {
+    #[serde(rename = "id")]
+    pub preset_id: PresetId,
+    #[serde(rename = "s")]
+    pub preset_settings: PresetSettings,
+    #[serde(rename = "m", default)]
+    pub blend_mode: BlendMode,
+    #[serde(rename = "o")]
+    pub opacity: u8,
+}
+
+/// Stack of layers composited over the current preset, from the bottom to the top
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Layers {
+    pub layers: heapless::Vec<Layer, MAX_LAYERS>,
+}
+
+impl Persistent for Layers {
+    const STORAGE_OFFSET: u32 = LAYERS_STORAGE_OFFSET;
+    const VERSION: u32 = LAYERS_VERSION;
+
+    fn is_valid(&self) -> bool {
+        self.layers
+            .iter()
+            .all(|layer| PresetId::new_fallible(layer.preset_id.id()).is_ok())
+    }
+}
+
+impl Layers {
+    /// Replaces the layer stack, checking that every layer refers to an existing preset
+    pub fn set(&mut self, layers: heapless::Vec<Layer, MAX_LAYERS>) -> Result<()> {
+        for layer in &layers {
+            PresetId::new_fallible(layer.preset_id.id())?;
+        }
+        self.layers = layers;
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, LED_COUNT, Result};
+
+/// Arrangement of the LEDs on a panel, maps the (x, y) coordinates used by 2-D presets to the
+/// index of the LED on the strip. The first LED of the strip is in the top left corner of the
+/// panel before it is rotated and flipped.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MatrixLayout {
+    #[serde(rename = "w")]
+    pub width: u8,
+    #[serde(rename = "h")]
+    pub height: u8,
+    /// Every other row is wired in the opposite direction
+    #[serde(rename = "serp")]
+    pub serpentine: bool,
+    /// Clockwise rotation of the panel in quarter turns
+    #[serde(rename = "rot")]
+    pub rotation: u8,
+    #[serde(rename = "fx")]
+    pub flip_x: bool,
+    #[serde(rename = "fy")]
+    pub flip_y: bool,
+}
+
+impl Default for MatrixLayout {
+    /// A single row, which makes 2-D presets look like their 1-D counterparts
+    fn default() -> Self {
+        Self {
+            width: LED_COUNT.min(u8::MAX as usize) as u8,
+            height: 1,
+            serpentine: false,
+            rotation: 0,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+}
+
+impl MatrixLayout {
+    pub fn validate(self) -> Result<Self> {
+        if self.width == 0 || self.height == 0 || self.rotation > 3 {
+            return Err(Error::InvalidLayout);
+        }
+        Ok(self)
+    }
+
+    /// Size of the panel as seen by presets, after it is rotated
+    pub fn dimensions(&self) -> (u8, u8) {
+        match self.rotation % 2 {
+            0 => (self.width, self.height),
+            _ => (self.height, self.width),
+        }
+    }
+
+    /// Index of the LED at the given coordinates, `None` if the panel is larger than the strip
+    pub fn index(&self, x: u8, y: u8) -> Option<usize> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (view_width, view_height) = self.dimensions();
+        let mut x = x as usize;
+        let mut y = y as usize;
+        if self.flip_x {
+            x = view_width as usize - 1 - x;
+        }
+        if self.flip_y {
+            y = view_height as usize - 1 - y;
+        }
+
+        let (column, row) = match self.rotation % 4 {
+            0 => (x, y),
+            1 => (y, height - 1 - x),
+            2 => (width - 1 - x, height - 1 - y),
+            _ => (width - 1 - y, x),
+        };
+        let column = match self.serpentine && row % 2 == 1 {
+            true => width - 1 - column,
+            false => column,
+        };
+
+        Some(row * width + column).filter(|&idx| idx < LED_COUNT)
+    }
+
+    /// Coordinates of every pixel of the panel along with the index of its LED
+    pub fn pixels(&self) -> impl Iterator<Item = (u8, u8, usize)> + '_ {
+        let (view_width, view_height) = self.dimensions();
+        (0..view_height).flat_map(move |y| {
+            (0..view_width).filter_map(move |x| self.index(x, y).map(|idx| (x, y, idx)))
+        })
+    }
+}
@@ -0,0 +1,199 @@
+use core::fmt::Write;
+
+use embassy_net::udp::UdpSocket;
+use embassy_net::{IpAddress, IpEndpoint};
+use embassy_time::Instant;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use sl1_protocol::log::{LogLevel, LogRecord};
+use sl1_protocol::{Method, Version};
+
+use crate::storage::Persistent;
+use crate::{
+    Error, LOG_MESSAGE_LENGTH, LOG_RECORDS, LOG_SETTINGS, LOG_SETTINGS_STORAGE_OFFSET,
+    LOG_SETTINGS_VERSION, LOG_SUBSCRIBERS, LOG_SUBSCRIPTION_LIFETIME, LOG_TARGET_LENGTH,
+    MAX_LOG_SUBSCRIBERS, Result,
+};
+
+/// Most verbose level that is logged, over USB as well as over the network
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFilter {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogFilter {
+    fn level_filter(self) -> LevelFilter {
+        match self {
+            LogFilter::Off => LevelFilter::Off,
+            LogFilter::Error => LevelFilter::Error,
+            LogFilter::Warn => LevelFilter::Warn,
+            LogFilter::Info => LevelFilter::Info,
+            LogFilter::Debug => LevelFilter::Debug,
+            LogFilter::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Host every log record is sent to, whether or not a client has subscribed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LogCollector {
+    pub address: [u8; 4],
+    pub port: u16,
+}
+
+impl LogCollector {
+    fn endpoint(&self) -> IpEndpoint {
+        let [a, b, c, d] = self.address;
+        IpEndpoint::new(IpAddress::v4(a, b, c, d), self.port)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LogSettings {
+    pub level: LogFilter,
+    pub collector: Option<LogCollector>,
+}
+
+impl Persistent for LogSettings {
+    const STORAGE_OFFSET: u32 = LOG_SETTINGS_STORAGE_OFFSET;
+    const VERSION: u32 = LOG_SETTINGS_VERSION;
+}
+
+impl LogSettings {
+    pub fn apply(&self) {
+        // The level is a single word, so it cannot be torn on the single core the firmware runs on
+        unsafe { log::set_max_level_racy(self.level.level_filter()) };
+    }
+}
+
+/// Clients that have asked for the log records to be streamed to them
+#[derive(Debug, Default)]
+pub struct LogSubscribers {
+    subscribers: heapless::Vec<(IpEndpoint, Instant), MAX_LOG_SUBSCRIBERS>,
+}
+
+impl LogSubscribers {
+    /// Subscribes the client or renews its subscription
+    pub fn subscribe(&mut self, endpoint: IpEndpoint) -> Result<()> {
+        self.remove_expired();
+        let expires_at = Instant::now() + LOG_SUBSCRIPTION_LIFETIME;
+        match self.subscribers.iter_mut().find(|(e, _)| *e == endpoint) {
+            Some((_, subscriber_expires_at)) => *subscriber_expires_at = expires_at,
+            None => self
+                .subscribers
+                .push((endpoint, expires_at))
+                .map_err(|_| Error::TooManyLogSubscribers)?,
+        }
+        Ok(())
+    }
+
+    fn remove_expired(&mut self) {
+        let now = Instant::now();
+        self.subscribers.retain(|(_, expires_at)| *expires_at > now);
+    }
+}
+
+/// Log record waiting for the server task to send it
+pub struct QueuedRecord {
+    level: Level,
+    uptime_ms: u64,
+    target: heapless::String<LOG_TARGET_LENGTH>,
+    message: heapless::String<LOG_MESSAGE_LENGTH>,
+}
+
+impl QueuedRecord {
+    fn new(record: &Record) -> Self {
+        let mut target = heapless::String::new();
+        for c in record.target().chars() {
+            if target.push(c).is_err() {
+                break;
+            }
+        }
+        // Messages that do not fit are cut short
+        let mut message = heapless::String::new();
+        let _ = write!(message, "{}", record.args());
+        Self {
+            level: record.level(),
+            uptime_ms: Instant::now().as_millis(),
+            target,
+            message,
+        }
+    }
+
+    /// Sends the record to the collector and to the subscribed clients. Errors are not logged, as
+    /// they would be queued to be sent again
+    pub async fn send(&self, socket: &mut UdpSocket<'_>, buf: &mut [u8]) {
+        let collector = LOG_SETTINGS.get().lock().await.collector;
+        let mut subscribers = LOG_SUBSCRIBERS.get().lock().await;
+        subscribers.remove_expired();
+        if collector.is_none() && subscribers.subscribers.is_empty() {
+            return;
+        }
+
+        let level = match self.level {
+            Level::Error => LogLevel::Error,
+            Level::Warn => LogLevel::Warn,
+            Level::Info => LogLevel::Info,
+            Level::Debug => LogLevel::Debug,
+            Level::Trace => LogLevel::Trace,
+        };
+        let record = LogRecord {
+            level,
+            uptime_ms: self.uptime_ms,
+            target: &self.target,
+            message: &self.message,
+        };
+        buf[0] = Version::V1 as u8;
+        buf[1] = Method::LogRecord as u8;
+        let message_len = 2 + record.to_bytes(&mut buf[2..]);
+
+        let endpoints = subscribers
+            .subscribers
+            .iter()
+            .map(|(endpoint, _)| *endpoint)
+            .chain(collector.map(|collector| collector.endpoint()));
+        for endpoint in endpoints {
+            let _ = socket.send_to(&buf[..message_len], endpoint).await;
+        }
+    }
+}
+
+/// Prints the records over USB like the `esp-println` logger and queues them to be sent over the
+/// network
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        esp_println::println!("{} - {}", record.level(), record.args());
+        // Records are dropped while the queue is full, logging must never block
+        let _ = LOG_RECORDS.try_send(QueuedRecord::new(record));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+
+/// Logs at the default level until the saved log settings are applied
+pub fn init() {
+    // Targets without atomic compare-and-swap can only set the logger racily, which is fine as
+    // long as it is done before anything else is running
+    unsafe {
+        log::set_logger_racy(&LOGGER).unwrap();
+        log::set_max_level_racy(LogFilter::default().level_filter());
+    }
+}
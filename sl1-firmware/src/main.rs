@@ -1,33 +1,68 @@
 #![no_std]
 #![no_main]
 
+mod boot;
+mod clips;
 mod constants;
 mod error;
+mod favorites;
+mod layers;
+mod layout;
+mod logger;
+mod ota;
+mod palettes;
+mod playlists;
 mod presets;
+mod reset;
+mod schedules;
+mod scripts;
 mod server;
 mod settings;
+mod sntp;
+mod stats;
+mod storage;
+mod time;
+mod timers;
 mod types;
 mod wifi;
 
-use core::str::FromStr;
 use core::sync::atomic::AtomicBool;
 
 use embassy_executor::Spawner;
 use embassy_net::StackResources;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
 use embassy_sync::lazy_lock::LazyLock;
+use embassy_sync::once_lock::OnceLock;
+use embassy_sync::signal::Signal;
 use esp_backtrace as _;
 use esp_hal::clock::CpuClock;
 use esp_hal::dma::{DmaRxBuf, DmaTxBuf};
 use esp_hal::dma_descriptors;
+use esp_hal::gpio::{Input, Pull};
+use esp_hal::rng::Rng;
 use esp_hal::spi::master::{Config, Spi, SpiDmaBus};
 use esp_hal::time::RateExtU32;
 use esp_storage::FlashStorage;
 use esp_wifi::EspWifiController;
-use esp_wifi::wifi::{ClientConfiguration, WifiDevice, WifiStaDevice, new_with_config};
-use settings::init_settings_storage;
+use esp_wifi::wifi::{WifiDevice, WifiStaDevice, new_with_config};
 use static_cell::StaticCell;
 
-use crate::settings::Settings;
+use crate::boot::{BootSettings, PowerState};
+use crate::favorites::Favorites;
+use crate::layers::Layers;
+use crate::logger::{LogSettings, LogSubscribers, QueuedRecord};
+use crate::ota::FirmwareUpdate;
+use crate::palettes::Palettes;
+use crate::playlists::Playlists;
+use crate::reset::ResetToken;
+use crate::schedules::Schedules;
+use crate::scripts::Script;
+use crate::settings::{Settings, WifiSettings};
+use crate::stats::Stats;
+use crate::storage::Persistent;
+use crate::time::{Clock, TimeSettings};
+use crate::timers::Timers;
 
 pub use crate::constants::*;
 pub use crate::error::{Error, Result};
@@ -39,12 +74,42 @@ static SHOULD_UPDATE: AtomicBool = AtomicBool::new(true);
 static STORAGE: LazyLock<Mutex<FlashStorage>> =
     LazyLock::new(|| Mutex::new(FlashStorage::default()));
 static SETTINGS: LazyLock<Mutex<Settings>> = LazyLock::new(|| Mutex::new(Settings::default()));
+static PALETTES: LazyLock<Mutex<Palettes>> = LazyLock::new(|| Mutex::new(Palettes::default()));
+static FAVORITES: LazyLock<Mutex<Favorites>> = LazyLock::new(|| Mutex::new(Favorites::default()));
+static LAYERS: LazyLock<Mutex<Layers>> = LazyLock::new(|| Mutex::new(Layers::default()));
+static SCRIPT: LazyLock<Mutex<Script>> = LazyLock::new(|| Mutex::new(Script::default()));
+static PLAYLISTS: LazyLock<Mutex<Playlists>> = LazyLock::new(|| Mutex::new(Playlists::default()));
+static STATS: LazyLock<Mutex<Stats>> = LazyLock::new(|| Mutex::new(Stats::default()));
+static CLOCK: LazyLock<Mutex<Clock>> = LazyLock::new(|| Mutex::new(Clock::default()));
+static TIME_SETTINGS: LazyLock<Mutex<TimeSettings>> =
+    LazyLock::new(|| Mutex::new(TimeSettings::default()));
+static SCHEDULES: LazyLock<Mutex<Schedules>> = LazyLock::new(|| Mutex::new(Schedules::default()));
+static TIMERS: LazyLock<Mutex<Timers>> = LazyLock::new(|| Mutex::new(Timers::default()));
+static BOOT_SETTINGS: LazyLock<Mutex<BootSettings>> =
+    LazyLock::new(|| Mutex::new(BootSettings::default()));
+static FIRMWARE_UPDATE: LazyLock<Mutex<Option<FirmwareUpdate>>> =
+    LazyLock::new(|| Mutex::new(None));
+static RESET_TOKEN: LazyLock<Mutex<Option<ResetToken>>> = LazyLock::new(|| Mutex::new(None));
+static LOG_SETTINGS: LazyLock<Mutex<LogSettings>> =
+    LazyLock::new(|| Mutex::new(LogSettings::default()));
+static LOG_SUBSCRIBERS: LazyLock<Mutex<LogSubscribers>> =
+    LazyLock::new(|| Mutex::new(LogSubscribers::default()));
+/// Log records waiting for the server task to send them over the network
+static LOG_RECORDS: Channel<CriticalSectionRawMutex, QueuedRecord, LOG_QUEUE_LENGTH> =
+    Channel::new();
+/// Hardware random number generator, shared with the Wi-Fi driver
+static RNG: OnceLock<Rng> = OnceLock::new();
+/// Sends the saved Wi-Fi settings to the Wi-Fi task, which reconnects if the credentials have
+/// changed
+static UPDATE_WIFI: Signal<CriticalSectionRawMutex, WifiSettings> = Signal::new();
+/// Wakes up the SNTP task after the time settings have been changed
+static SYNC_TIME: Signal<CriticalSectionRawMutex, ()> = Signal::new();
 
 #[esp_hal_embassy::main]
 async fn main(spawner: Spawner) -> ! {
     esp_alloc::heap_allocator!(128 * 1024);
 
-    esp_println::logger::init_logger_from_env();
+    crate::logger::init();
 
     let peripherals_config = esp_hal::Config::default().with_cpu_clock(CpuClock::max());
     let peripherals = esp_hal::init(peripherals_config);
@@ -59,38 +124,55 @@ async fn main(spawner: Spawner) -> ! {
     #[cfg(feature = "esp32c3")]
     esp_hal_embassy::init(timgsys.alarm0);
 
-    init_settings_storage().await.unwrap();
+    let log_settings = LogSettings::load().await.unwrap();
+    log_settings.apply();
+    *LOG_SETTINGS.get().lock().await = log_settings;
+
+    let is_firmware_unconfirmed = ota::check_boot().await.unwrap_or_else(|err| {
+        log::error!("Error checking firmware update: {err}");
+        false
+    });
+
     *SETTINGS.get().lock().await = Settings::load().await.unwrap();
+    *PALETTES.get().lock().await = Palettes::load().await.unwrap();
+    *PLAYLISTS.get().lock().await = Playlists::load().await.unwrap();
+    *FAVORITES.get().lock().await = Favorites::load().await.unwrap();
+    *LAYERS.get().lock().await = Layers::load().await.unwrap();
+    *SCRIPT.get().lock().await = Script::load().await.unwrap();
+    *TIME_SETTINGS.get().lock().await = TimeSettings::load().await.unwrap();
+    *SCHEDULES.get().lock().await = Schedules::load().await.unwrap();
+    *BOOT_SETTINGS.get().lock().await = BootSettings::load().await.unwrap();
+
+    let power_state = PowerState::load().await.unwrap();
+    BOOT_SETTINGS
+        .get()
+        .lock()
+        .await
+        .apply(&power_state, &mut *SETTINGS.get().lock().await);
 
     log::info!("Settings: {:?}", SETTINGS.get().lock().await);
 
+    let rng = Rng::new(peripherals.RNG);
+    let _ = RNG.init(rng);
+
     let timg1 = esp_hal::timer::timg::TimerGroup::new(peripherals.TIMG1);
     static ESP_WIFI_CONTROLLER: StaticCell<EspWifiController<'static>> = StaticCell::new();
-    let wifi_controller = ESP_WIFI_CONTROLLER.init(
-        esp_wifi::init(
-            timg1.timer0,
-            esp_hal::rng::Rng::new(peripherals.RNG),
-            peripherals.RADIO_CLK,
-        )
-        .unwrap(),
-    );
+    let wifi_controller =
+        ESP_WIFI_CONTROLLER.init(esp_wifi::init(timg1.timer0, rng, peripherals.RADIO_CLK).unwrap());
 
-    let sta_config = ClientConfiguration {
-        ssid: heapless::String::from_str(DEFAULT_WIFI_SSID).unwrap(),
-        password: heapless::String::from_str(DEFAULT_WIFI_PASSWORD).unwrap(),
-        ..Default::default()
-    };
+    let wifi_settings = SETTINGS.get().lock().await.wifi_settings.clone();
+    let sta_config = wifi_settings.client_configuration();
 
     let (device, controller): (WifiDevice<'_, WifiStaDevice>, _) =
         new_with_config(wifi_controller, peripherals.WIFI, sta_config).unwrap();
 
     let dhcp_config = embassy_net::DhcpConfig::default();
     let net_config = embassy_net::Config::dhcpv4(dhcp_config);
-    static RESOURCES: StaticCell<StackResources<4>> = StaticCell::new();
+    static RESOURCES: StaticCell<StackResources<6>> = StaticCell::new();
     let (stack, runner) = embassy_net::new(
         device,
         net_config,
-        RESOURCES.init(StackResources::<4>::new()),
+        RESOURCES.init(StackResources::<6>::new()),
         RANDOM_SEED,
     );
 
@@ -121,9 +203,28 @@ async fn main(spawner: Spawner) -> ! {
     let spi_dma_bus = SpiDmaBus::new(spi_dma, dma_rx_buf, dma_tx_buf);
     let leds = ws2812_spi::prerendered::Ws2812::new(spi_dma_bus, led_buf);
 
-    spawner.spawn(crate::wifi::wifi_task(controller)).unwrap();
+    #[cfg(feature = "esp32")]
+    let boot_button = Input::new(peripherals.GPIO0, Pull::Up);
+    #[cfg(feature = "esp32c3")]
+    let boot_button = Input::new(peripherals.GPIO9, Pull::Up);
+
+    spawner
+        .spawn(crate::wifi::wifi_task(controller, wifi_settings))
+        .unwrap();
     spawner.spawn(crate::server::net_task(runner)).unwrap();
     spawner.spawn(crate::server::server_task(stack)).unwrap();
+    spawner.spawn(crate::sntp::sntp_task(stack)).unwrap();
+    spawner.spawn(crate::schedules::schedule_task()).unwrap();
+    spawner.spawn(crate::timers::timer_task()).unwrap();
+    spawner
+        .spawn(crate::boot::power_state_task(power_state))
+        .unwrap();
+    spawner
+        .spawn(crate::reset::reset_button_task(boot_button))
+        .unwrap();
+    if is_firmware_unconfirmed {
+        spawner.spawn(crate::ota::confirm_task(stack)).unwrap();
+    }
 
     crate::presets::run_renderer(leds).await;
 }
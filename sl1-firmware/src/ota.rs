@@ -0,0 +1,268 @@
+use alloc::vec;
+
+use embassy_net::Stack;
+use embassy_time::Timer;
+use embedded_storage::{ReadStorage, Storage};
+use esp_hal::reset::software_reset;
+use esp_hal::rom::crc::crc32_le;
+use serde::{Deserialize, Serialize};
+use sl1_protocol::ota::{FIRMWARE_IMAGE_MAGIC, SHA256_LENGTH, Sha256};
+
+use crate::{
+    Error, FLASH_SECTOR_SIZE, OTA_CONFIRM_DELAY, OTA_DATA_OFFSET, OTA_SLOT_OFFSETS, OTA_SLOT_SIZE,
+    Result, STORAGE, storage,
+};
+
+/// States of an OTA slot, the same as the ones used by the ESP-IDF bootloader
+const STATE_NEW: u32 = 0;
+const STATE_PENDING_VERIFY: u32 = 1;
+const STATE_VALID: u32 = 2;
+const STATE_ABORTED: u32 = 4;
+
+const ENTRY_LENGTH: usize = 32;
+
+/// Entry of the `otadata` partition, which holds two of them in separate sectors. The bootloader
+/// boots the slot of the valid entry with the highest sequence number
+#[derive(Debug, Clone, Copy)]
+struct SelectEntry {
+    seq: u32,
+    state: u32,
+}
+
+impl SelectEntry {
+    fn from_bytes(bytes: &[u8; ENTRY_LENGTH]) -> Option<Self> {
+        let word =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let seq = word(0);
+        let is_valid = seq != 0 && seq != u32::MAX && word(28) == checksum(seq);
+        is_valid.then_some(Self {
+            seq,
+            state: word(24),
+        })
+    }
+
+    fn to_bytes(self) -> [u8; ENTRY_LENGTH] {
+        // The label is left erased
+        let mut bytes = [0xff; ENTRY_LENGTH];
+        bytes[0..4].copy_from_slice(&self.seq.to_le_bytes());
+        bytes[24..28].copy_from_slice(&self.state.to_le_bytes());
+        bytes[28..32].copy_from_slice(&checksum(self.seq).to_le_bytes());
+        bytes
+    }
+
+    fn slot(&self) -> usize {
+        (self.seq as usize - 1) % OTA_SLOT_OFFSETS.len()
+    }
+}
+
+fn checksum(seq: u32) -> u32 {
+    crc32_le(u32::MAX, &seq.to_le_bytes())
+}
+
+struct OtaData {
+    entries: [Option<SelectEntry>; 2],
+}
+
+impl OtaData {
+    async fn read() -> Result<Self> {
+        let mut storage = STORAGE.get().lock().await;
+        let mut entries = [None; 2];
+        for (idx, entry) in entries.iter_mut().enumerate() {
+            let mut bytes = [0; ENTRY_LENGTH];
+            storage
+                .read(OTA_DATA_OFFSET + idx as u32 * FLASH_SECTOR_SIZE, &mut bytes)
+                .map_err(Error::StorageRead)?;
+            *entry = SelectEntry::from_bytes(&bytes);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Index of the entry the bootloader boots from, `None` if it boots from the first slot as
+    /// nothing has been selected yet
+    fn active(&self) -> Option<usize> {
+        (0..self.entries.len())
+            .filter(|&idx| self.entries[idx].is_some())
+            .max_by_key(|&idx| self.entries[idx].map(|entry| entry.seq))
+    }
+
+    fn active_entry(&self) -> Option<SelectEntry> {
+        self.active().and_then(|idx| self.entries[idx])
+    }
+
+    fn running_slot(&self) -> usize {
+        self.active_entry().map(|entry| entry.slot()).unwrap_or(0)
+    }
+
+    async fn write(&mut self, idx: usize, entry: SelectEntry) -> Result<()> {
+        let mut sector = vec![0xff; FLASH_SECTOR_SIZE as usize];
+        sector[..ENTRY_LENGTH].copy_from_slice(&entry.to_bytes());
+        STORAGE
+            .get()
+            .lock()
+            .await
+            .write(OTA_DATA_OFFSET + idx as u32 * FLASH_SECTOR_SIZE, &sector)
+            .map_err(Error::StorageWrite)?;
+        self.entries[idx] = Some(entry);
+        Ok(())
+    }
+
+    /// Makes the bootloader boot from the slot on the next boot. The active entry is kept, so that
+    /// the device can roll back to it
+    async fn select(&mut self, slot: usize, state: u32) -> Result<()> {
+        let max_seq = self.active_entry().map(|entry| entry.seq).unwrap_or(0);
+        let mut entry = SelectEntry {
+            seq: max_seq + 1,
+            state,
+        };
+        while entry.slot() != slot {
+            entry.seq += 1;
+        }
+        let idx = self.active().map(|idx| (idx + 1) % 2).unwrap_or(0);
+        self.write(idx, entry).await
+    }
+
+    async fn set_state(&mut self, state: u32) -> Result<()> {
+        let Some(idx) = self.active() else {
+            return Ok(());
+        };
+        let mut entry = self.entries[idx].unwrap();
+        entry.state = state;
+        self.write(idx, entry).await
+    }
+}
+
+/// Firmware running on the device
+#[derive(Serialize)]
+pub struct FirmwareInfo {
+    version: &'static str,
+    slot: u8,
+    /// Firmware has not confirmed itself healthy yet
+    pending_verify: bool,
+    max_size: u32,
+}
+
+impl FirmwareInfo {
+    pub async fn read() -> Result<Self> {
+        let ota_data = OtaData::read().await?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION"),
+            slot: ota_data.running_slot() as u8,
+            pending_verify: ota_data
+                .active_entry()
+                .is_some_and(|entry| entry.state == STATE_PENDING_VERIFY),
+            max_size: OTA_SLOT_SIZE,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FirmwareUpdateRequest {
+    pub size: u32,
+    pub sha256: [u8; SHA256_LENGTH],
+}
+
+/// Image being uploaded to the slot the device is not running from
+pub struct FirmwareUpdate {
+    slot: usize,
+    request: FirmwareUpdateRequest,
+    hasher: Sha256,
+    written: u32,
+}
+
+impl FirmwareUpdate {
+    pub async fn begin(request: FirmwareUpdateRequest) -> Result<Self> {
+        if request.size == 0 || request.size > OTA_SLOT_SIZE {
+            return Err(Error::InvalidFirmwareUpdate);
+        }
+        let slot = (OtaData::read().await?.running_slot() + 1) % OTA_SLOT_OFFSETS.len();
+        log::info!(target: "OTA", "Updating firmware in slot {slot}, {} bytes", request.size);
+        // Nothing of the firmware previously stored in the slot may be left behind the new image
+        storage::erase(OTA_SLOT_OFFSETS[slot], OTA_SLOT_SIZE).await?;
+        Ok(Self {
+            slot,
+            request,
+            hasher: Sha256::new(),
+            written: 0,
+        })
+    }
+
+    /// Chunks have to be written in order, the last chunk may be sent again if its response has
+    /// been lost
+    pub async fn write_chunk(&mut self, offset: u32, data: &[u8]) -> Result<()> {
+        let end = offset
+            .checked_add(data.len() as u32)
+            .ok_or(Error::InvalidFirmwareUpdate)?;
+        if offset < self.written && end == self.written {
+            return Ok(());
+        }
+        if offset != self.written || end > self.request.size {
+            return Err(Error::InvalidFirmwareUpdate);
+        }
+        if offset == 0 && data.first() != Some(&FIRMWARE_IMAGE_MAGIC) {
+            return Err(Error::InvalidFirmwareImage);
+        }
+        STORAGE
+            .get()
+            .lock()
+            .await
+            .write(OTA_SLOT_OFFSETS[self.slot] + offset, data)
+            .map_err(Error::StorageWrite)?;
+        self.hasher.update(data);
+        self.written = end;
+        Ok(())
+    }
+
+    /// Checks the uploaded image and boots from it on the next boot
+    pub async fn finish(self) -> Result<()> {
+        if self.written != self.request.size || self.hasher.finalize() != self.request.sha256 {
+            return Err(Error::InvalidFirmwareImage);
+        }
+        OtaData::read().await?.select(self.slot, STATE_NEW).await?;
+        log::info!(target: "OTA", "Firmware in slot {} will be booted", self.slot);
+        Ok(())
+    }
+}
+
+/// Checks whether the firmware has been booted for the first time after an update. The bootloader
+/// flashed by espflash does not roll back by itself, so if the new firmware has been booted before
+/// and has not confirmed itself healthy, the previous firmware is booted again
+///
+/// Returns whether the firmware still has to confirm itself healthy
+pub async fn check_boot() -> Result<bool> {
+    let mut ota_data = OtaData::read().await?;
+    let Some(entry) = ota_data.active_entry() else {
+        return Ok(false);
+    };
+    match entry.state {
+        STATE_NEW => {
+            log::info!(target: "OTA", "Booted new firmware from slot {}", entry.slot());
+            ota_data.set_state(STATE_PENDING_VERIFY).await?;
+            Ok(true)
+        }
+        STATE_PENDING_VERIFY => {
+            log::error!(target: "OTA", "New firmware has not confirmed itself, rolling back");
+            let previous_slot = (entry.slot() + 1) % OTA_SLOT_OFFSETS.len();
+            ota_data.set_state(STATE_ABORTED).await?;
+            ota_data.select(previous_slot, STATE_VALID).await?;
+            software_reset();
+            Ok(false)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Confirms the firmware healthy once it has been connected to the network for a while
+#[embassy_executor::task]
+pub async fn confirm_task(stack: Stack<'static>) {
+    stack.wait_config_up().await;
+    Timer::after(OTA_CONFIRM_DELAY).await;
+
+    let result = match OtaData::read().await {
+        Ok(mut ota_data) => ota_data.set_state(STATE_VALID).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(()) => log::info!(target: "OTA", "Firmware confirmed healthy"),
+        Err(err) => log::error!(target: "OTA", "Error confirming firmware: {err}"),
+    }
+}
@@ -0,0 +1,168 @@
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::presets::utils::lerp_color;
+use crate::storage::Persistent;
+use crate::{
+    Error, MAX_PALETTE_STOPS, MAX_PALETTES, PALETTES_STORAGE_OFFSET, PALETTES_VERSION, Result,
+};
+
+/// Gradient used by palette-capable presets to turn an 8-bit position into a color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Palette {
+    pub name: heapless::String<16>,
+    /// Gradient stops as `[position, r, g, b]`, sorted by position
+    pub stops: heapless::Vec<[u8; 4], MAX_PALETTE_STOPS>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new("Grayscale", &[[0, 0, 0, 0], [255, 255, 255, 255]])
+    }
+}
+
+impl Palette {
+    fn new(name: &str, stops: &[[u8; 4]]) -> Self {
+        Self {
+            name: heapless::String::from_str(name).unwrap(),
+            stops: heapless::Vec::from_slice(stops).unwrap(),
+        }
+    }
+
+    /// Checks that the palette has at least one stop and sorts the stops by position
+    pub fn validate(mut self) -> Result<Self> {
+        if self.stops.is_empty() {
+            return Err(Error::InvalidPalette);
+        }
+        self.stops.sort_unstable_by_key(|stop| stop[0]);
+        Ok(self)
+    }
+
+    pub fn color_at(&self, position: u8) -> [u8; 3] {
+        let rgb = |stop: &[u8; 4]| [stop[1], stop[2], stop[3]];
+
+        let Some(first) = self.stops.first() else {
+            return [0; 3];
+        };
+        if position <= first[0] {
+            return rgb(first);
+        }
+
+        for pair in self.stops.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            if position <= to[0] {
+                let frac = (position - from[0]) as u16 * 255 / (to[0] - from[0]) as u16;
+                return lerp_color(&rgb(from), &rgb(to), frac as u8);
+            }
+        }
+
+        rgb(self.stops.last().unwrap())
+    }
+}
+
+/// Palettes stored on the device, addressed by their slot index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Palettes {
+    palettes: [Option<Palette>; MAX_PALETTES],
+}
+
+impl Default for Palettes {
+    fn default() -> Self {
+        let mut palettes = [const { None }; MAX_PALETTES];
+        palettes[0] = Some(Palette::new(
+            "Fire",
+            &[
+                [0, 0, 0, 0],
+                [64, 127, 0, 0],
+                [128, 255, 0, 0],
+                [191, 255, 127, 0],
+                [255, 255, 255, 0],
+            ],
+        ));
+        palettes[1] = Some(Palette::new(
+            "Rainbow",
+            &[
+                [0, 255, 0, 0],
+                [85, 0, 255, 0],
+                [170, 0, 0, 255],
+                [255, 255, 0, 0],
+            ],
+        ));
+        palettes[2] = Some(Palette::new(
+            "Ocean",
+            &[
+                [0, 0, 0, 32],
+                [96, 0, 64, 160],
+                [176, 0, 160, 200],
+                [255, 200, 255, 255],
+            ],
+        ));
+        palettes[3] = Some(Palette::new(
+            "Lava",
+            &[
+                [0, 0, 0, 0],
+                [96, 128, 0, 0],
+                [176, 255, 64, 0],
+                [255, 255, 200, 64],
+            ],
+        ));
+        palettes[4] = Some(Palette::new(
+            "Forest",
+            &[[0, 0, 32, 0], [128, 32, 128, 16], [255, 160, 255, 64]],
+        ));
+        palettes[5] = Some(Palette::new(
+            "Aurora",
+            &[
+                [0, 0, 16, 8],
+                [80, 0, 160, 64],
+                [160, 32, 255, 128],
+                [220, 96, 32, 160],
+                [255, 160, 0, 200],
+            ],
+        ));
+        Self { palettes }
+    }
+}
+
+impl Persistent for Palettes {
+    const STORAGE_OFFSET: u32 = PALETTES_STORAGE_OFFSET;
+    const VERSION: u32 = PALETTES_VERSION;
+
+    fn is_valid(&self) -> bool {
+        self.palettes.iter().flatten().all(|palette| {
+            !palette.stops.is_empty() && palette.stops.is_sorted_by_key(|stop| stop[0])
+        })
+    }
+}
+
+impl Palettes {
+    pub fn get(&self, id: u8) -> Option<&Palette> {
+        self.palettes.get(id as usize)?.as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &Palette)> {
+        self.palettes
+            .iter()
+            .enumerate()
+            .filter_map(|(id, palette)| Some((id as u8, palette.as_ref()?)))
+    }
+
+    pub fn insert(&mut self, id: u8, palette: Palette) -> Result<()> {
+        let slot = self
+            .palettes
+            .get_mut(id as usize)
+            .ok_or(Error::PaletteIdOutOfBounds)?;
+        *slot = Some(palette.validate()?);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: u8) -> Result<()> {
+        self.palettes
+            .get_mut(id as usize)
+            .ok_or(Error::PaletteIdOutOfBounds)?
+            .take()
+            .map(|_| ())
+            .ok_or(Error::PaletteNotFound)
+    }
+}
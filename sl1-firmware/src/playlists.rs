@@ -0,0 +1,96 @@
+use embassy_time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{PresetId, PresetSettings};
+use crate::storage::Persistent;
+use crate::{
+    Error, MAX_PLAYLIST_ENTRIES, MAX_PLAYLISTS, PLAYLISTS_STORAGE_OFFSET, PLAYLISTS_VERSION, Result,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    #[serde(rename = "id")]
+    pub preset_id: PresetId,
+    #[serde(rename = "s")]
+    pub preset_settings: PresetSettings,
+    /// How long the preset is shown, in seconds
+    #[serde(rename = "d")]
+    pub duration: u16,
+}
+
+impl PlaylistEntry {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration.max(1) as u64)
+    }
+}
+
+/// Sequence of presets that the renderer cycles through when the playlist is active
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Playlist {
+    pub name: heapless::String<16>,
+    #[serde(default)]
+    pub shuffle: bool,
+    pub entries: heapless::Vec<PlaylistEntry, MAX_PLAYLIST_ENTRIES>,
+}
+
+impl Playlist {
+    /// Checks that the playlist is not empty and only refers to existing presets
+    pub fn validate(self) -> Result<Self> {
+        if self.entries.is_empty() {
+            return Err(Error::InvalidPlaylist);
+        }
+        for entry in &self.entries {
+            PresetId::new_fallible(entry.preset_id.id())?;
+        }
+        Ok(self)
+    }
+}
+
+/// Playlists stored on the device, addressed by their slot index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Playlists {
+    playlists: [Option<Playlist>; MAX_PLAYLISTS],
+}
+
+impl Persistent for Playlists {
+    const STORAGE_OFFSET: u32 = PLAYLISTS_STORAGE_OFFSET;
+    const VERSION: u32 = PLAYLISTS_VERSION;
+
+    fn is_valid(&self) -> bool {
+        self.playlists
+            .iter()
+            .flatten()
+            .all(|playlist| playlist.clone().validate().is_ok())
+    }
+}
+
+impl Playlists {
+    pub fn get(&self, id: u8) -> Option<&Playlist> {
+        self.playlists.get(id as usize)?.as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &Playlist)> {
+        self.playlists
+            .iter()
+            .enumerate()
+            .filter_map(|(id, playlist)| Some((id as u8, playlist.as_ref()?)))
+    }
+
+    pub fn insert(&mut self, id: u8, playlist: Playlist) -> Result<()> {
+        let slot = self
+            .playlists
+            .get_mut(id as usize)
+            .ok_or(Error::PlaylistIdOutOfBounds)?;
+        *slot = Some(playlist.validate()?);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: u8) -> Result<()> {
+        self.playlists
+            .get_mut(id as usize)
+            .ok_or(Error::PlaylistIdOutOfBounds)?
+            .take()
+            .map(|_| ())
+            .ok_or(Error::PlaylistNotFound)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::noise::{PerlinNoise, time_step};
+use crate::presets::utils::{fade_u8, scale_color};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Aurora",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Scale", 32),
+        ParamInfo::palette(ParamId::Palette, "Palette", 5),
+    ],
+};
+
+pub struct AuroraPreset {
+    palette: Palette,
+    scale: u16,
+    perlin: PerlinNoise,
+    speed_mult: u16,
+    time: u16,
+}
+
+impl Preset for AuroraPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            palette: palette.clone(),
+            scale: preset_settings.scale as u16,
+            perlin: PerlinNoise::default(),
+            speed_mult: time_step(preset_settings.speed),
+            time: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        // One noise field picks the color of the curtains, a finer one with more octaves decides
+        // how bright they are, contrast is boosted so that dark gaps appear between the curtains
+        frame.iter_mut().enumerate().for_each(|(led_idx, led)| {
+            let x = led_idx as u16 * self.scale;
+            let band = self.perlin.get_u8_3d(x, self.time, 0);
+            let intensity = self.perlin.get_u8_3d_octaves(
+                x.wrapping_mul(2),
+                self.time.wrapping_mul(2),
+                0x8000,
+                3,
+            );
+            *led = scale_color(&self.palette.color_at(band), fade_u8(intensity));
+        });
+
+        self.time = self.time.wrapping_add(self.speed_mult);
+    }
+}
@@ -0,0 +1,40 @@
+use embassy_time::{Duration, Ticker};
+
+use crate::presets::utils::scale_u8;
+use crate::presets::{Frame, draw_black, draw_frame};
+use crate::{LED_COUNT, LedsAdapter};
+
+const FRAME_RATE: u64 = 50;
+/// Frames it takes the head to cross the strip
+const SWEEP_FRAMES: usize = 40;
+/// Frames the tail takes to fade out after the sweep
+const FADE_FRAMES: usize = 20;
+const COLOR: [u8; 3] = [255, 160, 80];
+/// Part of the brightness the tail keeps every frame, out of 256
+const TAIL_DECAY: u8 = 200;
+
+/// Sweeps a warm head across the strip, leaving a fading tail behind
+pub async fn play(leds: &mut LedsAdapter) {
+    let mut frame: Frame = [[0; 3]; LED_COUNT];
+    let mut ticker = Ticker::every(Duration::from_hz(FRAME_RATE));
+    let mut head = 0;
+
+    for step in 1..=SWEEP_FRAMES + FADE_FRAMES {
+        frame
+            .iter_mut()
+            .flatten()
+            .for_each(|value| *value = scale_u8(*value, TAIL_DECAY));
+
+        // The head moves by more than one LED per frame on long strips
+        let next_head = LED_COUNT * step.min(SWEEP_FRAMES) / SWEEP_FRAMES;
+        frame[head..next_head].fill(COLOR);
+        head = next_head;
+
+        if let Err(err) = draw_frame(leds, &frame) {
+            log::error!("{err}");
+        }
+        ticker.next().await;
+    }
+
+    draw_black(leds);
+}
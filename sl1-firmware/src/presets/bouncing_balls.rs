@@ -0,0 +1,39 @@
+use sl1_protocol::effects::bouncing_balls::BouncingBalls;
+
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::rng::PRESET_SEED;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Bouncing Balls",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Gravity", 128),
+        ParamInfo::u8(ParamId::Scale, "Balls", 96),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+pub struct BouncingBallsPreset {
+    bouncing_balls: BouncingBalls<LED_COUNT>,
+}
+
+impl Preset for BouncingBallsPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            bouncing_balls: BouncingBalls::new(
+                preset_settings.speed,
+                preset_settings.scale,
+                PRESET_SEED,
+                |position| palette.color_at(position),
+            ),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.bouncing_balls.render(frame);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::utils::{fade_u8, lerp_u8, scale_color};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Breathing",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::color(ParamId::Color, "Color", [255, 255, 255]),
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Minimum", 0),
+    ],
+};
+
+pub struct BreathingPreset {
+    color: [u8; 3],
+    minimum: u8,
+    step: u16,
+    phase: u16,
+}
+
+impl Preset for BreathingPreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            color: preset_settings.color,
+            minimum: preset_settings.scale,
+            step: preset_settings.speed as u16 * 2 + 64,
+            phase: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let position = (self.phase >> 8) as u8;
+        let triangle = match position {
+            0..128 => position * 2,
+            128.. => (u8::MAX - position) * 2,
+        };
+        // Squaring the triangle wave makes the pulse linger near its minimum like breathing does
+        let level = lerp_u8(self.minimum, u8::MAX, fade_u8(triangle));
+        frame.fill(scale_color(&self.color, level));
+
+        self.phase = self.phase.wrapping_add(self.step);
+    }
+}
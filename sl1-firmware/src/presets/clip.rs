@@ -0,0 +1,56 @@
+use embassy_time::Duration;
+use sl1_protocol::clip::{
+    CLIP_HEADER_LENGTH, ClipHeader, FRAME_LENGTH_PREFIX, MAX_FRAME_LENGTH, decode_frame,
+};
+
+use crate::presets::Frame;
+use crate::{Error, Result, clips};
+
+/// Streams the frames of a stored clip from flash, looping it once it ends
+pub struct ClipPlayer {
+    id: u8,
+    header: ClipHeader,
+    frame_index: u16,
+    offset: u32,
+    buf: [u8; MAX_FRAME_LENGTH],
+}
+
+impl ClipPlayer {
+    pub async fn new(id: u8) -> Result<Option<Self>> {
+        if clips::get(id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let mut header = [0u8; CLIP_HEADER_LENGTH];
+        clips::read(id, 0, &mut header).await?;
+        Ok(Some(Self {
+            id,
+            header: ClipHeader::from_bytes(&header).map_err(Error::Clip)?,
+            frame_index: 0,
+            offset: CLIP_HEADER_LENGTH as u32,
+            buf: [0; MAX_FRAME_LENGTH],
+        }))
+    }
+
+    pub fn frame_time(&self) -> Duration {
+        Duration::from_micros(1_000_000 / self.header.fps as u64)
+    }
+
+    pub async fn render(&mut self, frame: &mut Frame) -> Result<()> {
+        if self.frame_index == self.header.frame_count {
+            self.frame_index = 0;
+            self.offset = CLIP_HEADER_LENGTH as u32;
+        }
+
+        let mut prefix = [0u8; FRAME_LENGTH_PREFIX];
+        clips::read(self.id, self.offset, &mut prefix).await?;
+        let frame_length = (u16::from_le_bytes(prefix) as usize).min(MAX_FRAME_LENGTH);
+        let data = &mut self.buf[..frame_length];
+        clips::read(self.id, self.offset + FRAME_LENGTH_PREFIX as u32, data).await?;
+
+        self.frame_index += 1;
+        self.offset += (FRAME_LENGTH_PREFIX + frame_length) as u32;
+
+        decode_frame(data, frame).map_err(Error::Clip)
+    }
+}
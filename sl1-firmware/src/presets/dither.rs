@@ -0,0 +1,6 @@
+use crate::LED_COUNT;
+
+// The conversion is shared with the host, where it is tested
+pub use sl1_protocol::dither::{expand, quantize_rounded};
+
+pub type Ditherer = sl1_protocol::dither::Ditherer<LED_COUNT>;
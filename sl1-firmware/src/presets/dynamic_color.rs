@@ -1,18 +1,28 @@
-use core::sync::atomic::Ordering;
-
-use embassy_time::Ticker;
-use smart_leds_trait::SmartLedsWrite;
-
-use crate::presets::Preset;
-use crate::presets::utils::{color_wheel, dim, whiten};
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::utils::{color_wheel, whiten};
+use crate::presets::{Frame, Preset, PresetContext};
 use crate::settings::PresetSettings;
-use crate::{Error, FRAME_TIME, LED_COUNT, LedsAdapter, Result, SHOULD_UPDATE};
 
-pub struct DynamicColorPreset {}
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Dynamic Color",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 255),
+        ParamInfo::u8(ParamId::Scale, "Whiteness", 0),
+    ],
+};
+
+pub struct DynamicColorPreset {
+    scale: u8,
+    wait_cycles: u8,
+    speed_mult: u8,
+    step: u8,
+    cycle: u8,
+}
 
 impl Preset for DynamicColorPreset {
-    async fn run(leds: &mut LedsAdapter, preset_settings: &PresetSettings) -> Result<()> {
-        let mut ticker = Ticker::every(FRAME_TIME);
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
         let wait_cycles = if preset_settings.speed < 128 {
             128 - preset_settings.speed
         } else {
@@ -24,24 +34,23 @@ impl Preset for DynamicColorPreset {
             1
         };
 
-        loop {
-            for i in 0..=255 as u8 {
-                let wheel_pos = i.wrapping_mul(speed_mult);
-                let color = dim(
-                    &whiten(&color_wheel(wheel_pos), preset_settings.scale),
-                    255 - preset_settings.brightness,
-                );
+        Self {
+            scale: preset_settings.scale,
+            wait_cycles,
+            speed_mult,
+            step: 0,
+            cycle: 0,
+        }
+    }
 
-                leds.write([color; LED_COUNT].into_iter())
-                    .map_err(|_| Error::LedAdapterWrite)?;
+    fn render(&mut self, frame: &mut Frame) {
+        let wheel_pos = self.step.wrapping_mul(self.speed_mult);
+        frame.fill(whiten(&color_wheel(wheel_pos), self.scale));
 
-                for _ in 0..wait_cycles {
-                    if SHOULD_UPDATE.load(Ordering::Relaxed) {
-                        return Ok(());
-                    }
-                    ticker.next().await;
-                }
-            }
+        self.cycle += 1;
+        if self.cycle >= self.wait_cycles {
+            self.cycle = 0;
+            self.step = self.step.wrapping_add(1);
         }
     }
 }
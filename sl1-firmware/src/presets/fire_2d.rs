@@ -0,0 +1,57 @@
+use crate::layout::MatrixLayout;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::noise::{PerlinNoise, time_step};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Fire 2D",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 255),
+        ParamInfo::u8(ParamId::Scale, "Scale", 64),
+        ParamInfo::palette(ParamId::Palette, "Palette", 0),
+    ],
+};
+
+/// Flames rising from the bottom row of the panel: the noise field scrolls upwards and cools
+/// down with the height
+pub struct Fire2dPreset {
+    layout: MatrixLayout,
+    palette: Palette,
+    scale: u16,
+    perlin: PerlinNoise,
+    speed_mult: u16,
+    time: u16,
+}
+
+impl Preset for Fire2dPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, context: &PresetContext) -> Self {
+        Self {
+            layout: context.layout,
+            palette: palette.clone(),
+            scale: preset_settings.scale as u16,
+            perlin: PerlinNoise::default(),
+            speed_mult: time_step(preset_settings.speed),
+            time: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        // LEDs past the end of a panel smaller than the strip stay dark
+        frame.fill([0; 3]);
+        let (_, height) = self.layout.dimensions();
+        for (x, y, idx) in self.layout.pixels() {
+            let noise = self.perlin.get_u8_2d(
+                x as u16 * self.scale,
+                (y as u16 * self.scale).wrapping_add(self.time.wrapping_mul(4)),
+            );
+            let rows_from_bottom = (height - 1 - y) as u16;
+            let cooling = (rows_from_bottom * 255 / height as u16) as u8;
+            frame[idx] = self.palette.color_at(noise.saturating_sub(cooling));
+        }
+
+        self.time = self.time.wrapping_add(self.speed_mult);
+    }
+}
@@ -0,0 +1,37 @@
+use sl1_protocol::effects::fireworks::Fireworks;
+
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::rng::PRESET_SEED;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Fireworks",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Launch Rate", 128),
+        ParamInfo::u8(ParamId::Scale, "Particles", 128),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+pub struct FireworksPreset {
+    palette: Palette,
+    fireworks: Fireworks<LED_COUNT>,
+}
+
+impl Preset for FireworksPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            palette: palette.clone(),
+            fireworks: Fireworks::new(preset_settings.speed, preset_settings.scale, PRESET_SEED),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.fireworks
+            .render(frame, |position| self.palette.color_at(position));
+    }
+}
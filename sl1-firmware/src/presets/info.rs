@@ -0,0 +1,106 @@
+use serde::Serialize;
+
+use crate::MAX_PALETTES;
+use crate::settings::PresetSettings;
+
+/// Description of a preset and the parameters it reads from its `PresetSettings`, sent to
+/// clients so that they can build preset specific controls.
+#[derive(Debug, Serialize)]
+pub struct PresetInfo {
+    pub name: &'static str,
+    pub params: &'static [ParamInfo],
+}
+
+impl PresetInfo {
+    pub fn default_settings(&self) -> PresetSettings {
+        let mut preset_settings = PresetSettings::default();
+        for param in self.params {
+            match param.id {
+                ParamId::Brightness => preset_settings.brightness = param.default as u8,
+                ParamId::Speed => preset_settings.speed = param.default as u8,
+                ParamId::Scale => preset_settings.scale = param.default as u8,
+                ParamId::Color => {
+                    let [_, r, g, b] = param.default.to_be_bytes();
+                    preset_settings.color = [r, g, b];
+                }
+                ParamId::Palette => preset_settings.palette = param.default as u8,
+            }
+        }
+        preset_settings
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParamInfo {
+    #[serde(rename = "name")]
+    pub id: ParamId,
+    pub label: &'static str,
+    #[serde(rename = "type")]
+    pub kind: ParamKind,
+    pub min: u8,
+    pub max: u8,
+    /// Default value, colors are packed as 0xRRGGBB, palettes are referenced by their id
+    pub default: u32,
+}
+
+impl ParamInfo {
+    pub const fn u8(id: ParamId, label: &'static str, default: u8) -> Self {
+        Self {
+            id,
+            label,
+            kind: ParamKind::U8,
+            min: 0,
+            max: 255,
+            default: default as u32,
+        }
+    }
+
+    pub const fn color(id: ParamId, label: &'static str, default: [u8; 3]) -> Self {
+        Self {
+            id,
+            label,
+            kind: ParamKind::Color,
+            min: 0,
+            max: 255,
+            default: u32::from_be_bytes([0, default[0], default[1], default[2]]),
+        }
+    }
+
+    pub const fn palette(id: ParamId, label: &'static str, default: u8) -> Self {
+        Self {
+            id,
+            label,
+            kind: ParamKind::Palette,
+            min: 0,
+            max: MAX_PALETTES as u8 - 1,
+            default: default as u32,
+        }
+    }
+}
+
+/// Field of `PresetSettings` the parameter is stored in, serialized with the same names
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum ParamId {
+    #[serde(rename = "b")]
+    Brightness,
+    #[serde(rename = "sp")]
+    Speed,
+    #[serde(rename = "sc")]
+    Scale,
+    #[serde(rename = "c")]
+    Color,
+    #[serde(rename = "p")]
+    Palette,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum ParamKind {
+    #[serde(rename = "u8")]
+    U8,
+    #[serde(rename = "color")]
+    Color,
+    #[serde(rename = "palette")]
+    Palette,
+}
+
+pub const BRIGHTNESS: ParamInfo = ParamInfo::u8(ParamId::Brightness, "Brightness", 50);
@@ -0,0 +1,46 @@
+use alloc::boxed::Box;
+
+use crate::LED_COUNT;
+use crate::layers::{BlendMode, Layer};
+use crate::palettes::Palette;
+use crate::presets::{
+    Frame, HighPrecisionFrame, Preset, PresetContext, apply_brightness, new_preset,
+};
+
+/// Renders a layer and composites it over the frame rendered by the layers below it
+pub struct LayerRenderer {
+    preset: Box<dyn Preset>,
+    brightness: u8,
+    blend_mode: BlendMode,
+    opacity: u8,
+    frame: Frame,
+    high_precision_frame: HighPrecisionFrame,
+}
+
+impl LayerRenderer {
+    pub fn new(layer: &Layer, palette: &Palette, context: &PresetContext) -> Self {
+        Self {
+            preset: new_preset(layer.preset_id, &layer.preset_settings, palette, context),
+            brightness: layer.preset_settings.brightness,
+            blend_mode: layer.blend_mode,
+            opacity: layer.opacity,
+            frame: [[0; 3]; LED_COUNT],
+            high_precision_frame: [[0; 3]; LED_COUNT],
+        }
+    }
+
+    pub fn render_onto(&mut self, output: &mut HighPrecisionFrame) {
+        self.preset.render(&mut self.frame);
+        apply_brightness(&self.frame, self.brightness, &mut self.high_precision_frame);
+
+        output
+            .iter_mut()
+            .flatten()
+            .zip(self.high_precision_frame.iter().flatten())
+            .for_each(|(below, &above)| {
+                let blended = self.blend_mode.blend(*below, above) as i32;
+                let delta = (blended - *below as i32) * self.opacity as i32 / 255;
+                *below = (*below as i32 + delta) as u16;
+            });
+    }
+}
@@ -0,0 +1,39 @@
+use sl1_protocol::effects::meteor::Meteor;
+
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::rng::PRESET_SEED;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Meteor",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::color(ParamId::Color, "Color", [255, 255, 255]),
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Trail", 128),
+    ],
+};
+
+pub struct MeteorPreset {
+    meteor: Meteor<LED_COUNT>,
+}
+
+impl Preset for MeteorPreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            meteor: Meteor::new(
+                preset_settings.color,
+                preset_settings.speed,
+                preset_settings.scale,
+                PRESET_SEED,
+            ),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.meteor.render(frame);
+    }
+}
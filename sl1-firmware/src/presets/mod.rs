@@ -1,56 +1,346 @@
+mod aurora;
+mod boot_animation;
+mod bouncing_balls;
+mod breathing;
+mod clip;
+mod dither;
 mod dynamic_color;
 mod fire;
+mod fire_2d;
+mod fireworks;
+pub mod info;
+mod lava;
+mod layer;
+mod meteor;
 mod noise;
+mod noise_2d;
+mod ocean;
+mod plasma;
+mod playlist;
+mod police;
+mod power;
+mod rainbow_2d;
+mod rng;
 mod running_rainbow;
+mod script;
+mod sparkle;
 mod static_color;
-mod utils;
+mod strobe;
+pub mod sunrise;
+mod theater_chase;
+mod twinkle;
+pub mod utils;
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::sync::atomic::Ordering;
 
-use embassy_time::Timer;
+use embassy_time::{Duration, Instant, Ticker, Timer};
 use smart_leds_trait::SmartLedsWrite;
 
-use crate::settings::PresetSettings;
-use crate::{FRAME_TIME, LED_COUNT, LedsAdapter, Result, SETTINGS, SHOULD_UPDATE};
+use crate::layout::MatrixLayout;
+use crate::palettes::Palette;
+use crate::presets::clip::ClipPlayer;
+use crate::presets::dither::Ditherer;
+use crate::presets::info::PresetInfo;
+use crate::presets::layer::LayerRenderer;
+use crate::presets::playlist::PlaylistPlayer;
+use crate::presets::sunrise::SunriseRenderer;
+use crate::scripts::Script;
+use crate::settings::{PresetId, PresetSettings};
+use crate::timers::ShownSettings;
+use crate::{
+    BOOT_SETTINGS, CLOCK, Error, LAYERS, LED_COUNT, LedsAdapter, PALETTES, PLAYLISTS, Result,
+    SCRIPT, SETTINGS, SHOULD_UPDATE, STATS, TIMERS,
+};
+
+pub type Frame = [[u8; 3]; LED_COUNT];
+pub type HighPrecisionFrame = [[u16; 3]; LED_COUNT];
+
+/// State of the renderer that presets may depend on, read when the presets are built
+pub struct PresetContext<'a> {
+    pub frame_time: Duration,
+    pub layout: MatrixLayout,
+    pub script: &'a Script,
+}
 
 trait Preset {
-    async fn run(leds: &mut LedsAdapter, preset_settings: &PresetSettings) -> Result<()>;
+    fn new(preset_settings: &PresetSettings, palette: &Palette, context: &PresetContext) -> Self
+    where
+        Self: Sized;
+
+    fn render(&mut self, frame: &mut Frame);
+}
+
+type PresetConstructor = fn(&PresetSettings, &Palette, &PresetContext) -> Box<dyn Preset>;
+
+macro_rules! preset_registry {
+    ($($module:ident::$preset:ident),* $(,)?) => {
+        pub const PRESET_INFO: &[&PresetInfo] = &[$(&$module::INFO),*];
+        pub const PRESET_COUNT: u8 = PRESET_INFO.len() as u8;
+
+        fn new_preset(
+            preset_id: PresetId,
+            preset_settings: &PresetSettings,
+            palette: &Palette,
+            context: &PresetContext,
+        ) -> Box<dyn Preset> {
+            const CONSTRUCTORS: &[PresetConstructor] = &[$(box_preset::<$module::$preset>),*];
+            CONSTRUCTORS[preset_id.id() as usize](preset_settings, palette, context)
+        }
+    };
+}
+
+preset_registry! {
+    static_color::StaticColorPreset,
+    dynamic_color::DynamicColorPreset,
+    running_rainbow::RunningRainbowPreset,
+    fire::FirePreset,
+    twinkle::TwinklePreset,
+    sparkle::SparklePreset,
+    meteor::MeteorPreset,
+    breathing::BreathingPreset,
+    theater_chase::TheaterChasePreset,
+    strobe::StrobePreset,
+    police::PolicePreset,
+    plasma::PlasmaPreset,
+    ocean::OceanPreset,
+    lava::LavaPreset,
+    aurora::AuroraPreset,
+    bouncing_balls::BouncingBallsPreset,
+    fireworks::FireworksPreset,
+    script::ScriptPreset,
+    noise_2d::Noise2dPreset,
+    rainbow_2d::Rainbow2dPreset,
+    fire_2d::Fire2dPreset,
+}
+
+fn box_preset<P: Preset + 'static>(
+    preset_settings: &PresetSettings,
+    palette: &Palette,
+    context: &PresetContext,
+) -> Box<dyn Preset> {
+    Box::new(P::new(preset_settings, palette, context))
 }
 
 pub async fn run_renderer(mut leds: LedsAdapter) -> ! {
+    let mut frame: Frame = [[0; 3]; LED_COUNT];
+    let mut high_precision_frame: HighPrecisionFrame = [[0; 3]; LED_COUNT];
+    let mut output_frame: Frame = [[0; 3]; LED_COUNT];
+    let mut ditherer = Ditherer::default();
+    let mut playlist_player: Option<PlaylistPlayer> = None;
+    let mut clip_player: Option<ClipPlayer> = None;
+    // Kept across restarts of the loop, so that only the end of the entry moves the playlist on
+    let mut entry_end: Option<Instant> = None;
+
+    if BOOT_SETTINGS.get().lock().await.boot_animation {
+        boot_animation::play(&mut leds).await;
+    }
+
     loop {
+        // The loop is restarted when the settings have changed or when the current playlist entry
+        // has ended, in which case the playlist moves on to its next entry
+        let settings_changed = SHOULD_UPDATE.load(Ordering::Relaxed);
         SHOULD_UPDATE.store(false, Ordering::Relaxed);
 
-        if !SETTINGS.get().lock().await.is_on {
+        let settings_lock = SETTINGS.get().lock().await;
+        let is_on = settings_lock.is_on;
+        let mut preset_id = settings_lock.current_preset_id;
+        let mut preset_settings = settings_lock.preset_settings[preset_id.id() as usize];
+        let power_settings = settings_lock.power_settings;
+        let renderer_settings = settings_lock.renderer_settings;
+        let active_playlist = settings_lock.active_playlist;
+        let active_clip = settings_lock.active_clip;
+        let layout = settings_lock.layout;
+        let shown_settings = ShownSettings::from_settings(&settings_lock);
+        drop(settings_lock);
+
+        let mut timers_lock = TIMERS.get().lock().await;
+        // The end of the sunrise is shown until the lights are changed
+        if timers_lock
+            .sunrise_end
+            .is_some_and(|sunrise_end| sunrise_end != shown_settings)
+        {
+            timers_lock.sunrise_end = None;
+        }
+        let timers = *timers_lock;
+        drop(timers_lock);
+        let sleep_timer = timers.sleep;
+        let sunrise = match (
+            timers.sunrise.filter(|alarm| alarm.is_started),
+            CLOCK.get().lock().await.now_millis(),
+        ) {
+            (Some(alarm), Some(now)) => Some(SunriseRenderer::new(&alarm, now)),
+            _ if timers.sunrise_end.is_some() => Some(SunriseRenderer::ended()),
+            _ => None,
+        };
+
+        if settings_changed {
+            playlist_player = match active_playlist {
+                Some(playlist_id) => PLAYLISTS
+                    .get()
+                    .lock()
+                    .await
+                    .get(playlist_id)
+                    .cloned()
+                    .and_then(PlaylistPlayer::new),
+                None => None,
+            };
+            clip_player = match active_clip {
+                Some(clip_id) => ClipPlayer::new(clip_id).await.unwrap_or_else(|err| {
+                    log::error!("{err}");
+                    None
+                }),
+                None => None,
+            };
+            entry_end = None;
+        } else if let Some(player) = &mut playlist_player
+            && entry_end.is_some_and(|entry_end| Instant::now() >= entry_end)
+        {
+            player.advance();
+            entry_end = None;
+        }
+
+        if let Some(player) = &playlist_player {
+            let entry = player.current();
+            preset_id = entry.preset_id;
+            preset_settings = entry.preset_settings;
+            entry_end.get_or_insert_with(|| Instant::now() + player.duration());
+        }
+
+        let frame_time = match &clip_player {
+            Some(player) => player.frame_time(),
+            None => renderer_settings.frame_time(),
+        };
+        // The frame rate is measured again, as a clip may render at its own rate
+        STATS.get().lock().await.frame_interval_us = 0;
+
+        // The sunrise lights up the strip even if it has been turned off
+        if !is_on && sunrise.is_none() {
             draw_black(&mut leds);
             loop {
                 if SHOULD_UPDATE.load(Ordering::Relaxed) {
                     break;
                 }
-                Timer::after(FRAME_TIME).await;
+                Timer::after(frame_time).await;
             }
             continue;
         }
 
-        let settings_lock = SETTINGS.get().lock().await;
-        let preset_id = settings_lock.current_preset_id;
-        let preset_settings = settings_lock.preset_settings[preset_id.id() as usize];
-        drop(settings_lock);
-
-        let res = match preset_id.id() {
-            0 => static_color::StaticColorPreset::run(&mut leds, &preset_settings).await,
-            1 => dynamic_color::DynamicColorPreset::run(&mut leds, &preset_settings).await,
-            2 => running_rainbow::RunningRainbowPreset::run(&mut leds, &preset_settings).await,
-            3 => fire::FirePreset::run(&mut leds, &preset_settings).await,
-            _ => unreachable!(),
+        let script = SCRIPT.get().lock().await.clone();
+        let context = PresetContext {
+            frame_time,
+            layout,
+            script: &script,
         };
+        let palettes = PALETTES.get().lock().await;
+        let palette_for =
+            |settings: &PresetSettings| palettes.get(settings.palette).cloned().unwrap_or_default();
+        let mut preset = new_preset(
+            preset_id,
+            &preset_settings,
+            &palette_for(&preset_settings),
+            &context,
+        );
+        let mut layers: Vec<LayerRenderer> = LAYERS
+            .get()
+            .lock()
+            .await
+            .layers
+            .iter()
+            .map(|layer| LayerRenderer::new(layer, &palette_for(&layer.preset_settings), &context))
+            .collect();
+        drop(palettes);
+        let mut ticker = Ticker::every(frame_time);
+        let mut last_frame_start = None;
+
+        while !SHOULD_UPDATE.load(Ordering::Relaxed)
+            && entry_end.is_none_or(|entry_end| Instant::now() < entry_end)
+        {
+            let frame_start = Instant::now();
+            let frame_interval =
+                last_frame_start.map(|last_frame_start| frame_start - last_frame_start);
+            last_frame_start = Some(frame_start);
+
+            match &sunrise {
+                Some(sunrise) => sunrise.render(&mut high_precision_frame),
+                None => {
+                    match &mut clip_player {
+                        Some(player) => {
+                            if let Err(err) = player.render(&mut frame).await {
+                                log::error!("{err}");
+                            }
+                        }
+                        None => preset.render(&mut frame),
+                    }
+                    apply_brightness(
+                        &frame,
+                        preset_settings.brightness,
+                        &mut high_precision_frame,
+                    );
+                    for layer in &mut layers {
+                        layer.render_onto(&mut high_precision_frame);
+                    }
+                }
+            }
+            if let Some(sleep_timer) = &sleep_timer {
+                fade(&mut high_precision_frame, sleep_timer.level());
+            }
+
+            let (power_ma, power_limited) =
+                power::limit_power(&mut high_precision_frame, &power_settings);
+
+            match renderer_settings.dithering {
+                true => ditherer.quantize(&high_precision_frame, &mut output_frame),
+                false => dither::quantize_rounded(&high_precision_frame, &mut output_frame),
+            }
 
-        if let Err(err) = res {
-            log::error!("{err}");
+            let render_time = frame_start.elapsed();
+
+            if let Err(err) = draw_frame(&mut leds, &output_frame) {
+                log::error!("{err}");
+            }
+
+            let total_time = frame_start.elapsed();
+
+            let mut stats = STATS.get().lock().await;
+            stats.power_ma = power_ma;
+            stats.power_limited = power_limited;
+            if let Some(frame_interval) = frame_interval {
+                stats.record_frame_interval(frame_interval);
+            }
+            stats.record_frame(
+                render_time,
+                total_time - render_time,
+                total_time > frame_time,
+            );
+            drop(stats);
+
+            ticker.next().await;
         }
     }
 }
 
+fn apply_brightness(frame: &Frame, brightness: u8, output: &mut HighPrecisionFrame) {
+    frame
+        .iter()
+        .flatten()
+        .zip(output.iter_mut().flatten())
+        .for_each(|(&value, out)| *out = dither::expand(value, brightness));
+}
+
+fn fade(frame: &mut HighPrecisionFrame, level: u16) {
+    frame
+        .iter_mut()
+        .flatten()
+        .for_each(|value| *value = (*value as u32 * level as u32 / u16::MAX as u32) as u16);
+}
+
+fn draw_frame(leds: &mut LedsAdapter, frame: &Frame) -> Result<()> {
+    leds.write(frame.iter().copied())
+        .map_err(|_| Error::LedAdapterWrite)
+}
+
 fn draw_black(leds: &mut LedsAdapter) {
     leds.write(core::iter::repeat_n([0, 0, 0], LED_COUNT))
         .unwrap();
@@ -1,6 +1,18 @@
 // The implementation of Perlin noise is taken from the aurduino FastLED library
 
-use crate::presets::utils::{fade_u8, grad_u8, lerp_i8};
+use crate::presets::utils::{fade_u8, grad_u8, grad3_u8, lerp_i8};
+
+/// Offset added to the coordinates of every octave, so that octaves do not line up at the origin
+const OCTAVE_OFFSET: u16 = 0x3a7d;
+
+/// Maps the speed setting of a preset to the step its noise time coordinate advances each frame
+pub fn time_step(speed: u8) -> u16 {
+    if speed > 0 {
+        (speed / 8).clamp(1, 31) as u16
+    } else {
+        0
+    }
+}
 
 pub struct PerlinNoise {
     permutation_table: [u8; 256],
@@ -85,8 +97,99 @@ impl PerlinNoise {
     }
 
     pub fn get_u8_2d(&self, x: u16, y: u16) -> u8 {
-        let mut result = self.get_i8_2d(x, y) as i16;
-        result += 64;
-        ((result as u16) << 1) as u8
+        to_u8(self.get_i8_2d(x, y))
+    }
+
+    pub fn get_i8_3d(&self, x: u16, y: u16, z: u16) -> i8 {
+        let p = |idx: u8| self.permutation_table[idx as usize];
+
+        let x_ = (x >> 8) as u8;
+        let y_ = (y >> 8) as u8;
+        let z_ = (z >> 8) as u8;
+
+        let a = p(x_).wrapping_add(y_);
+        let aa = p(a).wrapping_add(z_);
+        let ab = p(a.wrapping_add(1)).wrapping_add(z_);
+        let b = p(x_.wrapping_add(1)).wrapping_add(y_);
+        let ba = p(b).wrapping_add(z_);
+        let bb = p(b.wrapping_add(1)).wrapping_add(z_);
+
+        let u = fade_u8(x as u8);
+        let v = fade_u8(y as u8);
+        let w = fade_u8(z as u8);
+
+        let xx = (((x as u8) >> 1) & 0x7f) as i8;
+        let yy = (((y as u8) >> 1) & 0x7f) as i8;
+        let zz = (((z as u8) >> 1) & 0x7f) as i8;
+        let n = 0x80u8 as i8;
+
+        let x1 = lerp_i8(
+            grad3_u8(p(aa), xx, yy, zz),
+            grad3_u8(p(ba), xx.wrapping_sub(n), yy, zz),
+            u,
+        );
+        let x2 = lerp_i8(
+            grad3_u8(p(ab), xx, yy.wrapping_sub(n), zz),
+            grad3_u8(p(bb), xx.wrapping_sub(n), yy.wrapping_sub(n), zz),
+            u,
+        );
+        let x3 = lerp_i8(
+            grad3_u8(p(aa.wrapping_add(1)), xx, yy, zz.wrapping_sub(n)),
+            grad3_u8(
+                p(ba.wrapping_add(1)),
+                xx.wrapping_sub(n),
+                yy,
+                zz.wrapping_sub(n),
+            ),
+            u,
+        );
+        let x4 = lerp_i8(
+            grad3_u8(
+                p(ab.wrapping_add(1)),
+                xx,
+                yy.wrapping_sub(n),
+                zz.wrapping_sub(n),
+            ),
+            grad3_u8(
+                p(bb.wrapping_add(1)),
+                xx.wrapping_sub(n),
+                yy.wrapping_sub(n),
+                zz.wrapping_sub(n),
+            ),
+            u,
+        );
+
+        lerp_i8(lerp_i8(x1, x2, v), lerp_i8(x3, x4, v), w)
+    }
+
+    pub fn get_u8_3d(&self, x: u16, y: u16, z: u16) -> u8 {
+        to_u8(self.get_i8_3d(x, y, z))
+    }
+
+    /// Fractal noise: sums `octaves` layers of 3-D noise, each with twice the frequency and half
+    /// the amplitude of the previous one, which adds finer detail to the base noise field
+    pub fn get_u8_3d_octaves(&self, x: u16, y: u16, z: u16, octaves: u8) -> u8 {
+        let mut sum: i32 = 0;
+        let mut total_weight: i32 = 0;
+        let mut weight: i32 = 256;
+
+        for octave in 0..octaves.max(1) {
+            let offset = OCTAVE_OFFSET.wrapping_mul(octave as u16);
+            let noise = self.get_i8_3d(
+                (x << octave).wrapping_add(offset),
+                (y << octave).wrapping_add(offset),
+                z.wrapping_add(offset),
+            );
+            sum += noise as i32 * weight;
+            total_weight += weight;
+            weight /= 2;
+        }
+
+        to_u8((sum / total_weight) as i8)
     }
 }
+
+fn to_u8(noise: i8) -> u8 {
+    let result = noise as i16 + 64;
+    ((result as u16) << 1) as u8
+}
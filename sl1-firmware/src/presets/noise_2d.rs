@@ -0,0 +1,53 @@
+use crate::layout::MatrixLayout;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::noise::{PerlinNoise, time_step};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Noise 2D",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Scale", 48),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+/// Noise field sampled at the coordinates of every pixel of the panel, drifting diagonally
+pub struct Noise2dPreset {
+    layout: MatrixLayout,
+    palette: Palette,
+    scale: u16,
+    perlin: PerlinNoise,
+    speed_mult: u16,
+    time: u16,
+}
+
+impl Preset for Noise2dPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, context: &PresetContext) -> Self {
+        Self {
+            layout: context.layout,
+            palette: palette.clone(),
+            scale: preset_settings.scale as u16,
+            perlin: PerlinNoise::default(),
+            speed_mult: time_step(preset_settings.speed),
+            time: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        // LEDs past the end of a panel smaller than the strip stay dark
+        frame.fill([0; 3]);
+        for (x, y, idx) in self.layout.pixels() {
+            let noise = self.perlin.get_u8_2d(
+                (x as u16 * self.scale).wrapping_add(self.time),
+                (y as u16 * self.scale).wrapping_add(self.time / 2),
+            );
+            frame[idx] = self.palette.color_at(noise);
+        }
+
+        self.time = self.time.wrapping_add(self.speed_mult);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::noise::{PerlinNoise, time_step};
+use crate::presets::utils::scale_color;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Ocean",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Scale", 32),
+        ParamInfo::palette(ParamId::Palette, "Palette", 2),
+    ],
+};
+
+pub struct OceanPreset {
+    palette: Palette,
+    scale: u16,
+    perlin: PerlinNoise,
+    speed_mult: u16,
+    time: u16,
+}
+
+impl Preset for OceanPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            palette: palette.clone(),
+            scale: preset_settings.scale as u16,
+            perlin: PerlinNoise::default(),
+            speed_mult: time_step(preset_settings.speed),
+            time: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        // Waves travel along the strip while their shape slowly changes, a finer noise layer
+        // modulates the brightness to form the crests
+        let wave_offset = self.time.wrapping_mul(4);
+        frame.iter_mut().enumerate().for_each(|(led_idx, led)| {
+            let x = led_idx as u16 * self.scale;
+            let wave = self
+                .perlin
+                .get_u8_2d(x.wrapping_add(wave_offset), self.time / 2);
+            let crest = self.perlin.get_u8_3d(x.wrapping_mul(2), self.time, 0x1000);
+            *led = scale_color(&self.palette.color_at(wave), 128 + crest / 2);
+        });
+
+        self.time = self.time.wrapping_add(self.speed_mult);
+    }
+}
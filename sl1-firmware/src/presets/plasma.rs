@@ -0,0 +1,49 @@
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::noise::{PerlinNoise, time_step};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Plasma",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Scale", 32),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+pub struct PlasmaPreset {
+    palette: Palette,
+    scale: u16,
+    perlin: PerlinNoise,
+    speed_mult: u16,
+    time: u16,
+}
+
+impl Preset for PlasmaPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            palette: palette.clone(),
+            scale: preset_settings.scale as u16,
+            perlin: PerlinNoise::default(),
+            speed_mult: time_step(preset_settings.speed),
+            time: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        // The palette slowly rotates on top of the moving noise field
+        let hue_shift = (self.time >> 4) as u8;
+        frame.iter_mut().enumerate().for_each(|(led_idx, led)| {
+            let x = led_idx as u16 * self.scale;
+            let noise = self
+                .perlin
+                .get_u8_3d_octaves(x, self.time, self.time / 3, 2);
+            *led = self.palette.color_at(noise.wrapping_add(hue_shift));
+        });
+
+        self.time = self.time.wrapping_add(self.speed_mult);
+    }
+}
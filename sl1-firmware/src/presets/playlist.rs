@@ -0,0 +1,57 @@
+use embassy_time::{Duration, Instant};
+
+use crate::MAX_PLAYLIST_ENTRIES;
+use crate::playlists::{Playlist, PlaylistEntry};
+use crate::presets::rng::Rng;
+
+/// Keeps track of the entry of the active playlist that is being shown
+pub struct PlaylistPlayer {
+    playlist: Playlist,
+    order: heapless::Vec<u8, MAX_PLAYLIST_ENTRIES>,
+    position: usize,
+    rng: Rng,
+}
+
+impl PlaylistPlayer {
+    pub fn new(playlist: Playlist) -> Option<Self> {
+        if playlist.entries.is_empty() {
+            return None;
+        }
+
+        let mut player = Self {
+            order: (0..playlist.entries.len() as u8).collect(),
+            playlist,
+            position: 0,
+            rng: Rng::new(Instant::now().as_ticks() as u32),
+        };
+        player.shuffle();
+        Some(player)
+    }
+
+    pub fn current(&self) -> &PlaylistEntry {
+        &self.playlist.entries[self.order[self.position] as usize]
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.current().duration()
+    }
+
+    pub fn advance(&mut self) {
+        self.position += 1;
+        if self.position == self.order.len() {
+            self.position = 0;
+            self.shuffle();
+        }
+    }
+
+    /// Reorders the entries with a Fisher-Yates shuffle if the playlist asks for it
+    fn shuffle(&mut self) {
+        if !self.playlist.shuffle {
+            return;
+        }
+        for idx in (1..self.order.len()).rev() {
+            let other = self.rng.next_u32() as usize % (idx + 1);
+            self.order.swap(idx, other);
+        }
+    }
+}
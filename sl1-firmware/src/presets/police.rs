@@ -0,0 +1,59 @@
+use embassy_time::Instant;
+
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+const RED: [u8; 3] = [255, 0, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+
+/// Each cycle is split into 8 slots: two red flashes followed by two blue flashes
+const CYCLE_SLOTS: u64 = 8;
+/// A flash and the pause after it take two slots, so slots are never shorter than this to keep
+/// the flashing at or below three flashes per second (WCAG 2.3.1)
+const MIN_SLOT_MS: u64 = 167;
+const MAX_SLOT_MS: u64 = 500;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Police",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Segments", 0),
+    ],
+};
+
+pub struct PolicePreset {
+    start: Instant,
+    slot_ms: u64,
+    segments: usize,
+}
+
+impl Preset for PolicePreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            start: Instant::now(),
+            slot_ms: MAX_SLOT_MS - (MAX_SLOT_MS - MIN_SLOT_MS) * preset_settings.speed as u64 / 255,
+            segments: 2 * (1 + preset_settings.scale as usize / 64),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let slot = self.start.elapsed().as_millis() / self.slot_ms % CYCLE_SLOTS;
+        let (color, lit_segment) = match slot {
+            0 | 2 => (RED, 0),
+            4 | 6 => (BLUE, 1),
+            _ => ([0; 3], 0),
+        };
+
+        frame.iter_mut().enumerate().for_each(|(idx, led)| {
+            let segment = idx * self.segments / LED_COUNT;
+            *led = match segment % 2 == lit_segment {
+                true => color,
+                false => [0; 3],
+            };
+        });
+    }
+}
@@ -0,0 +1,25 @@
+use crate::presets::HighPrecisionFrame;
+use crate::settings::PowerSettings;
+use crate::{LED_COUNT, LED_IDLE_CURRENT_MA};
+
+/// Estimates the current drawn by the strip while showing `frame` and, if it exceeds the budget
+/// from `power_settings`, scales the whole frame down to fit. Returns the estimated current in mA
+/// after limiting and whether the frame has been dimmed.
+pub fn limit_power(frame: &mut HighPrecisionFrame, power_settings: &PowerSettings) -> (u32, bool) {
+    let idle_current = LED_COUNT as u32 * LED_IDLE_CURRENT_MA;
+    let channel_sum: u32 = frame.iter().flatten().map(|&v| v as u32).sum();
+    let active_current = channel_sum / 255 * power_settings.channel_current as u32 / 257;
+    let budget = power_settings.max_current as u32;
+
+    if budget == 0 || active_current == 0 || idle_current + active_current <= budget {
+        return (idle_current + active_current, false);
+    }
+
+    let available_current = budget.saturating_sub(idle_current);
+    frame
+        .iter_mut()
+        .flatten()
+        .for_each(|v| *v = (*v as u32 * available_current / active_current) as u16);
+
+    (idle_current + available_current, true)
+}
@@ -0,0 +1,52 @@
+use crate::layout::MatrixLayout;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Rainbow 2D",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 255),
+        ParamInfo::u8(ParamId::Scale, "Scale", 0),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+/// Running rainbow moving diagonally across the panel
+pub struct Rainbow2dPreset {
+    layout: MatrixLayout,
+    palette: Palette,
+    speed_mult: u8,
+    scale_factor: usize,
+    step: u8,
+}
+
+impl Preset for Rainbow2dPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, context: &PresetContext) -> Self {
+        Self {
+            layout: context.layout,
+            palette: palette.clone(),
+            speed_mult: 128u8.wrapping_sub(preset_settings.speed),
+            scale_factor: preset_settings.scale as usize * 2,
+            step: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        // LEDs past the end of a panel smaller than the strip stay dark
+        frame.fill([0; 3]);
+        let frame_wheel_pos = self.step.wrapping_mul(self.speed_mult);
+        let (width, height) = self.layout.dimensions();
+        let diagonal = width as usize + height as usize;
+        for (x, y, idx) in self.layout.pixels() {
+            let distance = x as usize + y as usize;
+            let wheel_pos = ((distance * self.scale_factor / diagonal % 256) as u8)
+                .wrapping_add(frame_wheel_pos);
+            frame[idx] = self.palette.color_at(wheel_pos);
+        }
+
+        self.step = self.step.wrapping_add(1);
+    }
+}
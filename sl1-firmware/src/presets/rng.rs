@@ -0,0 +1,7 @@
+use crate::RANDOM_SEED;
+
+pub use sl1_protocol::effects::rng::Rng;
+
+/// Every preset instance starts from the same seed, so a preset renders the same sequence of
+/// frames each time it is started
+pub const PRESET_SEED: u32 = RANDOM_SEED as u32;
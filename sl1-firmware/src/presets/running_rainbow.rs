@@ -1,41 +1,44 @@
-use core::sync::atomic::Ordering;
-
-use embassy_time::Ticker;
-use smart_leds_trait::SmartLedsWrite;
-
-use crate::presets::Preset;
-use crate::presets::utils::{color_wheel, dim};
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::{Frame, Preset, PresetContext};
 use crate::settings::PresetSettings;
-use crate::{Error, FRAME_TIME, LED_COUNT, LedsAdapter, Result, SHOULD_UPDATE};
 
-pub struct RunningRainbowPreset {}
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Running Rainbow",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 255),
+        ParamInfo::u8(ParamId::Scale, "Scale", 0),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+pub struct RunningRainbowPreset {
+    palette: Palette,
+    speed_mult: u8,
+    scale_factor: usize,
+    step: u8,
+}
 
 impl Preset for RunningRainbowPreset {
-    async fn run(leds: &mut LedsAdapter, preset_settings: &PresetSettings) -> Result<()> {
-        let mut ticker = Ticker::every(FRAME_TIME);
-        let mut strip = [[0; 3]; LED_COUNT];
-        let speed_mult = 128u8.wrapping_sub(preset_settings.speed);
-        let scale_factor: usize = preset_settings.scale as usize * 2;
-
-        loop {
-            for i in 0..=255 as u8 {
-                let frame_wheel_pos = i.wrapping_mul(speed_mult);
-                #[allow(clippy::needless_range_loop)]
-                for idx in 0..LED_COUNT {
-                    let wheel_pos = ((idx * scale_factor / LED_COUNT % 256) as u8)
-                        .wrapping_add(frame_wheel_pos);
-                    strip[idx] = dim(&color_wheel(wheel_pos), 255 - preset_settings.brightness);
-                }
-
-                leds.write(strip.into_iter())
-                    .map_err(|_| Error::LedAdapterWrite)?;
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            palette: palette.clone(),
+            speed_mult: 128u8.wrapping_sub(preset_settings.speed),
+            scale_factor: preset_settings.scale as usize * 2,
+            step: 0,
+        }
+    }
 
-                if SHOULD_UPDATE.load(Ordering::Relaxed) {
-                    return Ok(());
-                }
+    fn render(&mut self, frame: &mut Frame) {
+        let frame_wheel_pos = self.step.wrapping_mul(self.speed_mult);
+        frame.iter_mut().enumerate().for_each(|(idx, led)| {
+            let wheel_pos =
+                ((idx * self.scale_factor / LED_COUNT % 256) as u8).wrapping_add(frame_wheel_pos);
+            *led = self.palette.color_at(wheel_pos);
+        });
 
-                ticker.next().await;
-            }
-        }
+        self.step = self.step.wrapping_add(1);
     }
 }
@@ -0,0 +1,77 @@
+use embassy_time::Instant;
+use sl1_protocol::script::{Environment, Inputs, run};
+
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::noise::PerlinNoise;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::scripts::Script;
+use crate::settings::PresetSettings;
+use crate::{LED_COUNT, SCRIPT_INSTRUCTION_BUDGET};
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Script",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Scale", 128),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+struct ScriptEnvironment {
+    palette: Palette,
+    perlin: PerlinNoise,
+}
+
+impl Environment for ScriptEnvironment {
+    fn noise(&self, x: u16, y: u16) -> u8 {
+        self.perlin.get_u8_2d(x, y)
+    }
+
+    fn palette(&self, position: u8) -> [u8; 3] {
+        self.palette.color_at(position)
+    }
+}
+
+/// Runs the script uploaded by the desktop app for every pixel
+pub struct ScriptPreset {
+    script: Script,
+    env: ScriptEnvironment,
+    speed: u8,
+    scale: u8,
+    start: Instant,
+}
+
+impl Preset for ScriptPreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, context: &PresetContext) -> Self {
+        Self {
+            script: context.script.clone(),
+            env: ScriptEnvironment {
+                palette: palette.clone(),
+                perlin: PerlinNoise::default(),
+            },
+            speed: preset_settings.speed,
+            scale: preset_settings.scale,
+            start: Instant::now(),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let mut inputs = Inputs {
+            index: 0,
+            count: LED_COUNT as i32,
+            time: self.start.elapsed().as_millis() as i32,
+            speed: self.speed as i32,
+            scale: self.scale as i32,
+        };
+        let mut budget = SCRIPT_INSTRUCTION_BUDGET;
+
+        for (idx, led) in frame.iter_mut().enumerate() {
+            inputs.index = idx as i32;
+            // Pixels that do not fit into the budget are left black, so that they do not keep their
+            // color from the last frame
+            *led = run(&self.script.code, &inputs, &self.env, &mut budget).unwrap_or([0; 3]);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use sl1_protocol::effects::sparkle::Sparkle;
+
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::rng::PRESET_SEED;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Sparkle",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::color(ParamId::Color, "Color", [0, 0, 64]),
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Density", 32),
+    ],
+};
+
+pub struct SparklePreset {
+    sparkle: Sparkle<LED_COUNT>,
+}
+
+impl Preset for SparklePreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            sparkle: Sparkle::new(
+                preset_settings.color,
+                preset_settings.speed,
+                preset_settings.scale,
+                PRESET_SEED,
+            ),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.sparkle.render(frame);
+    }
+}
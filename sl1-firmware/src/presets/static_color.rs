@@ -1,33 +1,28 @@
-use core::sync::atomic::Ordering;
-
-use embassy_time::Ticker;
-use smart_leds_trait::SmartLedsWrite;
-
-use crate::presets::Preset;
-use crate::presets::utils::{color_wheel, dim, whiten};
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::{Frame, Preset, PresetContext};
 use crate::settings::PresetSettings;
-use crate::{Error, FRAME_TIME, LED_COUNT, LedsAdapter, Result, SHOULD_UPDATE};
-
-pub struct StaticColorPreset {}
-
-impl Preset for StaticColorPreset {
-    async fn run(leds: &mut LedsAdapter, preset_settings: &PresetSettings) -> Result<()> {
-        let mut ticker = Ticker::every(FRAME_TIME);
 
-        let color = dim(
-            &whiten(&color_wheel(preset_settings.scale), preset_settings.speed),
-            255 - preset_settings.brightness,
-        );
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Static Color",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::color(ParamId::Color, "Color", [255, 255, 255]),
+    ],
+};
 
-        loop {
-            leds.write([color; LED_COUNT].into_iter())
-                .map_err(|_| Error::LedAdapterWrite)?;
-
-            if SHOULD_UPDATE.load(Ordering::Relaxed) {
-                return Ok(());
-            }
+pub struct StaticColorPreset {
+    color: [u8; 3],
+}
 
-            ticker.next().await;
+impl Preset for StaticColorPreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            color: preset_settings.color,
         }
     }
+
+    fn render(&mut self, frame: &mut Frame) {
+        frame.fill(self.color);
+    }
 }
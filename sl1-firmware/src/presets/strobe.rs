@@ -0,0 +1,56 @@
+use embassy_time::Instant;
+
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+const MIN_FREQUENCY_MILLIHZ: u64 = 500;
+/// More than three flashes per second can trigger seizures (WCAG 2.3.1), so this is never
+/// exceeded regardless of settings
+const MAX_FREQUENCY_MILLIHZ: u64 = 3_000;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Strobe",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::color(ParamId::Color, "Color", [255, 255, 255]),
+        ParamInfo::u8(ParamId::Speed, "Frequency", 64),
+        ParamInfo::u8(ParamId::Scale, "Duty", 32),
+    ],
+};
+
+/// Flashes are timed by the clock rather than by frames, so that the frequency cap holds for any
+/// frame rate
+pub struct StrobePreset {
+    color: [u8; 3],
+    start: Instant,
+    period_us: u64,
+    on_time_us: u64,
+}
+
+impl Preset for StrobePreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, context: &PresetContext) -> Self {
+        let frequency_millihz = MIN_FREQUENCY_MILLIHZ
+            + (MAX_FREQUENCY_MILLIHZ - MIN_FREQUENCY_MILLIHZ) * preset_settings.speed as u64 / 255;
+        let period_us = 1_000_000_000 / frequency_millihz;
+        // Flashes shorter than a frame could fall between two frames and never be shown
+        let on_time_us = (period_us * (preset_settings.scale as u64 + 1) / 256)
+            .max(context.frame_time.as_micros());
+
+        Self {
+            color: preset_settings.color,
+            start: Instant::now(),
+            period_us,
+            on_time_us,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let phase = self.start.elapsed().as_micros() % self.period_us;
+        frame.fill(match phase < self.on_time_us {
+            true => self.color,
+            false => [0; 3],
+        });
+    }
+}
@@ -0,0 +1,56 @@
+use embassy_time::{Duration, Instant};
+
+use crate::presets::HighPrecisionFrame;
+use crate::presets::utils::lerp_color;
+use crate::timers::SunriseAlarm;
+
+/// Colors the sunrise passes through, from deep red through orange to warm white
+const SUNRISE_COLORS: [[u8; 3]; 4] = [[255, 0, 0], [255, 48, 0], [255, 120, 24], [255, 190, 110]];
+
+/// Draws the sunrise in place of the current preset, regardless of whether the lights are on
+pub struct SunriseRenderer {
+    created_at: Instant,
+    /// Time the sunrise had been running for when the renderer was created
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl SunriseRenderer {
+    pub fn new(alarm: &SunriseAlarm, unix_millis: u64) -> Self {
+        Self {
+            created_at: Instant::now(),
+            elapsed: Duration::from_millis(unix_millis.saturating_sub(alarm.starts_at() * 1000)),
+            duration: Duration::from_secs(alarm.duration as u64 * 60),
+        }
+    }
+
+    /// Keeps showing the last color of a sunrise that is over
+    pub fn ended() -> Self {
+        Self {
+            created_at: Instant::now(),
+            elapsed: Duration::from_secs(1),
+            duration: Duration::from_secs(1),
+        }
+    }
+
+    /// Progress of the sunrise, from 0 to `u16::MAX`
+    fn progress(&self) -> u32 {
+        let elapsed = self.elapsed + self.created_at.elapsed();
+        let progress = elapsed.as_millis() * u16::MAX as u64 / self.duration.as_millis().max(1);
+        progress.min(u16::MAX as u64) as u32
+    }
+
+    pub fn render(&self, frame: &mut HighPrecisionFrame) {
+        let progress = self.progress();
+
+        let position = progress * (SUNRISE_COLORS.len() as u32 - 1);
+        let idx = (position >> 16) as usize;
+        let frac = (position >> 8) as u8;
+        let color = lerp_color(&SUNRISE_COLORS[idx], &SUNRISE_COLORS[idx + 1], frac);
+
+        // Brightness rises quadratically, as the eye is more sensitive to changes in dim light
+        let level = progress * progress / u16::MAX as u32;
+        let color = color.map(|channel| (channel as u32 * level / u8::MAX as u32) as u16);
+        frame.fill(color);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Theater Chase",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::color(ParamId::Color, "Color", [255, 255, 255]),
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Spacing", 32),
+    ],
+};
+
+pub struct TheaterChasePreset {
+    color: [u8; 3],
+    spacing: usize,
+    wait_cycles: u8,
+    cycle: u8,
+    offset: usize,
+}
+
+impl Preset for TheaterChasePreset {
+    fn new(preset_settings: &PresetSettings, _palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            color: preset_settings.color,
+            spacing: 2 + preset_settings.scale as usize / 32,
+            wait_cycles: (u8::MAX - preset_settings.speed) / 8 + 1,
+            cycle: 0,
+            offset: 0,
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        frame.iter_mut().enumerate().for_each(|(idx, led)| {
+            *led = match (idx + self.offset) % self.spacing {
+                0 => self.color,
+                _ => [0; 3],
+            };
+        });
+
+        self.cycle += 1;
+        if self.cycle >= self.wait_cycles {
+            self.cycle = 0;
+            self.offset = (self.offset + 1) % self.spacing;
+        }
+    }
+}
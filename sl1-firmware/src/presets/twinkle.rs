@@ -0,0 +1,37 @@
+use sl1_protocol::effects::twinkle::Twinkle;
+
+use crate::LED_COUNT;
+use crate::palettes::Palette;
+use crate::presets::info::{BRIGHTNESS, ParamId, ParamInfo, PresetInfo};
+use crate::presets::rng::PRESET_SEED;
+use crate::presets::{Frame, Preset, PresetContext};
+use crate::settings::PresetSettings;
+
+pub const INFO: PresetInfo = PresetInfo {
+    name: "Twinkle",
+    params: &[
+        BRIGHTNESS,
+        ParamInfo::u8(ParamId::Speed, "Speed", 128),
+        ParamInfo::u8(ParamId::Scale, "Density", 64),
+        ParamInfo::palette(ParamId::Palette, "Palette", 1),
+    ],
+};
+
+pub struct TwinklePreset {
+    palette: Palette,
+    twinkle: Twinkle<LED_COUNT>,
+}
+
+impl Preset for TwinklePreset {
+    fn new(preset_settings: &PresetSettings, palette: &Palette, _context: &PresetContext) -> Self {
+        Self {
+            palette: palette.clone(),
+            twinkle: Twinkle::new(preset_settings.speed, preset_settings.scale, PRESET_SEED),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.twinkle
+            .render(frame, |position| self.palette.color_at(position));
+    }
+}
@@ -0,0 +1,75 @@
+use embassy_futures::select::{Either, select};
+use embassy_time::{Instant, Timer};
+use esp_hal::gpio::Input;
+use esp_hal::reset::software_reset;
+use serde::Serialize;
+
+use crate::{
+    Error, FACTORY_RESET_HOLD_TIME, RESET_TOKEN, RESET_TOKEN_LIFETIME, RESTART_DELAY, RNG, Result,
+    STORAGE_PARTITION_OFFSET, STORAGE_PARTITION_SIZE, storage,
+};
+
+/// Token a client has to send back to reboot or reset the device, so that a stray message can
+/// not restart it
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ResetToken {
+    token: u32,
+    #[serde(skip)]
+    issued_at: Instant,
+}
+
+impl ResetToken {
+    /// Issues a new token, replacing the previous one
+    pub async fn issue() {
+        let mut rng = *RNG.get().await;
+        let token = Self {
+            token: rng.random(),
+            issued_at: Instant::now(),
+        };
+        *RESET_TOKEN.get().lock().await = Some(token);
+    }
+
+    /// Checks the token sent by a client, every token can only be used once
+    pub async fn confirm(token: u32) -> Result<()> {
+        let issued = RESET_TOKEN
+            .get()
+            .lock()
+            .await
+            .take()
+            .ok_or(Error::InvalidResetToken)?;
+        if issued.token != token || issued.issued_at.elapsed() > RESET_TOKEN_LIFETIME {
+            return Err(Error::InvalidResetToken);
+        }
+        Ok(())
+    }
+}
+
+/// Erases the storage partition, every setting is initialized with its default value on the next
+/// boot. Clips are kept, as they are stored in a partition of their own
+pub async fn erase_settings() -> Result<()> {
+    log::warn!("Erasing settings");
+    storage::erase(STORAGE_PARTITION_OFFSET, STORAGE_PARTITION_SIZE).await
+}
+
+/// Restarts the device after giving the server a moment to send its response
+pub async fn restart() {
+    log::info!("Restarting");
+    Timer::after(RESTART_DELAY).await;
+    software_reset();
+}
+
+/// Resets the device to factory settings once the boot button has been held down for a few
+/// seconds
+#[embassy_executor::task]
+pub async fn reset_button_task(mut button: Input<'static>) -> ! {
+    loop {
+        button.wait_for_low().await;
+        let hold = Timer::after(FACTORY_RESET_HOLD_TIME);
+        if let Either::First(()) = select(hold, button.wait_for_high()).await {
+            if let Err(err) = erase_settings().await {
+                log::error!("Error erasing settings: {err}");
+            }
+            restart().await;
+        }
+    }
+}
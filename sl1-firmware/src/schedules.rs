@@ -0,0 +1,156 @@
+use core::sync::atomic::Ordering;
+
+use embassy_time::Ticker;
+use serde::{Deserialize, Serialize};
+use sl1_protocol::schedule::Trigger;
+
+use crate::settings::PresetId;
+use crate::storage::Persistent;
+use crate::{
+    CLOCK, Error, FAVORITES, MAX_FAVORITES, MAX_SCHEDULES, Result, SCHEDULE_CHECK_INTERVAL,
+    SCHEDULES, SCHEDULES_STORAGE_OFFSET, SCHEDULES_VERSION, SETTINGS, SHOULD_UPDATE, TIME_SETTINGS,
+};
+
+/// Largest jump of the clock, in seconds, for which the schedules skipped over are still run.
+/// Larger jumps happen when the clock is set for the first time or is off by a lot, running
+/// every schedule of the past hours at once would be of no use then
+const MAX_CATCH_UP_SECONDS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleAction {
+    TurnOn,
+    TurnOff,
+    Preset(u8),
+    Favorite(u8),
+    Brightness(u8),
+}
+
+/// Action run at a time of the day on the selected days of the week
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Schedule {
+    pub enabled: bool,
+    /// Days of the week, bit 0 is Monday
+    pub days: u8,
+    /// Local time of the day in minutes since midnight
+    pub minute: u16,
+    pub action: ScheduleAction,
+}
+
+impl Schedule {
+    fn trigger(&self) -> Trigger {
+        Trigger {
+            days: self.days,
+            minute: self.minute,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let is_action_valid = match self.action {
+            ScheduleAction::Preset(preset_id) => PresetId::new_fallible(preset_id).is_ok(),
+            ScheduleAction::Favorite(favorite_id) => (favorite_id as usize) < MAX_FAVORITES,
+            _ => true,
+        };
+        match self.trigger().is_valid() && is_action_valid {
+            true => Ok(()),
+            false => Err(Error::InvalidSchedule),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Schedules {
+    pub schedules: heapless::Vec<Schedule, MAX_SCHEDULES>,
+}
+
+impl Persistent for Schedules {
+    const STORAGE_OFFSET: u32 = SCHEDULES_STORAGE_OFFSET;
+    const VERSION: u32 = SCHEDULES_VERSION;
+
+    fn is_valid(&self) -> bool {
+        self.schedules
+            .iter()
+            .all(|schedule| schedule.validate().is_ok())
+    }
+}
+
+impl Schedules {
+    pub fn set(&mut self, schedules: heapless::Vec<Schedule, MAX_SCHEDULES>) -> Result<()> {
+        schedules.iter().try_for_each(Schedule::validate)?;
+        self.schedules = schedules;
+        Ok(())
+    }
+
+    /// Actions of the enabled schedules which fire after `from` and no later than `to`
+    fn due(&self, from: i64, to: i64, utc_offset: i16) -> impl Iterator<Item = ScheduleAction> {
+        self.schedules
+            .iter()
+            .filter(move |schedule| {
+                schedule.enabled && schedule.trigger().is_due(from, to, utc_offset)
+            })
+            .map(|schedule| schedule.action)
+    }
+}
+
+#[embassy_executor::task]
+pub async fn schedule_task() -> ! {
+    let mut ticker = Ticker::every(SCHEDULE_CHECK_INTERVAL);
+    let mut checked_until: Option<i64> = None;
+
+    loop {
+        ticker.next().await;
+
+        let Some(now) = CLOCK.get().lock().await.now_millis() else {
+            continue;
+        };
+        let now = (now / 1000) as i64;
+        let Some(from) = checked_until
+            .replace(now)
+            .filter(|from| (0..=MAX_CATCH_UP_SECONDS).contains(&(now - from)))
+        else {
+            continue;
+        };
+
+        let utc_offset = TIME_SETTINGS.get().lock().await.utc_offset;
+        let due: heapless::Vec<ScheduleAction, MAX_SCHEDULES> = SCHEDULES
+            .get()
+            .lock()
+            .await
+            .due(from, now, utc_offset)
+            .collect();
+        for action in due {
+            log::info!(target: "Schedules", "Running scheduled action: {action:?}");
+            if let Err(err) = run(action).await {
+                log::warn!(target: "Schedules", "Error running scheduled action: {err}");
+            }
+        }
+    }
+}
+
+async fn run(action: ScheduleAction) -> Result<()> {
+    let mut settings = SETTINGS.get().lock().await;
+    match action {
+        ScheduleAction::TurnOn => settings.is_on = true,
+        ScheduleAction::TurnOff => settings.is_on = false,
+        ScheduleAction::Preset(preset_id) => {
+            settings.current_preset_id = PresetId::new_fallible(preset_id)?;
+            settings.active_playlist = None;
+            settings.active_clip = None;
+        }
+        ScheduleAction::Favorite(favorite_id) => {
+            FAVORITES
+                .get()
+                .lock()
+                .await
+                .get(favorite_id)
+                .ok_or(Error::FavoriteNotFound)?
+                .recall(&mut settings)?;
+        }
+        ScheduleAction::Brightness(brightness) => {
+            let current_preset_id = settings.current_preset_id.id();
+            settings.preset_settings[current_preset_id as usize].brightness = brightness;
+        }
+    }
+    SHOULD_UPDATE.store(true, Ordering::Relaxed);
+    Ok(())
+}
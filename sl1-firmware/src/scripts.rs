@@ -0,0 +1,55 @@
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sl1_protocol::script::{MAX_SCRIPT_LENGTH, Op, validate};
+
+use crate::storage::Persistent;
+use crate::{Error, Result, SCRIPT_STORAGE_OFFSET, SCRIPT_VERSION};
+
+/// Compiled effect script run by the script preset, see `sl1_protocol::script`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Script {
+    pub name: heapless::String<16>,
+    pub code: heapless::Vec<u8, MAX_SCRIPT_LENGTH>,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        // palette(sin8(i * 8 + t / 16))
+        let code = [
+            Op::Index as u8,
+            Op::Push as u8,
+            8,
+            0,
+            Op::Mul as u8,
+            Op::Time as u8,
+            Op::Push as u8,
+            16,
+            0,
+            Op::Div as u8,
+            Op::Add as u8,
+            Op::Sin8 as u8,
+            Op::Palette as u8,
+        ];
+        Self {
+            name: heapless::String::from_str("Wave").unwrap(),
+            code: heapless::Vec::from_slice(&code).unwrap(),
+        }
+    }
+}
+
+impl Persistent for Script {
+    const STORAGE_OFFSET: u32 = SCRIPT_STORAGE_OFFSET;
+    const VERSION: u32 = SCRIPT_VERSION;
+
+    fn is_valid(&self) -> bool {
+        validate(&self.code).is_ok()
+    }
+}
+
+impl Script {
+    pub fn validate(self) -> Result<Self> {
+        validate(&self.code).map_err(Error::Script)?;
+        Ok(self)
+    }
+}
@@ -1,20 +1,126 @@
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::sync::atomic::Ordering;
 
+use embassy_futures::select::{Either, select};
 use embassy_net::udp::{PacketMetadata, UdpMetadata, UdpSocket};
 use embassy_net::{Runner, Stack};
-use esp_hal::reset::software_reset;
-use esp_hal::riscv::register::mstatus::set_tvm;
 use esp_wifi::wifi::{WifiDevice, WifiStaDevice};
 
+use serde::{Deserialize, Serialize};
 use sl1_protocol::{Method, Version};
 
+use crate::boot::BootSettings;
+use crate::clips::MAX_CLIP_LENGTH;
+use crate::favorites::Favorite;
+use crate::layers::Layer;
+use crate::layout::MatrixLayout;
+use crate::logger::LogSettings;
+use crate::ota::{FirmwareInfo, FirmwareUpdate, FirmwareUpdateRequest};
+use crate::palettes::Palette;
+use crate::playlists::Playlist;
+use crate::presets::info::ParamInfo;
+use crate::presets::{PRESET_COUNT, PRESET_INFO};
+use crate::reset::{self, ResetToken};
+use crate::schedules::Schedule;
+use crate::scripts::Script;
 use crate::settings::{PresetId, PresetSettings, Settings, WifiSettings};
+use crate::storage::Persistent;
+use crate::time::{TimeSettings, TimeSource};
+use crate::timers::{SleepTimer, SunriseAlarm, SunriseRequest};
 use crate::{
-    Error, MESSAGE_BUFFER_LENGTH, MINIMAL_CLIENT_MESSAGE_LENGTH, PRESET_INFO, Result, SERVER_PORT,
-    SETTINGS, SHOULD_UPDATE,
+    BOOT_SETTINGS, CLOCK, Error, FAVORITES, FIRMWARE_UPDATE, LAYERS, LED_COUNT, LOG_RECORDS,
+    LOG_SETTINGS, LOG_SUBSCRIBERS, MAX_CLIPS, MAX_FAVORITES, MAX_FRAME_RATE, MAX_LAYERS,
+    MAX_PALETTES, MAX_PLAYLISTS, MAX_SCHEDULES, MESSAGE_BUFFER_LENGTH,
+    MINIMAL_CLIENT_MESSAGE_LENGTH, PALETTES, PLAYLISTS, RESET_TOKEN, Result, SCHEDULES, SCRIPT,
+    SERVER_PORT, SETTINGS, SHOULD_UPDATE, STATS, SYNC_TIME, TIME_SETTINGS, TIMERS, UPDATE_WIFI,
+    clips,
 };
 
+/// Presets are sent one at a time, as the whole registry does not fit into a single message
+#[derive(Serialize)]
+struct PresetInfoPage {
+    id: u8,
+    count: u8,
+    name: &'static str,
+    params: &'static [ParamInfo],
+}
+
+/// Entry of the palette, playlist and favorite lists, their contents are only sent when a single
+/// item is requested
+#[derive(Serialize)]
+struct ItemSummary<'a> {
+    id: u8,
+    name: &'a str,
+}
+
+/// Palette or playlist along with the slot it is stored in
+#[derive(Serialize, Deserialize)]
+struct StoredItem<T> {
+    id: u8,
+    #[serde(flatten)]
+    item: T,
+}
+
+/// Clips stored on the device, along with the LED count and size the uploaded clips should have
+#[derive(Serialize)]
+struct ClipList {
+    led_count: u16,
+    max_length: u32,
+    clips: heapless::Vec<ClipSummary, MAX_CLIPS>,
+}
+
+#[derive(Serialize)]
+struct ClipSummary {
+    id: u8,
+    name: heapless::String<16>,
+    length: u32,
+}
+
+/// Sent once all of the chunks of a clip have been uploaded
+#[derive(Deserialize)]
+struct FinishedClip {
+    name: heapless::String<16>,
+    length: u32,
+}
+
+/// Current time of the device along with its time settings
+#[derive(Serialize)]
+struct TimeStatus<'a> {
+    /// Unix time in milliseconds, `None` until the clock has been set
+    unix_ms: Option<u64>,
+    source: TimeSource,
+    #[serde(flatten)]
+    settings: &'a TimeSettings,
+}
+
+/// Time set by a client on networks without an SNTP server
+#[derive(Deserialize)]
+struct ManualTime {
+    unix_ms: u64,
+}
+
+/// Timers armed on the device
+#[derive(Serialize)]
+struct TimerStatus {
+    /// Seconds until the lights are turned off, `None` if the sleep timer is not armed
+    sleep_remaining: Option<u64>,
+    sunrise: Option<SunriseAlarm>,
+}
+
+/// Name the current preset is saved under as a favorite
+#[derive(Deserialize)]
+struct FavoriteName {
+    name: heapless::String<16>,
+}
+
+/// Token confirming a reboot or a factory reset, sent as a little endian `u32`
+fn parse_reset_token(buf: &[u8]) -> Result<u32> {
+    let token = buf.get(2..6).ok_or(Error::InvalidMessageLength)?;
+    Ok(u32::from_le_bytes(token.try_into().unwrap()))
+}
+
 #[embassy_executor::task]
 pub async fn net_task(mut stack_runner: Runner<'static, WifiDevice<'static, WifiStaDevice>>) -> ! {
     stack_runner.run().await
@@ -31,10 +137,26 @@ enum GetClientMessage {
     Ping,
     IsOn,
     CurrentPresetId,
-    PresetInfo,
+    PresetInfo(PresetId),
     Settings,
     WifiSettings,
     CurrentPresetSettings,
+    Stats,
+    Palettes,
+    Palette(u8),
+    Playlists,
+    Playlist(u8),
+    Favorites,
+    Layers,
+    Script,
+    Clips,
+    Time,
+    Schedules,
+    Timers,
+    BootSettings,
+    ResetToken,
+    FirmwareInfo,
+    LogSettings,
 }
 
 #[derive(Clone, Debug)]
@@ -43,13 +165,46 @@ enum SetClientMessage {
     TurnOn,
     TurnOff,
     Preset(PresetId),
-    Settings(Settings),
+    Settings(Box<Settings>),
     WifiSettings(WifiSettings),
     CurrentPresetSettings(PresetSettings),
     Brightness(u8),
     Speed(u8),
     Scale(u8),
     SaveSettings,
+    FrameRate(u8),
+    Color([u8; 3]),
+    UploadPalette(u8, Palette),
+    DeletePalette(u8),
+    Palette(u8),
+    UploadPlaylist(u8, Box<Playlist>),
+    DeletePlaylist(u8),
+    Playlist(u8),
+    SaveFavorite(u8, heapless::String<16>),
+    RecallFavorite(u8),
+    DeleteFavorite(u8),
+    Layers(heapless::Vec<Layer, MAX_LAYERS>),
+    UploadScript(Box<Script>),
+    UploadClipChunk(u8, u32, Vec<u8>),
+    FinishClip(u8, heapless::String<16>, u32),
+    DeleteClip(u8),
+    PlayClip(u8),
+    Layout(MatrixLayout),
+    Time(u64),
+    TimeSettings(TimeSettings),
+    Schedules(heapless::Vec<Schedule, MAX_SCHEDULES>),
+    SleepTimer(u8),
+    CancelSleepTimer,
+    Sunrise(SunriseRequest),
+    CancelSunrise,
+    BootSettings(BootSettings),
+    Reboot(u32),
+    FactoryReset(u32),
+    BeginFirmwareUpdate(FirmwareUpdateRequest),
+    UploadFirmwareChunk(u32, Vec<u8>),
+    FinishFirmwareUpdate,
+    LogSettings(LogSettings),
+    SubscribeLogs,
 }
 
 #[allow(unreachable_patterns)]
@@ -57,60 +212,226 @@ impl ClientMessage {
     fn from_message(buf: &[u8]) -> Result<Self> {
         use ClientMessage as CM;
         use GetClientMessage as GCM;
+        use Method as M;
         use SetClientMessage as SCM;
 
-        let version = Version::try_from(buf[0])?;
+        let version = Version::try_from(buf[0]).map_err(Error::ProtocolVersion)?;
         match version {
             Version::V1 => {}
             _ => return Err(Error::UnsupportedProtocolVersion),
         };
 
-        let method = buf[1];
+        let method = Method::try_from(buf[1]).map_err(|_| Error::UnsupportedClientMessageMethod)?;
 
         match method {
-            0x01 => Ok(CM::Get(GCM::Ping)),
-            0x02 => Ok(CM::Get(GCM::IsOn)),
-            0x03 => Ok(CM::Get(GCM::CurrentPresetId)),
-            0x04 => Ok(CM::Get(GCM::PresetInfo)),
-            0x05 => Ok(CM::Get(GCM::Settings)),
-            0x06 => Ok(CM::Get(GCM::WifiSettings)),
-            0x07 => Ok(CM::Get(GCM::CurrentPresetSettings)),
-
-            0x08 => Ok(CM::Set(SCM::Toggle)),
-            0x09 => Ok(CM::Set(SCM::TurnOn)),
-            0x0a => Ok(CM::Set(SCM::TurnOff)),
-            0x0B => {
+            M::GetPing => Ok(CM::Get(GCM::Ping)),
+            M::GetIsOn => Ok(CM::Get(GCM::IsOn)),
+            M::GetCurrentPresetId => Ok(CM::Get(GCM::CurrentPresetId)),
+            M::GetPresetInfo => {
+                let preset_id = PresetId::new_fallible(*buf.get(2).unwrap_or(&0))?;
+                Ok(CM::Get(GCM::PresetInfo(preset_id)))
+            }
+            M::GetSettings => Ok(CM::Get(GCM::Settings)),
+            M::GetCurrentPresetSettings => Ok(CM::Get(GCM::CurrentPresetSettings)),
+            M::GetWifiSettings => Ok(CM::Get(GCM::WifiSettings)),
+
+            M::SetToggle => Ok(CM::Set(SCM::Toggle)),
+            M::SetTurnOn => Ok(CM::Set(SCM::TurnOn)),
+            M::SetTurnOff => Ok(CM::Set(SCM::TurnOff)),
+            M::SetPreset => {
                 let preset_id = PresetId::new_fallible(buf[2])?;
                 Ok(CM::Set(SCM::Preset(preset_id)))
             }
-            0x0C => {
-                let settings: Settings =
+            M::SetSettings => {
+                let settings: Box<Settings> =
                     serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
                 Ok(CM::Set(SCM::Settings(settings)))
             }
-            0x0D => {
+            M::SetWifiSettings => {
                 let wifi_settings: WifiSettings =
                     serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
                 Ok(CM::Set(SCM::WifiSettings(wifi_settings)))
             }
-            0x0E => {
+            M::SetCurrentPresetSettings => {
                 let preset_settings: PresetSettings =
                     serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
                 Ok(CM::Set(SCM::CurrentPresetSettings(preset_settings)))
             }
-            0x0F => {
+            M::SetBrightness => {
                 let brightness = buf[2];
                 Ok(CM::Set(SCM::Brightness(brightness)))
             }
-            0x10 => {
+            M::SetSpeed => {
                 let speed = buf[2];
                 Ok(CM::Set(SCM::Speed(speed)))
             }
-            0x11 => {
+            M::SetScale => {
                 let scale = buf[2];
                 Ok(CM::Set(SCM::Scale(scale)))
             }
-            0x12 => Ok(CM::Set(SCM::SaveSettings)),
+            M::SaveSettings => Ok(CM::Set(SCM::SaveSettings)),
+
+            M::GetStats => Ok(CM::Get(GCM::Stats)),
+            M::SetFrameRate => {
+                let frame_rate = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::FrameRate(frame_rate)))
+            }
+            M::SetColor => {
+                let color = buf
+                    .get(2..5)
+                    .and_then(|color| color.try_into().ok())
+                    .ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::Color(color)))
+            }
+            M::GetPalettes => Ok(CM::Get(GCM::Palettes)),
+            M::GetPalette => {
+                let palette_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Get(GCM::Palette(palette_id)))
+            }
+            M::UploadPalette => {
+                let message: StoredItem<Palette> =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::UploadPalette(message.id, message.item)))
+            }
+            M::DeletePalette => {
+                let palette_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::DeletePalette(palette_id)))
+            }
+            M::SetPalette => {
+                let palette_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::Palette(palette_id)))
+            }
+            M::GetPlaylists => Ok(CM::Get(GCM::Playlists)),
+            M::GetPlaylist => {
+                let playlist_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Get(GCM::Playlist(playlist_id)))
+            }
+            M::UploadPlaylist => {
+                let message: StoredItem<Box<Playlist>> =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::UploadPlaylist(message.id, message.item)))
+            }
+            M::DeletePlaylist => {
+                let playlist_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::DeletePlaylist(playlist_id)))
+            }
+            M::SetPlaylist => {
+                let playlist_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::Playlist(playlist_id)))
+            }
+            M::GetFavorites => Ok(CM::Get(GCM::Favorites)),
+            M::SaveFavorite => {
+                let message: StoredItem<FavoriteName> =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::SaveFavorite(message.id, message.item.name)))
+            }
+            M::RecallFavorite => {
+                let favorite_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::RecallFavorite(favorite_id)))
+            }
+            M::DeleteFavorite => {
+                let favorite_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::DeleteFavorite(favorite_id)))
+            }
+            M::GetLayers => Ok(CM::Get(GCM::Layers)),
+            M::SetLayers => {
+                let layers = serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::Layers(layers)))
+            }
+            M::GetScript => Ok(CM::Get(GCM::Script)),
+            M::UploadScript => {
+                let script = serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::UploadScript(script)))
+            }
+            M::GetClips => Ok(CM::Get(GCM::Clips)),
+            M::UploadClipChunk => {
+                let header = buf.get(2..7).ok_or(Error::InvalidMessageLength)?;
+                let offset = u32::from_le_bytes(header[1..].try_into().unwrap());
+                Ok(CM::Set(SCM::UploadClipChunk(
+                    header[0],
+                    offset,
+                    buf[7..].to_vec(),
+                )))
+            }
+            M::FinishClip => {
+                let message: StoredItem<FinishedClip> =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::FinishClip(
+                    message.id,
+                    message.item.name,
+                    message.item.length,
+                )))
+            }
+            M::DeleteClip => {
+                let clip_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::DeleteClip(clip_id)))
+            }
+            M::PlayClip => {
+                let clip_id = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::PlayClip(clip_id)))
+            }
+            M::SetLayout => {
+                let layout: MatrixLayout =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::Layout(layout)))
+            }
+            M::GetTime => Ok(CM::Get(GCM::Time)),
+            M::SetTime => {
+                let time: ManualTime =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::Time(time.unix_ms)))
+            }
+            M::SetTimeSettings => {
+                let time_settings: TimeSettings =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::TimeSettings(time_settings)))
+            }
+            M::GetSchedules => Ok(CM::Get(GCM::Schedules)),
+            M::SetSchedules => {
+                let schedules =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::Schedules(schedules)))
+            }
+            M::GetTimers => Ok(CM::Get(GCM::Timers)),
+            M::ArmSleepTimer => {
+                let minutes = *buf.get(2).ok_or(Error::InvalidMessageLength)?;
+                Ok(CM::Set(SCM::SleepTimer(minutes)))
+            }
+            M::CancelSleepTimer => Ok(CM::Set(SCM::CancelSleepTimer)),
+            M::ArmSunrise => {
+                let sunrise: SunriseRequest =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::Sunrise(sunrise)))
+            }
+            M::CancelSunrise => Ok(CM::Set(SCM::CancelSunrise)),
+            M::GetBootSettings => Ok(CM::Get(GCM::BootSettings)),
+            M::SetBootSettings => {
+                let boot_settings: BootSettings =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::BootSettings(boot_settings)))
+            }
+            M::GetResetToken => Ok(CM::Get(GCM::ResetToken)),
+            M::Reboot => Ok(CM::Set(SCM::Reboot(parse_reset_token(buf)?))),
+            M::FactoryReset => Ok(CM::Set(SCM::FactoryReset(parse_reset_token(buf)?))),
+            M::GetFirmwareInfo => Ok(CM::Get(GCM::FirmwareInfo)),
+            M::BeginFirmwareUpdate => {
+                let request: FirmwareUpdateRequest =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::BeginFirmwareUpdate(request)))
+            }
+            M::UploadFirmwareChunk => {
+                let offset = buf.get(2..6).ok_or(Error::InvalidMessageLength)?;
+                let offset = u32::from_le_bytes(offset.try_into().unwrap());
+                Ok(CM::Set(SCM::UploadFirmwareChunk(offset, buf[6..].to_vec())))
+            }
+            M::FinishFirmwareUpdate => Ok(CM::Set(SCM::FinishFirmwareUpdate)),
+            M::GetLogSettings => Ok(CM::Get(GCM::LogSettings)),
+            M::SetLogSettings => {
+                let log_settings: LogSettings =
+                    serde_json::from_slice(&buf[2..]).map_err(Error::Deserialization)?;
+                Ok(CM::Set(SCM::LogSettings(log_settings)))
+            }
+            M::SubscribeLogs => Ok(CM::Set(SCM::SubscribeLogs)),
 
             _ => Err(Error::UnsupportedClientMessageMethod),
         }
@@ -124,7 +445,7 @@ enum ServerMessage {
     GetPing,
     GetIsOn,
     GetCurrentPresetId,
-    GetPresetInfo,
+    GetPresetInfo(PresetId),
     GetSettings,
     GetCurrentPresetSettings,
     GetWifiSettings,
@@ -140,32 +461,132 @@ enum ServerMessage {
     SetSpeed,
     SetScale,
     SaveSettings,
+
+    GetStats,
+    SetFrameRate,
+    SetColor,
+    GetPalettes,
+    GetPalette(u8),
+    UploadPalette,
+    DeletePalette,
+    SetPalette,
+    GetPlaylists,
+    GetPlaylist(u8),
+    UploadPlaylist,
+    DeletePlaylist,
+    SetPlaylist,
+    GetFavorites,
+    SaveFavorite,
+    RecallFavorite,
+    DeleteFavorite,
+    GetLayers,
+    SetLayers,
+    GetScript,
+    UploadScript,
+    GetClips,
+    UploadClipChunk,
+    FinishClip,
+    DeleteClip,
+    PlayClip,
+    SetLayout,
+    GetTime,
+    SetTime,
+    SetTimeSettings,
+    GetSchedules,
+    SetSchedules,
+    GetTimers,
+    ArmSleepTimer,
+    CancelSleepTimer,
+    ArmSunrise,
+    CancelSunrise,
+    GetBootSettings,
+    SetBootSettings,
+    GetResetToken,
+    Reboot,
+    FactoryReset,
+    GetFirmwareInfo,
+    BeginFirmwareUpdate,
+    UploadFirmwareChunk,
+    FinishFirmwareUpdate,
+    GetLogSettings,
+    SetLogSettings,
+    SubscribeLogs,
 }
 
 impl ServerMessage {
-    fn method_id(&self) -> u8 {
+    fn method(&self) -> Method {
+        use Method as M;
         use ServerMessage as SM;
 
         match self {
-            SM::Error => 0x00,
-            SM::GetPing => 0x01,
-            SM::GetIsOn => 0x02,
-            SM::GetCurrentPresetId => 0x03,
-            SM::GetPresetInfo => 0x04,
-            SM::GetSettings => 0x05,
-            SM::GetCurrentPresetSettings => 0x06,
-            SM::GetWifiSettings => 0x07,
-            SM::SetToggle => 0x08,
-            SM::SetTurnOn => 0x09,
-            SM::SetTurnOff => 0x0A,
-            SM::SetPreset => 0x0B,
-            SM::SetSettings => 0x0C,
-            SM::SetWifiSettings => 0x0D,
-            SM::SetCurrentPresetSettings => 0x0E,
-            SM::SetBrightness => 0x0F,
-            SM::SetSpeed => 0x10,
-            SM::SetScale => 0x11,
-            SM::SaveSettings => 0x12,
+            SM::Error => M::Error,
+            SM::GetPing => M::GetPing,
+            SM::GetIsOn => M::GetIsOn,
+            SM::GetCurrentPresetId => M::GetCurrentPresetId,
+            SM::GetPresetInfo(_) => M::GetPresetInfo,
+            SM::GetSettings => M::GetSettings,
+            SM::GetCurrentPresetSettings => M::GetCurrentPresetSettings,
+            SM::GetWifiSettings => M::GetWifiSettings,
+            SM::SetToggle => M::SetToggle,
+            SM::SetTurnOn => M::SetTurnOn,
+            SM::SetTurnOff => M::SetTurnOff,
+            SM::SetPreset => M::SetPreset,
+            SM::SetSettings => M::SetSettings,
+            SM::SetWifiSettings => M::SetWifiSettings,
+            SM::SetCurrentPresetSettings => M::SetCurrentPresetSettings,
+            SM::SetBrightness => M::SetBrightness,
+            SM::SetSpeed => M::SetSpeed,
+            SM::SetScale => M::SetScale,
+            SM::SaveSettings => M::SaveSettings,
+            SM::GetStats => M::GetStats,
+            SM::SetFrameRate => M::SetFrameRate,
+            SM::SetColor => M::SetColor,
+            SM::GetPalettes => M::GetPalettes,
+            SM::GetPalette(_) => M::GetPalette,
+            SM::UploadPalette => M::UploadPalette,
+            SM::DeletePalette => M::DeletePalette,
+            SM::SetPalette => M::SetPalette,
+            SM::GetPlaylists => M::GetPlaylists,
+            SM::GetPlaylist(_) => M::GetPlaylist,
+            SM::UploadPlaylist => M::UploadPlaylist,
+            SM::DeletePlaylist => M::DeletePlaylist,
+            SM::SetPlaylist => M::SetPlaylist,
+            SM::GetFavorites => M::GetFavorites,
+            SM::SaveFavorite => M::SaveFavorite,
+            SM::RecallFavorite => M::RecallFavorite,
+            SM::DeleteFavorite => M::DeleteFavorite,
+            SM::GetLayers => M::GetLayers,
+            SM::SetLayers => M::SetLayers,
+            SM::GetScript => M::GetScript,
+            SM::UploadScript => M::UploadScript,
+            SM::GetClips => M::GetClips,
+            SM::UploadClipChunk => M::UploadClipChunk,
+            SM::FinishClip => M::FinishClip,
+            SM::DeleteClip => M::DeleteClip,
+            SM::PlayClip => M::PlayClip,
+            SM::SetLayout => M::SetLayout,
+            SM::GetTime => M::GetTime,
+            SM::SetTime => M::SetTime,
+            SM::SetTimeSettings => M::SetTimeSettings,
+            SM::GetSchedules => M::GetSchedules,
+            SM::SetSchedules => M::SetSchedules,
+            SM::GetTimers => M::GetTimers,
+            SM::ArmSleepTimer => M::ArmSleepTimer,
+            SM::CancelSleepTimer => M::CancelSleepTimer,
+            SM::ArmSunrise => M::ArmSunrise,
+            SM::CancelSunrise => M::CancelSunrise,
+            SM::GetBootSettings => M::GetBootSettings,
+            SM::SetBootSettings => M::SetBootSettings,
+            SM::GetResetToken => M::GetResetToken,
+            SM::Reboot => M::Reboot,
+            SM::FactoryReset => M::FactoryReset,
+            SM::GetFirmwareInfo => M::GetFirmwareInfo,
+            SM::BeginFirmwareUpdate => M::BeginFirmwareUpdate,
+            SM::UploadFirmwareChunk => M::UploadFirmwareChunk,
+            SM::FinishFirmwareUpdate => M::FinishFirmwareUpdate,
+            SM::GetLogSettings => M::GetLogSettings,
+            SM::SetLogSettings => M::SetLogSettings,
+            SM::SubscribeLogs => M::SubscribeLogs,
         }
     }
 
@@ -185,6 +606,39 @@ impl ServerMessage {
             SCM::Speed(_) => SM::SetSpeed,
             SCM::Scale(_) => SM::SetScale,
             SCM::SaveSettings => SM::SaveSettings,
+            SCM::FrameRate(_) => SM::SetFrameRate,
+            SCM::Color(_) => SM::SetColor,
+            SCM::UploadPalette(..) => SM::UploadPalette,
+            SCM::DeletePalette(_) => SM::DeletePalette,
+            SCM::Palette(_) => SM::SetPalette,
+            SCM::UploadPlaylist(..) => SM::UploadPlaylist,
+            SCM::DeletePlaylist(_) => SM::DeletePlaylist,
+            SCM::Playlist(_) => SM::SetPlaylist,
+            SCM::SaveFavorite(..) => SM::SaveFavorite,
+            SCM::RecallFavorite(_) => SM::RecallFavorite,
+            SCM::DeleteFavorite(_) => SM::DeleteFavorite,
+            SCM::Layers(_) => SM::SetLayers,
+            SCM::UploadScript(_) => SM::UploadScript,
+            SCM::UploadClipChunk(..) => SM::UploadClipChunk,
+            SCM::FinishClip(..) => SM::FinishClip,
+            SCM::DeleteClip(_) => SM::DeleteClip,
+            SCM::PlayClip(_) => SM::PlayClip,
+            SCM::Layout(_) => SM::SetLayout,
+            SCM::Time(_) => SM::SetTime,
+            SCM::TimeSettings(_) => SM::SetTimeSettings,
+            SCM::Schedules(_) => SM::SetSchedules,
+            SCM::SleepTimer(_) => SM::ArmSleepTimer,
+            SCM::CancelSleepTimer => SM::CancelSleepTimer,
+            SCM::Sunrise(_) => SM::ArmSunrise,
+            SCM::CancelSunrise => SM::CancelSunrise,
+            SCM::BootSettings(_) => SM::SetBootSettings,
+            SCM::Reboot(_) => SM::Reboot,
+            SCM::FactoryReset(_) => SM::FactoryReset,
+            SCM::BeginFirmwareUpdate(_) => SM::BeginFirmwareUpdate,
+            SCM::UploadFirmwareChunk(..) => SM::UploadFirmwareChunk,
+            SCM::FinishFirmwareUpdate => SM::FinishFirmwareUpdate,
+            SCM::LogSettings(_) => SM::SetLogSettings,
+            SCM::SubscribeLogs => SM::SubscribeLogs,
         }
     }
 
@@ -196,16 +650,54 @@ impl ServerMessage {
             GCM::Ping => SM::GetPing,
             GCM::IsOn => SM::GetIsOn,
             GCM::CurrentPresetId => SM::GetCurrentPresetId,
-            GCM::PresetInfo => SM::GetPresetInfo,
+            GCM::PresetInfo(preset_id) => SM::GetPresetInfo(*preset_id),
             GCM::Settings => SM::GetSettings,
             GCM::WifiSettings => SM::GetWifiSettings,
             GCM::CurrentPresetSettings => SM::GetCurrentPresetSettings,
+            GCM::Stats => SM::GetStats,
+            GCM::Palettes => SM::GetPalettes,
+            GCM::Palette(palette_id) => SM::GetPalette(*palette_id),
+            GCM::Playlists => SM::GetPlaylists,
+            GCM::Playlist(playlist_id) => SM::GetPlaylist(*playlist_id),
+            GCM::Favorites => SM::GetFavorites,
+            GCM::Layers => SM::GetLayers,
+            GCM::Script => SM::GetScript,
+            GCM::Clips => SM::GetClips,
+            GCM::Time => SM::GetTime,
+            GCM::Schedules => SM::GetSchedules,
+            GCM::Timers => SM::GetTimers,
+            GCM::BootSettings => SM::GetBootSettings,
+            GCM::ResetToken => SM::GetResetToken,
+            GCM::FirmwareInfo => SM::GetFirmwareInfo,
+            GCM::LogSettings => SM::GetLogSettings,
         }
     }
 
     async fn from_client_message_fallible(message: ClientMessage) -> Result<Self> {
         match message {
-            ClientMessage::Get(message) => Ok(Self::from_get_client_message(&message)),
+            ClientMessage::Get(message) => {
+                match message {
+                    GetClientMessage::Palette(palette_id) => {
+                        PALETTES
+                            .get()
+                            .lock()
+                            .await
+                            .get(palette_id)
+                            .ok_or(Error::PaletteNotFound)?;
+                    }
+                    GetClientMessage::Playlist(playlist_id) => {
+                        PLAYLISTS
+                            .get()
+                            .lock()
+                            .await
+                            .get(playlist_id)
+                            .ok_or(Error::PlaylistNotFound)?;
+                    }
+                    GetClientMessage::ResetToken => ResetToken::issue().await,
+                    _ => {}
+                }
+                Ok(Self::from_get_client_message(&message))
+            }
             ClientMessage::Set(message) => {
                 use SetClientMessage as SCM;
 
@@ -229,16 +721,18 @@ impl ServerMessage {
                     SCM::Preset(preset_id) => {
                         SHOULD_UPDATE.store(true, Ordering::Relaxed);
                         settings.current_preset_id = preset_id;
+                        settings.active_playlist = None;
+                        settings.active_clip = None;
                     }
                     SCM::Settings(new_settings) => {
-                        *settings = new_settings;
+                        if settings.replace(*new_settings)? {
+                            SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        }
                         settings.save().await?;
-                        software_reset();
                     }
                     SCM::WifiSettings(wifi_settings) => {
                         settings.wifi_settings = wifi_settings;
                         settings.save().await?;
-                        software_reset();
                     }
                     SCM::CurrentPresetSettings(preset_settings) => {
                         SHOULD_UPDATE.store(true, Ordering::Relaxed);
@@ -263,7 +757,199 @@ impl ServerMessage {
                     }
                     SCM::SaveSettings => {
                         settings.save().await?;
+                        LAYERS.get().lock().await.save().await?;
+                    }
+                    SCM::FrameRate(frame_rate) => {
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        settings.renderer_settings.frame_rate = frame_rate.clamp(1, MAX_FRAME_RATE);
+                    }
+                    SCM::Color(color) => {
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        let current_preset_id = settings.current_preset_id.id();
+                        settings.preset_settings[current_preset_id as usize].color = color;
+                    }
+                    SCM::UploadPalette(palette_id, palette) => {
+                        let mut palettes = PALETTES.get().lock().await;
+                        palettes.insert(palette_id, palette)?;
+                        palettes.save().await?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
                     }
+                    SCM::DeletePalette(palette_id) => {
+                        let mut palettes = PALETTES.get().lock().await;
+                        palettes.remove(palette_id)?;
+                        palettes.save().await?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::Palette(palette_id) => {
+                        if palette_id as usize >= MAX_PALETTES {
+                            return Err(Error::PaletteIdOutOfBounds);
+                        }
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        let current_preset_id = settings.current_preset_id.id();
+                        settings.preset_settings[current_preset_id as usize].palette = palette_id;
+                    }
+                    SCM::UploadPlaylist(playlist_id, playlist) => {
+                        let mut playlists = PLAYLISTS.get().lock().await;
+                        playlists.insert(playlist_id, *playlist)?;
+                        playlists.save().await?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::DeletePlaylist(playlist_id) => {
+                        let mut playlists = PLAYLISTS.get().lock().await;
+                        playlists.remove(playlist_id)?;
+                        playlists.save().await?;
+                        if settings.active_playlist == Some(playlist_id) {
+                            settings.active_playlist = None;
+                        }
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::Playlist(playlist_id) => {
+                        PLAYLISTS
+                            .get()
+                            .lock()
+                            .await
+                            .get(playlist_id)
+                            .ok_or(Error::PlaylistNotFound)?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        settings.active_playlist = Some(playlist_id);
+                        settings.active_clip = None;
+                    }
+                    SCM::SaveFavorite(favorite_id, name) => {
+                        let preset_id = settings.current_preset_id;
+                        let favorite = Favorite {
+                            name,
+                            preset_id,
+                            preset_settings: settings.preset_settings[preset_id.id() as usize],
+                        };
+                        let mut favorites = FAVORITES.get().lock().await;
+                        favorites.insert(favorite_id, favorite)?;
+                        favorites.save().await?;
+                    }
+                    SCM::RecallFavorite(favorite_id) => {
+                        let favorites = FAVORITES.get().lock().await;
+                        favorites
+                            .get(favorite_id)
+                            .ok_or(Error::FavoriteNotFound)?
+                            .recall(&mut settings)?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::DeleteFavorite(favorite_id) => {
+                        let mut favorites = FAVORITES.get().lock().await;
+                        favorites.remove(favorite_id)?;
+                        favorites.save().await?;
+                    }
+                    SCM::Layers(layers) => {
+                        LAYERS.get().lock().await.set(layers)?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::UploadScript(script) => {
+                        let mut stored_script = SCRIPT.get().lock().await;
+                        *stored_script = script.validate()?;
+                        stored_script.save().await?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::UploadClipChunk(clip_id, offset, data) => {
+                        // The clip is being overwritten, so it cannot be played in the meantime
+                        if settings.active_clip == Some(clip_id) {
+                            settings.active_clip = None;
+                            SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        }
+                        clips::write_chunk(clip_id, offset, &data).await?;
+                    }
+                    SCM::FinishClip(clip_id, name, length) => {
+                        clips::finish(clip_id, &name, length).await?;
+                    }
+                    SCM::DeleteClip(clip_id) => {
+                        clips::get(clip_id).await?.ok_or(Error::ClipNotFound)?;
+                        clips::delete(clip_id).await?;
+                        if settings.active_clip == Some(clip_id) {
+                            settings.active_clip = None;
+                            SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    SCM::PlayClip(clip_id) => {
+                        clips::get(clip_id).await?.ok_or(Error::ClipNotFound)?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                        settings.active_clip = Some(clip_id);
+                        settings.active_playlist = None;
+                    }
+                    SCM::Layout(layout) => {
+                        settings.layout = layout.validate()?;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::Time(unix_ms) => {
+                        CLOCK.get().lock().await.set(unix_ms, TimeSource::Manual);
+                    }
+                    SCM::TimeSettings(time_settings) => {
+                        let mut stored_time_settings = TIME_SETTINGS.get().lock().await;
+                        *stored_time_settings = time_settings.validate()?;
+                        stored_time_settings.save().await?;
+                        SYNC_TIME.signal(());
+                    }
+                    SCM::Schedules(schedules) => {
+                        let mut stored_schedules = SCHEDULES.get().lock().await;
+                        stored_schedules.set(schedules)?;
+                        stored_schedules.save().await?;
+                    }
+                    SCM::SleepTimer(minutes) => {
+                        TIMERS.get().lock().await.sleep = Some(SleepTimer::new(minutes)?);
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::CancelSleepTimer => {
+                        TIMERS.get().lock().await.sleep = None;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::Sunrise(sunrise) => {
+                        let alarm = SunriseAlarm::new(sunrise).await?;
+                        TIMERS.get().lock().await.sunrise = Some(alarm);
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::CancelSunrise => {
+                        let mut timers = TIMERS.get().lock().await;
+                        timers.sunrise = None;
+                        timers.sunrise_end = None;
+                        SHOULD_UPDATE.store(true, Ordering::Relaxed);
+                    }
+                    SCM::BootSettings(boot_settings) => {
+                        let mut stored_boot_settings = BOOT_SETTINGS.get().lock().await;
+                        *stored_boot_settings = boot_settings;
+                        stored_boot_settings.save().await?;
+                    }
+                    SCM::Reboot(token) => ResetToken::confirm(token).await?,
+                    SCM::FactoryReset(token) => {
+                        ResetToken::confirm(token).await?;
+                        reset::erase_settings().await?;
+                        *settings = Settings::default();
+                    }
+                    SCM::BeginFirmwareUpdate(request) => {
+                        let update = FirmwareUpdate::begin(request).await?;
+                        *FIRMWARE_UPDATE.get().lock().await = Some(update);
+                    }
+                    SCM::UploadFirmwareChunk(offset, data) => {
+                        FIRMWARE_UPDATE
+                            .get()
+                            .lock()
+                            .await
+                            .as_mut()
+                            .ok_or(Error::FirmwareUpdateNotStarted)?
+                            .write_chunk(offset, &data)
+                            .await?;
+                    }
+                    SCM::FinishFirmwareUpdate => {
+                        let update = FIRMWARE_UPDATE.get().lock().await.take();
+                        update
+                            .ok_or(Error::FirmwareUpdateNotStarted)?
+                            .finish()
+                            .await?;
+                    }
+                    SCM::LogSettings(log_settings) => {
+                        let mut stored_log_settings = LOG_SETTINGS.get().lock().await;
+                        *stored_log_settings = log_settings;
+                        stored_log_settings.save().await?;
+                        stored_log_settings.apply();
+                    }
+                    // The client is subscribed by the server task, which knows its address
+                    SCM::SubscribeLogs => {}
                 };
                 Ok(response_message)
             }
@@ -289,7 +975,7 @@ impl ServerMessage {
         use ServerMessage as SM;
 
         buf[0] = Version::V1 as u8;
-        buf[1] = self.method_id();
+        buf[1] = self.method() as u8;
         let mut payload: String = String::new();
         let settings = SETTINGS.get().lock().await;
 
@@ -298,7 +984,16 @@ impl ServerMessage {
                 buf[2] = settings.is_on as u8;
             }
             SM::GetCurrentPresetId => payload = settings.current_preset_id.id().to_string(),
-            SM::GetPresetInfo => payload = String::from(PRESET_INFO),
+            SM::GetPresetInfo(preset_id) => {
+                let preset_info = PRESET_INFO[preset_id.id() as usize];
+                let page = PresetInfoPage {
+                    id: preset_id.id(),
+                    count: PRESET_COUNT,
+                    name: preset_info.name,
+                    params: preset_info.params,
+                };
+                payload = serde_json::to_string(&page).map_err(Error::Serialization)?;
+            }
             SM::GetSettings => {
                 payload = serde_json::to_string(&*settings).map_err(Error::Serialization)?;
             }
@@ -312,10 +1007,138 @@ impl ServerMessage {
                 payload =
                     serde_json::to_string(&settings.wifi_settings).map_err(Error::Serialization)?;
             }
+            SM::GetStats => {
+                let mut stats = STATS.get().lock().await;
+                stats.sample();
+                payload = serde_json::to_string(&*stats).map_err(Error::Serialization)?;
+                stats.max_frame_time_us = 0;
+            }
+            SM::GetPalettes => {
+                let palettes = PALETTES.get().lock().await;
+                let summaries: heapless::Vec<ItemSummary, MAX_PALETTES> = palettes
+                    .iter()
+                    .map(|(id, palette)| ItemSummary {
+                        id,
+                        name: &palette.name,
+                    })
+                    .collect();
+                payload = serde_json::to_string(&summaries).map_err(Error::Serialization)?;
+            }
+            SM::GetPalette(palette_id) => {
+                let palettes = PALETTES.get().lock().await;
+                let message = StoredItem {
+                    id: *palette_id,
+                    item: palettes.get(*palette_id).ok_or(Error::PaletteNotFound)?,
+                };
+                payload = serde_json::to_string(&message).map_err(Error::Serialization)?;
+            }
+            SM::GetPlaylists => {
+                let playlists = PLAYLISTS.get().lock().await;
+                let summaries: heapless::Vec<ItemSummary, MAX_PLAYLISTS> = playlists
+                    .iter()
+                    .map(|(id, playlist)| ItemSummary {
+                        id,
+                        name: &playlist.name,
+                    })
+                    .collect();
+                payload = serde_json::to_string(&summaries).map_err(Error::Serialization)?;
+            }
+            SM::GetFavorites => {
+                let favorites = FAVORITES.get().lock().await;
+                let summaries: heapless::Vec<ItemSummary, MAX_FAVORITES> = favorites
+                    .iter()
+                    .map(|(id, favorite)| ItemSummary {
+                        id,
+                        name: &favorite.name,
+                    })
+                    .collect();
+                payload = serde_json::to_string(&summaries).map_err(Error::Serialization)?;
+            }
+            SM::GetLayers => {
+                let layers = LAYERS.get().lock().await;
+                payload = serde_json::to_string(&layers.layers).map_err(Error::Serialization)?;
+            }
+            SM::GetClips => {
+                let mut summaries: heapless::Vec<ClipSummary, MAX_CLIPS> = heapless::Vec::new();
+                for id in 0..MAX_CLIPS as u8 {
+                    if let Some(clip) = clips::get(id).await? {
+                        // Cannot overflow, as there is at most one summary per slot
+                        let _ = summaries.push(ClipSummary {
+                            id,
+                            name: clip.name,
+                            length: clip.length,
+                        });
+                    }
+                }
+                let message = ClipList {
+                    led_count: LED_COUNT as u16,
+                    max_length: MAX_CLIP_LENGTH,
+                    clips: summaries,
+                };
+                payload = serde_json::to_string(&message).map_err(Error::Serialization)?;
+            }
+            SM::GetTime => {
+                let clock = CLOCK.get().lock().await;
+                let time_settings = TIME_SETTINGS.get().lock().await;
+                let message = TimeStatus {
+                    unix_ms: clock.now_millis(),
+                    source: clock.source,
+                    settings: &time_settings,
+                };
+                payload = serde_json::to_string(&message).map_err(Error::Serialization)?;
+            }
+            SM::GetSchedules => {
+                let schedules = SCHEDULES.get().lock().await;
+                payload =
+                    serde_json::to_string(&schedules.schedules).map_err(Error::Serialization)?;
+            }
+            SM::GetTimers => {
+                let timers = TIMERS.get().lock().await;
+                let message = TimerStatus {
+                    sleep_remaining: timers.sleep.map(|sleep| sleep.remaining().as_secs()),
+                    sunrise: timers.sunrise,
+                };
+                payload = serde_json::to_string(&message).map_err(Error::Serialization)?;
+            }
+            SM::GetFirmwareInfo => {
+                let info = FirmwareInfo::read().await?;
+                payload = serde_json::to_string(&info).map_err(Error::Serialization)?;
+            }
+            SM::GetResetToken => {
+                let token = RESET_TOKEN
+                    .get()
+                    .lock()
+                    .await
+                    .ok_or(Error::InvalidResetToken)?;
+                payload = serde_json::to_string(&token).map_err(Error::Serialization)?;
+            }
+            SM::GetLogSettings => {
+                let log_settings = LOG_SETTINGS.get().lock().await;
+                payload = serde_json::to_string(&*log_settings).map_err(Error::Serialization)?;
+            }
+            SM::GetBootSettings => {
+                let boot_settings = BOOT_SETTINGS.get().lock().await;
+                payload = serde_json::to_string(&*boot_settings).map_err(Error::Serialization)?;
+            }
+            SM::GetScript => {
+                let script = SCRIPT.get().lock().await;
+                payload = serde_json::to_string(&*script).map_err(Error::Serialization)?;
+            }
+            SM::GetPlaylist(playlist_id) => {
+                let playlists = PLAYLISTS.get().lock().await;
+                let message = StoredItem {
+                    id: *playlist_id,
+                    item: playlists.get(*playlist_id).ok_or(Error::PlaylistNotFound)?,
+                };
+                payload = serde_json::to_string(&message).map_err(Error::Serialization)?;
+            }
             _ => {}
         }
 
         let payload_bytes = payload.as_bytes();
+        if 2 + payload_bytes.len() > buf.len() {
+            return Err(Error::InvalidMessageLength);
+        }
         buf[2..2 + payload_bytes.len()].copy_from_slice(payload_bytes);
         let message_len = 2 + payload_bytes.len();
         socket
@@ -344,13 +1167,19 @@ pub async fn server_task(stack: Stack<'static>) -> ! {
     log::info!("Server ready!");
 
     loop {
-        let (rx_size, from_addr) = match socket.recv_from(&mut message_buf).await {
-            Ok((size, addr)) => (size, addr),
-            Err(e) => {
+        let received = select(socket.recv_from(&mut message_buf), LOG_RECORDS.receive()).await;
+        let (rx_size, from_addr) = match received {
+            Either::First(Ok((size, addr))) => (size, addr),
+            Either::First(Err(e)) => {
                 log::error!("Error recieving data from UDP connection: {:?}", e);
                 continue;
             }
+            Either::Second(record) => {
+                record.send(&mut socket, &mut message_buf).await;
+                continue;
+            }
         };
+        STATS.get().lock().await.record_message();
 
         if rx_size < MINIMAL_CLIENT_MESSAGE_LENGTH {
             log::warn!(
@@ -358,6 +1187,7 @@ pub async fn server_task(stack: Stack<'static>) -> ! {
                 rx_size,
                 MINIMAL_CLIENT_MESSAGE_LENGTH
             );
+            STATS.get().lock().await.record_protocol_error();
             continue;
         }
 
@@ -365,17 +1195,44 @@ pub async fn server_task(stack: Stack<'static>) -> ! {
             Ok(message) => message,
             Err(e) => {
                 log::error!("Error parsing recieved message: {:?}", e);
+                STATS.get().lock().await.record_protocol_error();
                 continue;
             }
         };
 
-        let response = ServerMessage::from_client_message(request).await;
+        let mut response = ServerMessage::from_client_message(request).await;
+        if let ServerMessage::SubscribeLogs = response
+            && let Err(e) = LOG_SUBSCRIBERS
+                .get()
+                .lock()
+                .await
+                .subscribe(from_addr.endpoint)
+        {
+            log::error!("Error subscribing client to logs: {:?}", e);
+            response = ServerMessage::Error;
+        }
+        if let ServerMessage::Error = response {
+            STATS.get().lock().await.record_protocol_error();
+        }
 
-        response
+        if let Err(e) = response
             .send(&mut socket, &mut message_buf, from_addr)
             .await
-            .unwrap_or_else(|e| {
-                log::error!("Error sending response to client: {:?}", e);
-            });
+        {
+            log::error!("Error sending response to client: {:?}", e);
+            STATS.get().lock().await.record_protocol_error();
+        }
+
+        // Reconnecting drops the connection, so the new credentials are only applied once the
+        // client has got its response
+        if let ServerMessage::SetSettings | ServerMessage::SetWifiSettings = response {
+            UPDATE_WIFI.signal(SETTINGS.get().lock().await.wifi_settings.clone());
+        }
+        if let ServerMessage::Reboot
+        | ServerMessage::FactoryReset
+        | ServerMessage::FinishFirmwareUpdate = response
+        {
+            reset::restart().await;
+        }
     }
 }
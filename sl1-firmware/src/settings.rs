@@ -1,94 +1,145 @@
 use core::str::FromStr;
 
-use embedded_storage::{ReadStorage, Storage};
-use serde::{Deserialize, Serialize};
+use esp_wifi::wifi::ClientConfiguration;
 
+use embassy_time::Duration;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::layout::MatrixLayout;
+use crate::presets::{PRESET_COUNT, PRESET_INFO};
+use crate::storage::Persistent;
 use crate::{
-    DEFAULT_WIFI_PASSWORD, DEFAULT_WIFI_SSID, Error, PRESET_COUNT, Result, SETTINGS_STORAGE_OFFSET,
-    STORAGE,
+    DEFAULT_FRAME_RATE, DEFAULT_WIFI_PASSWORD, DEFAULT_WIFI_SSID, Error, MAX_FRAME_RATE, Result,
+    SETTINGS_STORAGE_OFFSET, SETTINGS_VERSION,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Settings {
     pub wifi_settings: WifiSettings,
+    #[serde(with = "changed_preset_settings")]
     pub preset_settings: [PresetSettings; PRESET_COUNT as usize],
     pub current_preset_id: PresetId,
     pub is_on: bool,
-}
-
-impl From<&[u8; core::mem::size_of::<Self>()]> for Settings {
-    fn from(bytes: &[u8; core::mem::size_of::<Self>()]) -> Self {
-        unsafe { core::mem::transmute(*bytes) }
-    }
-}
-
-impl<'a> From<&'a Settings> for &'a [u8] {
-    fn from(value: &'a Settings) -> Self {
-        unsafe { as_u8_slice(value) }
-    }
+    #[serde(default)]
+    pub power_settings: PowerSettings,
+    #[serde(default)]
+    pub renderer_settings: RendererSettings,
+    /// Playlist shown instead of the current preset
+    #[serde(default)]
+    pub active_playlist: Option<u8>,
+    /// Recorded clip shown instead of the current preset
+    #[serde(default)]
+    pub active_clip: Option<u8>,
+    #[serde(default)]
+    pub layout: MatrixLayout,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
             wifi_settings: WifiSettings::default(),
-            preset_settings: [PresetSettings::default(); PRESET_COUNT as usize],
+            preset_settings: core::array::from_fn(|id| PRESET_INFO[id].default_settings()),
             current_preset_id: PresetId::new_fallible(0).unwrap(),
             is_on: true,
+            power_settings: PowerSettings::default(),
+            renderer_settings: RendererSettings::default(),
+            active_playlist: None,
+            active_clip: None,
+            layout: MatrixLayout::default(),
         }
     }
 }
 
+impl Persistent for Settings {
+    const STORAGE_OFFSET: u32 = SETTINGS_STORAGE_OFFSET;
+    const VERSION: u32 = SETTINGS_VERSION;
+
+    fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+}
+
 impl Settings {
-    pub async fn save(&self) -> Result<()> {
-        STORAGE
-            .get()
-            .lock()
-            .await
-            .write(SETTINGS_STORAGE_OFFSET, self.into())
-            .map_err(Error::StorageWrite)?;
+    /// Checks the values the renderer relies on, which deserialize from any number
+    fn validate(&self) -> Result<()> {
+        PresetId::new_fallible(self.current_preset_id.id())?;
+        self.layout.validate()?;
         Ok(())
     }
 
-    pub async fn load() -> Result<Self> {
-        let mut buf = [0u8; core::mem::size_of::<Settings>()];
-        STORAGE
-            .get()
-            .lock()
-            .await
-            .read(SETTINGS_STORAGE_OFFSET, &mut buf)
-            .map_err(Error::StorageRead)?;
-        Ok((&buf).into())
-    }
-}
-
-// This function is kinda warcrimish, however I am content with its existence
-pub async fn init_settings_storage() -> Result<()> {
-    let mut buf = [0u8; core::mem::size_of::<Settings>()];
-    STORAGE
-        .get()
-        .lock()
-        .await
-        .read(SETTINGS_STORAGE_OFFSET, &mut buf)
-        .map_err(Error::StorageRead)?;
-    if (0..core::mem::size_of::<Settings>())
-        .map(|idx| buf[idx])
-        .all(|byte| byte == 0xff)
-    {
-        // This means that the settings storage was not initialized and all of the bytes in flash
-        // memory are by default set to 0xff, thus we need to initialize this region with default
-        // settings
-        Settings::default().save().await?;
-    }
-    Ok(())
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+    /// Replaces the settings, returns whether anything but the Wi-Fi settings has changed, in
+    /// which case the renderer has to be restarted. Wi-Fi settings are applied by the Wi-Fi task
+    pub fn replace(&mut self, settings: Settings) -> Result<bool> {
+        settings.validate()?;
+        let should_update = Settings {
+            wifi_settings: self.wifi_settings.clone(),
+            ..settings.clone()
+        } != *self;
+        *self = settings;
+        Ok(should_update)
+    }
+}
+
+/// Only the settings of the presets that differ from their defaults are sent and stored, as
+/// `[id, settings]` pairs, since the settings of every preset would not fit into a single message
+mod changed_preset_settings {
+    use serde::de::Error;
+
+    use super::*;
+
+    type PresetSettingsArray = [PresetSettings; PRESET_COUNT as usize];
+
+    pub fn serialize<S: Serializer>(
+        preset_settings: &PresetSettingsArray,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        // Collected first, since postcard has to know the length of the sequence up front
+        let changed: heapless::Vec<(u8, &PresetSettings), { PRESET_COUNT as usize }> =
+            preset_settings
+                .iter()
+                .enumerate()
+                .filter(|(id, settings)| **settings != PRESET_INFO[*id].default_settings())
+                .map(|(id, settings)| (id as u8, settings))
+                .collect();
+        changed.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<PresetSettingsArray, D::Error> {
+        let changed: heapless::Vec<(u8, PresetSettings), { PRESET_COUNT as usize }> =
+            heapless::Vec::deserialize(deserializer)?;
+        let mut preset_settings: PresetSettingsArray =
+            core::array::from_fn(|id| PRESET_INFO[id].default_settings());
+        for (id, settings) in changed {
+            *preset_settings
+                .get_mut(id as usize)
+                .ok_or_else(|| D::Error::custom("invalid preset id"))? = settings;
+        }
+        Ok(preset_settings)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WifiSettings {
     ssid: heapless::String<32>,
     password: heapless::String<64>,
 }
 
+impl WifiSettings {
+    pub fn ssid(&self) -> &str {
+        &self.ssid
+    }
+
+    pub fn client_configuration(&self) -> ClientConfiguration {
+        ClientConfiguration {
+            ssid: self.ssid.clone(),
+            password: self.password.clone(),
+            ..Default::default()
+        }
+    }
+}
+
 impl Default for WifiSettings {
     fn default() -> Self {
         Self {
@@ -98,7 +149,7 @@ impl Default for WifiSettings {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct PresetSettings {
     #[serde(rename = "b")]
     pub brightness: u8,
@@ -106,6 +157,11 @@ pub struct PresetSettings {
     pub speed: u8,
     #[serde(rename = "sc")]
     pub scale: u8,
+    #[serde(rename = "c", default = "default_color")]
+    pub color: [u8; 3],
+    /// Id of the palette used by palette-capable presets
+    #[serde(rename = "p", default)]
+    pub palette: u8,
 }
 
 impl Default for PresetSettings {
@@ -114,11 +170,60 @@ impl Default for PresetSettings {
             brightness: 50,
             speed: 255,
             scale: 0,
+            color: default_color(),
+            palette: 0,
+        }
+    }
+}
+
+fn default_color() -> [u8; 3] {
+    [255, 255, 255]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerSettings {
+    /// Current budget of the whole strip in mA, 0 disables limiting
+    #[serde(rename = "max_ma")]
+    pub max_current: u16,
+    /// Current drawn by a single LED channel at full duty in mA
+    #[serde(rename = "ch_ma")]
+    pub channel_current: u8,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            max_current: 2000,
+            channel_current: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RendererSettings {
+    /// Temporal dithering of the 16-bit frame on output
+    pub dithering: bool,
+    /// Target frame rate in frames per second
+    #[serde(rename = "fps")]
+    pub frame_rate: u8,
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        Self {
+            dithering: true,
+            frame_rate: DEFAULT_FRAME_RATE,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+impl RendererSettings {
+    pub fn frame_time(&self) -> Duration {
+        Duration::from_hz(self.frame_rate.clamp(1, MAX_FRAME_RATE) as u64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub struct PresetId(u8);
 
 impl PresetId {
@@ -139,7 +244,3 @@ impl core::fmt::Display for PresetId {
         write!(f, "{}", self.0)
     }
 }
-
-unsafe fn as_u8_slice<T: Sized>(p: &T) -> &[u8] {
-    unsafe { core::slice::from_raw_parts((p as *const T) as *const u8, core::mem::size_of::<T>()) }
-}
@@ -0,0 +1,93 @@
+use embassy_net::Stack;
+use embassy_net::dns::DnsQueryType;
+use embassy_net::udp::{PacketMetadata, UdpSocket};
+use embassy_time::{Instant, with_timeout};
+
+use crate::time::TimeSource;
+use crate::{
+    CLOCK, Error, Result, SNTP_RETRY_INTERVAL, SNTP_SYNC_INTERVAL, SNTP_TIMEOUT, SYNC_TIME,
+    TIME_SETTINGS,
+};
+
+const NTP_PORT: u16 = 123;
+const NTP_PACKET_LENGTH: usize = 48;
+/// Seconds from the start of the NTP era (1900) to the unix epoch (1970)
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+/// Leap indicator 0, version 4, client mode
+const NTP_CLIENT_HEADER: u8 = 0x23;
+const NTP_SERVER_MODE: u8 = 4;
+
+#[embassy_executor::task]
+pub async fn sntp_task(stack: Stack<'static>) -> ! {
+    let mut rx_meta = [PacketMetadata::EMPTY; 2];
+    let mut rx_buf = [0; 2 * NTP_PACKET_LENGTH];
+    let mut tx_meta = [PacketMetadata::EMPTY; 2];
+    let mut tx_buf = [0; 2 * NTP_PACKET_LENGTH];
+
+    stack.wait_config_up().await;
+
+    let mut socket = UdpSocket::new(stack, &mut rx_meta, &mut rx_buf, &mut tx_meta, &mut tx_buf);
+    // Port 0 binds the socket to an ephemeral port
+    socket.bind(0).unwrap();
+
+    loop {
+        let delay = match sync(stack, &mut socket).await {
+            Ok(()) => SNTP_SYNC_INTERVAL,
+            Err(err) => {
+                log::warn!(target: "SNTP", "Error synchronizing time: {err}");
+                SNTP_RETRY_INTERVAL
+            }
+        };
+
+        // Time is synchronized again right away once the server has been changed
+        if with_timeout(delay, SYNC_TIME.wait()).await.is_ok() {
+            log::info!(target: "SNTP", "Time settings changed, synchronizing time");
+        }
+    }
+}
+
+async fn sync(stack: Stack<'static>, socket: &mut UdpSocket<'_>) -> Result<()> {
+    let server = TIME_SETTINGS.get().lock().await.ntp_server.clone();
+    if server.is_empty() {
+        return Ok(());
+    }
+
+    let address = *stack
+        .dns_query(&server, DnsQueryType::A)
+        .await
+        .map_err(Error::DnsQuery)?
+        .first()
+        .ok_or(Error::DnsQuery(embassy_net::dns::Error::Failed))?;
+
+    let mut request = [0u8; NTP_PACKET_LENGTH];
+    request[0] = NTP_CLIENT_HEADER;
+    let sent_at = Instant::now();
+    socket
+        .send_to(&request, (address, NTP_PORT))
+        .await
+        .map_err(Error::SendError)?;
+
+    let mut response = [0u8; NTP_PACKET_LENGTH];
+    let (length, _) = with_timeout(SNTP_TIMEOUT, socket.recv_from(&mut response))
+        .await
+        .map_err(|_| Error::SntpTimeout)?
+        .map_err(Error::RecvError)?;
+    // A response with stratum 0 is a "kiss-o'-death" message telling the client to back off
+    if length < NTP_PACKET_LENGTH || response[0] & 0x07 != NTP_SERVER_MODE || response[1] == 0 {
+        return Err(Error::InvalidSntpResponse);
+    }
+
+    let seconds = u32::from_be_bytes(response[40..44].try_into().unwrap()) as u64;
+    let fraction = u32::from_be_bytes(response[44..48].try_into().unwrap()) as u64;
+    // Timestamps before the unix epoch belong to the next NTP era, which starts in 2036
+    let seconds = match seconds >= NTP_UNIX_OFFSET {
+        true => seconds - NTP_UNIX_OFFSET,
+        false => seconds + (1 << 32) - NTP_UNIX_OFFSET,
+    };
+    let round_trip = sent_at.elapsed().as_millis();
+    let unix_millis = seconds * 1000 + ((fraction * 1000) >> 32) + round_trip / 2;
+
+    CLOCK.get().lock().await.set(unix_millis, TimeSource::Sntp);
+    log::info!(target: "SNTP", "Synchronized time with {server}: {seconds}");
+    Ok(())
+}
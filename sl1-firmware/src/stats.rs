@@ -0,0 +1,82 @@
+use embassy_time::{Duration, Instant};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Stats {
+    /// Estimated current drawn by the strip for the last frame in mA
+    pub power_ma: u32,
+    /// Whether the last frame has been dimmed to fit into the current budget
+    pub power_limited: bool,
+    /// Measured frame rate of the renderer in frames per second, 0 while nothing is rendered
+    pub frame_rate: u8,
+    /// Moving average of the time between the starts of two frames in us
+    #[serde(skip)]
+    pub frame_interval_us: u32,
+    /// Moving average of the time spent rendering a frame in us
+    pub render_time_us: u32,
+    /// Moving average of the time spent writing a frame to the strip over SPI in us
+    pub write_time_us: u32,
+    /// Longest frame since the stats have last been requested in us
+    pub max_frame_time_us: u32,
+    /// Number of frames that did not fit into the frame time since boot
+    pub dropped_frames: u32,
+    /// Time since boot in seconds
+    pub uptime_s: u64,
+    pub heap_used: u32,
+    pub heap_free: u32,
+    /// Signal strength of the access point in dBm, `None` while disconnected
+    pub rssi: Option<i8>,
+    /// Number of times the connection to the access point has been lost or could not be made
+    pub wifi_reconnects: u32,
+    pub messages_received: u32,
+    /// Messages that could not be parsed or handled and responses that could not be sent
+    pub protocol_errors: u32,
+}
+
+impl Stats {
+    pub fn record_frame(&mut self, render_time: Duration, write_time: Duration, dropped: bool) {
+        self.render_time_us = moving_average(self.render_time_us, render_time.as_micros() as u32);
+        self.write_time_us = moving_average(self.write_time_us, write_time.as_micros() as u32);
+        let frame_time_us = (render_time + write_time).as_micros() as u32;
+        self.max_frame_time_us = self.max_frame_time_us.max(frame_time_us);
+        if dropped {
+            self.dropped_frames = self.dropped_frames.wrapping_add(1);
+        }
+    }
+
+    pub fn record_frame_interval(&mut self, interval: Duration) {
+        let interval_us = interval.as_micros() as u32;
+        self.frame_interval_us = match self.frame_interval_us {
+            0 => interval_us,
+            average => moving_average(average, interval_us),
+        };
+    }
+
+    pub fn record_message(&mut self) {
+        self.messages_received = self.messages_received.wrapping_add(1);
+    }
+
+    pub fn record_protocol_error(&mut self) {
+        self.protocol_errors = self.protocol_errors.wrapping_add(1);
+    }
+
+    pub fn record_wifi_reconnect(&mut self) {
+        self.wifi_reconnects = self.wifi_reconnects.wrapping_add(1);
+    }
+
+    /// Fills in the values that are not counted, but read when the stats are requested
+    pub fn sample(&mut self) {
+        self.frame_rate = 1_000_000u32
+            .checked_div(self.frame_interval_us)
+            .unwrap_or(0)
+            .min(u8::MAX as u32) as u8;
+        self.uptime_s = Instant::now().as_secs();
+        self.heap_used = esp_alloc::HEAP.used() as u32;
+        self.heap_free = esp_alloc::HEAP.free() as u32;
+        self.rssi = crate::wifi::rssi();
+    }
+}
+
+fn moving_average(average: u32, sample: u32) -> u32 {
+    average - average / 16 + sample / 16
+}
@@ -0,0 +1,91 @@
+use alloc::vec;
+
+use embedded_storage::{ReadStorage, Storage};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::{Error, FLASH_SECTOR_SIZE, Result, STORAGE};
+
+/// Every region starts with the format version and the length of the data that follows it
+const HEADER_SIZE: usize = 2 * core::mem::size_of::<u32>();
+const MAX_DATA_SIZE: usize = FLASH_SECTOR_SIZE as usize - HEADER_SIZE;
+
+/// Data stored in flash serialized with postcard, prefixed with a format version and its length.
+/// Each implementor owns the flash sector starting at `STORAGE_OFFSET`, `VERSION` should be bumped
+/// whenever the stored fields change, data that does not deserialize or is not valid is replaced
+/// with defaults
+#[allow(async_fn_in_trait)]
+pub trait Persistent: Default + Serialize + DeserializeOwned {
+    const STORAGE_OFFSET: u32;
+    const VERSION: u32;
+
+    /// Checks the values that deserialize but could not have been set through the server, such
+    /// as out of range ids written by a firmware with other limits
+    fn is_valid(&self) -> bool {
+        true
+    }
+
+    async fn save(&self) -> Result<()> {
+        let mut buf = vec![0u8; HEADER_SIZE];
+        buf[..4].copy_from_slice(&Self::VERSION.to_le_bytes());
+        let mut buf = postcard::to_extend(self, buf).map_err(Error::StorageSerialization)?;
+        let data_size = buf.len() - HEADER_SIZE;
+        if data_size > MAX_DATA_SIZE {
+            return Err(Error::StorageTooLarge);
+        }
+        buf[4..HEADER_SIZE].copy_from_slice(&(data_size as u32).to_le_bytes());
+        STORAGE
+            .get()
+            .lock()
+            .await
+            .write(Self::STORAGE_OFFSET, &buf)
+            .map_err(Error::StorageWrite)?;
+        Ok(())
+    }
+
+    async fn load() -> Result<Self> {
+        if let Some(value) = Self::read().await? {
+            return Ok(value);
+        }
+
+        // Either the region was not initialized (all of the bytes in flash memory are by default
+        // set to 0xff) or it was written by a firmware storing different data, thus we need to
+        // initialize this region with default values
+        let value = Self::default();
+        value.save().await?;
+        Ok(value)
+    }
+
+    /// Reads the stored data, `None` if it is missing, does not deserialize or is not valid
+    async fn read() -> Result<Option<Self>> {
+        let mut storage = STORAGE.get().lock().await;
+        let mut header = [0u8; HEADER_SIZE];
+        storage
+            .read(Self::STORAGE_OFFSET, &mut header)
+            .map_err(Error::StorageRead)?;
+
+        let version = u32::from_le_bytes(header[..4].try_into().unwrap());
+        let data_size = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        if version != Self::VERSION || data_size > MAX_DATA_SIZE {
+            return Ok(None);
+        }
+
+        let mut data = vec![0u8; data_size];
+        storage
+            .read(Self::STORAGE_OFFSET + HEADER_SIZE as u32, &mut data)
+            .map_err(Error::StorageRead)?;
+        Ok(postcard::from_bytes(&data).ok().filter(Self::is_valid))
+    }
+}
+
+/// Resets the region to the erased state, `offset` and `size` have to be sector aligned
+pub async fn erase(offset: u32, size: u32) -> Result<()> {
+    let erased = vec![0xffu8; FLASH_SECTOR_SIZE as usize];
+    let mut storage = STORAGE.get().lock().await;
+    for sector in (offset..offset + size).step_by(FLASH_SECTOR_SIZE as usize) {
+        storage
+            .write(sector, &erased)
+            .map_err(Error::StorageWrite)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use embassy_time::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Persistent;
+use crate::{Error, Result, TIME_SETTINGS_STORAGE_OFFSET, TIME_SETTINGS_VERSION};
+
+/// Largest offsets of the time zones in use, from UTC-12:00 to UTC+14:00, in minutes
+const UTC_OFFSET_RANGE: core::ops::RangeInclusive<i16> = -720..=840;
+
+/// Settings of the wall-clock, stored apart from `Settings` to keep them out of the settings
+/// message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeSettings {
+    /// Offset of the local time from UTC in minutes
+    pub utc_offset: i16,
+    /// Host name or address of the SNTP server, time is not synchronized when it is empty
+    pub ntp_server: heapless::String<32>,
+}
+
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            utc_offset: 0,
+            ntp_server: heapless::String::try_from("pool.ntp.org").unwrap(),
+        }
+    }
+}
+
+impl Persistent for TimeSettings {
+    const STORAGE_OFFSET: u32 = TIME_SETTINGS_STORAGE_OFFSET;
+    const VERSION: u32 = TIME_SETTINGS_VERSION;
+
+    fn is_valid(&self) -> bool {
+        UTC_OFFSET_RANGE.contains(&self.utc_offset)
+    }
+}
+
+impl TimeSettings {
+    pub fn validate(self) -> Result<Self> {
+        if !UTC_OFFSET_RANGE.contains(&self.utc_offset) {
+            return Err(Error::InvalidUtcOffset);
+        }
+        Ok(self)
+    }
+}
+
+/// Where the current time of the clock comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSource {
+    #[default]
+    None,
+    Sntp,
+    Manual,
+}
+
+/// Wall-clock kept as the unix time at the moment it was last set, advanced by the monotonic
+/// timer of the chip
+#[derive(Debug, Default)]
+pub struct Clock {
+    set_at: Option<(u64, Instant)>,
+    pub source: TimeSource,
+}
+
+impl Clock {
+    pub fn set(&mut self, unix_millis: u64, source: TimeSource) {
+        self.set_at = Some((unix_millis, Instant::now()));
+        self.source = source;
+    }
+
+    /// Current unix time in milliseconds, `None` until the clock has been set
+    pub fn now_millis(&self) -> Option<u64> {
+        self.set_at
+            .map(|(unix_millis, instant)| unix_millis + instant.elapsed().as_millis())
+    }
+}
@@ -0,0 +1,176 @@
+use core::sync::atomic::Ordering;
+
+use embassy_time::{Duration, Instant, Ticker};
+use serde::{Deserialize, Serialize};
+use sl1_protocol::schedule::{EVERY_DAY, MINUTES_PER_DAY, Trigger};
+
+use crate::settings::{PresetId, PresetSettings, Settings};
+use crate::{
+    CLOCK, Error, MAX_SUNRISE_DURATION, Result, SETTINGS, SHOULD_UPDATE, TIME_SETTINGS,
+    TIMER_CHECK_INTERVAL, TIMERS,
+};
+
+/// Fades the lights out over its duration and then turns them off
+#[derive(Debug, Clone, Copy)]
+pub struct SleepTimer {
+    pub ends_at: Instant,
+    pub duration: Duration,
+}
+
+impl SleepTimer {
+    pub fn new(minutes: u8) -> Result<Self> {
+        if minutes == 0 {
+            return Err(Error::InvalidTimer);
+        }
+        let duration = Duration::from_secs(minutes as u64 * 60);
+        Ok(Self {
+            ends_at: Instant::now() + duration,
+            duration,
+        })
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.ends_at.saturating_duration_since(Instant::now())
+    }
+
+    /// Part of the brightness left, from `u16::MAX` when armed down to 0 when it ends
+    pub fn level(&self) -> u16 {
+        (self.remaining().as_millis() * u16::MAX as u64 / self.duration.as_millis().max(1)) as u16
+    }
+}
+
+/// Sunrise requested by a client, the time of the day it should end at
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SunriseRequest {
+    /// Local time of the day in minutes since midnight
+    pub minute: u16,
+    /// Duration of the sunrise in minutes
+    pub duration: u16,
+}
+
+/// Ramps the lights from deep red to warm white, ending at the target time. The lights are left
+/// on showing warm white once it is over, until what they would show otherwise changes
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SunriseAlarm {
+    /// Unix time in seconds
+    pub target: u64,
+    /// Duration in minutes
+    pub duration: u16,
+    #[serde(rename = "started")]
+    pub is_started: bool,
+}
+
+impl SunriseAlarm {
+    /// Alarm ending at the next occurrence of the requested time of the day
+    pub async fn new(request: SunriseRequest) -> Result<Self> {
+        if request.minute >= MINUTES_PER_DAY
+            || request.duration == 0
+            || request.duration > MAX_SUNRISE_DURATION
+        {
+            return Err(Error::InvalidTimer);
+        }
+        let now = CLOCK
+            .get()
+            .lock()
+            .await
+            .now_millis()
+            .ok_or(Error::ClockNotSet)?;
+        let utc_offset = TIME_SETTINGS.get().lock().await.utc_offset;
+        let trigger = Trigger {
+            days: EVERY_DAY,
+            minute: request.minute,
+        };
+        let target = trigger
+            .next_after((now / 1000) as i64, utc_offset)
+            .ok_or(Error::InvalidTimer)?;
+        Ok(Self {
+            target: target as u64,
+            duration: request.duration,
+            is_started: false,
+        })
+    }
+
+    /// Unix time in seconds
+    pub fn starts_at(&self) -> u64 {
+        self.target.saturating_sub(self.duration as u64 * 60)
+    }
+}
+
+/// Part of the settings that decides what the lights show
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShownSettings {
+    is_on: bool,
+    preset_id: PresetId,
+    preset_settings: PresetSettings,
+    active_playlist: Option<u8>,
+    active_clip: Option<u8>,
+}
+
+impl ShownSettings {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            is_on: settings.is_on,
+            preset_id: settings.current_preset_id,
+            preset_settings: settings.preset_settings[settings.current_preset_id.id() as usize],
+            active_playlist: settings.active_playlist,
+            active_clip: settings.active_clip,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timers {
+    pub sleep: Option<SleepTimer>,
+    pub sunrise: Option<SunriseAlarm>,
+    /// Settings when the last sunrise ended, its last color is shown until they change
+    pub sunrise_end: Option<ShownSettings>,
+}
+
+/// Starts and ends the timers, the fading in between is done by the renderer
+#[embassy_executor::task]
+pub async fn timer_task() -> ! {
+    let mut ticker = Ticker::every(TIMER_CHECK_INTERVAL);
+
+    loop {
+        ticker.next().await;
+
+        let now = CLOCK.get().lock().await.now_millis().map(|now| now / 1000);
+        let mut timers = TIMERS.get().lock().await;
+
+        let is_sleep_over = timers
+            .sleep
+            .is_some_and(|sleep| sleep.ends_at <= Instant::now());
+        if is_sleep_over {
+            timers.sleep = None;
+        }
+
+        let mut is_sunrise_over = false;
+        if let (Some(sunrise), Some(now)) = (&mut timers.sunrise, now) {
+            if now >= sunrise.target {
+                is_sunrise_over = true;
+            } else if !sunrise.is_started && now >= sunrise.starts_at() {
+                log::info!(target: "Timers", "Sunrise started");
+                sunrise.is_started = true;
+                SHOULD_UPDATE.store(true, Ordering::Relaxed);
+            }
+        }
+        if is_sunrise_over {
+            timers.sunrise = None;
+        }
+        // The server locks the settings before the timers
+        drop(timers);
+
+        if is_sleep_over {
+            log::info!(target: "Timers", "Sleep timer ended");
+            SETTINGS.get().lock().await.is_on = false;
+            SHOULD_UPDATE.store(true, Ordering::Relaxed);
+        }
+        if is_sunrise_over {
+            log::info!(target: "Timers", "Sunrise ended");
+            let mut settings = SETTINGS.get().lock().await;
+            settings.is_on = true;
+            TIMERS.get().lock().await.sunrise_end = Some(ShownSettings::from_settings(&settings));
+            SHOULD_UPDATE.store(true, Ordering::Relaxed);
+        }
+    }
+}
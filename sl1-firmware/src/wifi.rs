@@ -1,23 +1,67 @@
+use embassy_futures::select::{Either, select};
 use embassy_time::{Duration, Timer};
-use esp_wifi::wifi::{WifiController, WifiEvent};
+use esp_wifi::wifi::{Configuration, WifiController, WifiEvent};
+use esp_wifi_sys::include::{ESP_OK, esp_wifi_sta_get_ap_info, wifi_ap_record_t};
 
-use crate::DEFAULT_WIFI_SSID;
+use crate::settings::WifiSettings;
+use crate::{STATS, UPDATE_WIFI};
 
 #[embassy_executor::task]
-pub async fn wifi_task(mut controller: WifiController<'static>) -> ! {
+pub async fn wifi_task(
+    mut controller: WifiController<'static>,
+    mut wifi_settings: WifiSettings,
+) -> ! {
     controller.start_async().await.unwrap();
     loop {
-        match controller.connect_async().await {
+        let update = match controller.connect_async().await {
             Ok(_) => {
-                log::info!(target: "WIFI", "Connected to {} wifi network.", DEFAULT_WIFI_SSID);
-                controller.wait_for_event(WifiEvent::StaDisconnected).await;
-                log::info!(target: "WIFI", "Disconnected from network, reconnecting...");
+                log::info!(target: "WIFI", "Connected to {} wifi network.", wifi_settings.ssid());
+                // Settings are sent on every settings change, the connection is only dropped
+                // when the credentials are different
+                loop {
+                    let disconnected = controller.wait_for_event(WifiEvent::StaDisconnected);
+                    match select(disconnected, UPDATE_WIFI.wait()).await {
+                        Either::First(()) => {
+                            log::info!(target: "WIFI", "Disconnected from network, reconnecting...");
+                            STATS.get().lock().await.record_wifi_reconnect();
+                            break None;
+                        }
+                        Either::Second(new_settings) if new_settings != wifi_settings => {
+                            break Some(new_settings);
+                        }
+                        Either::Second(_) => {}
+                    }
+                }
             }
 
             Err(err) => {
                 log::error!(target: "WIFI", "Error connecting to wifi network: {err:?}.\nReconnecting...");
-                Timer::after(Duration::from_millis(5000)).await;
+                STATS.get().lock().await.record_wifi_reconnect();
+                let retry = Timer::after(Duration::from_millis(5000));
+                match select(retry, UPDATE_WIFI.wait()).await {
+                    Either::First(()) => None,
+                    Either::Second(new_settings) => Some(new_settings),
+                }
             }
         };
+
+        if let Some(new_settings) = update.filter(|new_settings| *new_settings != wifi_settings) {
+            log::info!(target: "WIFI", "Credentials changed, connecting to {}", new_settings.ssid());
+            if let Err(err) = controller.disconnect_async().await {
+                log::error!(target: "WIFI", "Error disconnecting from wifi network: {err:?}");
+            }
+            let configuration = Configuration::Client(new_settings.client_configuration());
+            if let Err(err) = controller.set_configuration(&configuration) {
+                log::error!(target: "WIFI", "Error applying wifi settings: {err:?}");
+            }
+            wifi_settings = new_settings;
+        }
     }
 }
+
+/// Signal strength of the access point in dBm, `None` while disconnected
+pub fn rssi() -> Option<i8> {
+    let mut ap_info: wifi_ap_record_t = unsafe { core::mem::zeroed() };
+    let result = unsafe { esp_wifi_sta_get_ap_info(&mut ap_info) };
+    (result == ESP_OK as i32).then_some(ap_info.rssi)
+}
@@ -0,0 +1,118 @@
+//! Format of the animation clips that are recorded by the desktop app and played back by the
+//! device.
+//!
+//! A clip starts with a [`ClipHeader`] followed by `frame_count` frames. Every frame is prefixed
+//! with its length as a 16-bit little-endian integer and starts with its [`FrameKind`]:
+//! - key frames are run-length encoded as `[count, r, g, b]` runs covering every LED,
+//! - delta frames are a sequence of `[skip, count, count * [r, g, b]]` spans, LEDs that are not
+//!   covered by any span keep their color from the previous frame.
+//!
+//! The first frame of a clip must be a key frame, so that it can be looped.
+
+pub const CLIP_HEADER_LENGTH: usize = 5;
+pub const FRAME_LENGTH_PREFIX: usize = 2;
+/// Largest encoded frame, a key frame of 255 LEDs with no repeated colors still fits
+pub const MAX_FRAME_LENGTH: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipError {
+    InvalidHeader,
+    InvalidFrameKind,
+    FrameTooLong,
+    Truncated,
+}
+
+impl core::fmt::Display for ClipError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipHeader {
+    pub frame_count: u16,
+    pub fps: u8,
+    pub led_count: u16,
+}
+
+impl ClipHeader {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ClipError> {
+        let bytes = bytes
+            .get(..CLIP_HEADER_LENGTH)
+            .ok_or(ClipError::InvalidHeader)?;
+        let header = Self {
+            frame_count: u16::from_le_bytes([bytes[0], bytes[1]]),
+            fps: bytes[2],
+            led_count: u16::from_le_bytes([bytes[3], bytes[4]]),
+        };
+        if header.frame_count == 0 || header.fps == 0 {
+            return Err(ClipError::InvalidHeader);
+        }
+        Ok(header)
+    }
+
+    pub fn to_bytes(&self) -> [u8; CLIP_HEADER_LENGTH] {
+        let [f0, f1] = self.frame_count.to_le_bytes();
+        let [l0, l1] = self.led_count.to_le_bytes();
+        [f0, f1, self.fps, l0, l1]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum FrameKind {
+    Key = 0x00,
+    Delta = 0x01,
+}
+
+impl TryFrom<u8> for FrameKind {
+    type Error = ClipError;
+
+    fn try_from(value: u8) -> Result<Self, ClipError> {
+        match value {
+            0x00 => Ok(Self::Key),
+            0x01 => Ok(Self::Delta),
+            _ => Err(ClipError::InvalidFrameKind),
+        }
+    }
+}
+
+/// Decodes a single frame, without its length prefix, on top of the previous frame. LEDs past the
+/// end of `frame` are skipped, so clips recorded for longer strips can still be played.
+pub fn decode_frame(data: &[u8], frame: &mut [[u8; 3]]) -> Result<(), ClipError> {
+    let (&kind, mut rest) = data.split_first().ok_or(ClipError::Truncated)?;
+
+    match FrameKind::try_from(kind)? {
+        FrameKind::Key => {
+            frame.fill([0; 3]);
+            let mut led = 0;
+            while !rest.is_empty() {
+                let run = rest.get(..4).ok_or(ClipError::Truncated)?;
+                let end = (led + run[0] as usize).min(frame.len());
+                if let Some(leds) = frame.get_mut(led..end) {
+                    leds.fill([run[1], run[2], run[3]]);
+                }
+                led += run[0] as usize;
+                rest = &rest[4..];
+            }
+        }
+        FrameKind::Delta => {
+            let mut led = 0;
+            while !rest.is_empty() {
+                let span = rest.get(..2).ok_or(ClipError::Truncated)?;
+                let (skip, count) = (span[0] as usize, span[1] as usize);
+                let colors = rest.get(2..2 + count * 3).ok_or(ClipError::Truncated)?;
+                led += skip;
+                for (offset, color) in colors.chunks_exact(3).enumerate() {
+                    if let Some(target) = frame.get_mut(led + offset) {
+                        *target = [color[0], color[1], color[2]];
+                    }
+                }
+                led += count;
+                rest = &rest[2 + count * 3..];
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Conversion between the 8-bit frames rendered by the presets and the 16-bit frames the device
+//! composites.
+//!
+//! 16-bit channels span the whole `0..=65535` range, an 8-bit value `v` at full brightness becomes
+//! `v * 257`. Quantizing back to the 8-bit output of the strip divides by the same factor, so that
+//! full brightness gives back the rendered colors exactly.
+
+/// Distance between two consecutive 8-bit values in the 16-bit range
+const STEP: u32 = 257;
+
+/// Scales an 8-bit channel by the brightness into the 16-bit range
+pub fn expand(value: u8, brightness: u8) -> u16 {
+    (value as u32 * brightness as u32 * STEP / 255) as u16
+}
+
+/// Quantizes 16-bit frames of `N` LEDs, carrying the remainder of every channel over to the
+/// following frames, so that the average output matches the 16-bit value
+pub struct Ditherer<const N: usize> {
+    residuals: [[u16; 3]; N],
+}
+
+impl<const N: usize> Default for Ditherer<N> {
+    fn default() -> Self {
+        Self {
+            residuals: [[0; 3]; N],
+        }
+    }
+}
+
+impl<const N: usize> Ditherer<N> {
+    pub fn quantize(&mut self, input: &[[u16; 3]; N], output: &mut [[u8; 3]; N]) {
+        input
+            .iter()
+            .flatten()
+            .zip(self.residuals.iter_mut().flatten())
+            .zip(output.iter_mut().flatten())
+            .for_each(|((&value, residual), out)| {
+                let value = value as u32;
+                *residual += (value % STEP) as u16;
+                // Cannot overflow, as a value with a remainder is at most 254 steps
+                *out = (value / STEP) as u8;
+                if *residual as u32 >= STEP {
+                    *residual -= STEP as u16;
+                    *out += 1;
+                }
+            });
+    }
+}
+
+pub fn quantize_rounded<const N: usize>(input: &[[u16; 3]; N], output: &mut [[u8; 3]; N]) {
+    input
+        .iter()
+        .flatten()
+        .zip(output.iter_mut().flatten())
+        .for_each(|(&value, out)| *out = ((value as u32 + STEP / 2) / STEP) as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_brightness_gives_back_input() {
+        let mut ditherer = Ditherer::<1>::default();
+        for value in 0..=u8::MAX {
+            let input = [[expand(value, 255); 3]];
+            let mut output = [[0; 3]];
+
+            quantize_rounded(&input, &mut output);
+            assert_eq!(output, [[value; 3]]);
+
+            // The residuals stay empty, so the output never flickers
+            for _ in 0..4 {
+                ditherer.quantize(&input, &mut output);
+                assert_eq!(output, [[value; 3]]);
+            }
+        }
+    }
+
+    #[test]
+    fn dithered_average_matches_target() {
+        for target in (0..=u16::MAX)
+            .step_by(97)
+            .chain([1, 256, 257, 65278, 65534, 65535])
+        {
+            let mut ditherer = Ditherer::<1>::default();
+            let mut output = [[0; 3]];
+            let mut sum = 0;
+            for _ in 0..STEP {
+                ditherer.quantize(&[[target; 3]], &mut output);
+                sum += output[0][0] as u32;
+            }
+            assert_eq!(sum, target as u32, "target {target}");
+        }
+    }
+
+    #[test]
+    fn dithered_output_stays_within_one_step() {
+        let mut ditherer = Ditherer::<1>::default();
+        let mut output = [[0; 3]];
+        for _ in 0..1000 {
+            ditherer.quantize(&[[expand(128, 200); 3]], &mut output);
+            let low = expand(128, 200) as u32 / STEP;
+            assert!((low..=low + 1).contains(&(output[0][0] as u32)));
+        }
+    }
+
+    #[test]
+    fn rounded_quantization_picks_nearest_value() {
+        let mut output = [[0; 3]];
+        quantize_rounded(
+            &[[128 * 257 + 128, 128 * 257 + 129, 200 * 257]],
+            &mut output,
+        );
+        assert_eq!(output, [[128, 129, 200]]);
+    }
+}
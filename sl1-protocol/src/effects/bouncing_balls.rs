@@ -0,0 +1,158 @@
+use crate::effects::Frame;
+use crate::effects::rng::Rng;
+use crate::effects::utils::{add_color, scale_color, sqrt_u32};
+
+/// Positions and velocities are fixed-point numbers in LEDs (per frame) with 8 fractional bits
+const FRACTION_BITS: u32 = 8;
+const ONE: i32 = 1 << FRACTION_BITS;
+const MAX_BALLS: usize = 8;
+/// Share of the velocity kept after a bounce, out of 256
+const DAMPING: i32 = 220;
+/// Balls slower than this after a bounce are thrown up again
+const MIN_BOUNCE_VELOCITY: i32 = ONE / 4;
+
+#[derive(Clone, Copy, Default)]
+struct Ball {
+    position: i32,
+    velocity: i32,
+}
+
+/// Balls in palette colors thrown up from the start of the strip, bouncing until they come to
+/// rest
+pub struct BouncingBalls<const N: usize> {
+    balls: [Ball; MAX_BALLS],
+    colors: [[u8; 3]; MAX_BALLS],
+    count: usize,
+    gravity: i32,
+    rng: Rng,
+}
+
+impl<const N: usize> BouncingBalls<N> {
+    const TOP: i32 = (N as i32 - 1) * ONE;
+
+    pub fn new(gravity: u8, balls: u8, seed: u32, color_at: impl Fn(u8) -> [u8; 3]) -> Self {
+        let count = 1 + balls as usize * (MAX_BALLS - 1) / 255;
+        let mut bouncing_balls = Self {
+            balls: [Ball::default(); MAX_BALLS],
+            colors: core::array::from_fn(|idx| color_at((idx * 255 / count) as u8)),
+            count,
+            gravity: 1 + gravity as i32 / 16,
+            rng: Rng::new(seed),
+        };
+        for idx in 0..count {
+            bouncing_balls.throw(idx);
+        }
+        bouncing_balls
+    }
+
+    pub fn render(&mut self, frame: &mut Frame<N>) {
+        frame.fill([0; 3]);
+
+        for idx in 0..self.count {
+            let ball = &mut self.balls[idx];
+            ball.velocity -= self.gravity;
+            ball.position += ball.velocity;
+
+            if ball.position <= 0 {
+                ball.position = 0;
+                ball.velocity = -ball.velocity * DAMPING / 256;
+                if ball.velocity < MIN_BOUNCE_VELOCITY {
+                    self.throw(idx);
+                }
+            }
+
+            // Spread the ball between the two nearest LEDs to keep its motion smooth
+            let ball = self.balls[idx];
+            let led = (ball.position.min(Self::TOP) >> FRACTION_BITS) as usize;
+            let fraction = (ball.position & (ONE - 1)) as u8;
+            let color = self.colors[idx];
+            frame[led] = add_color(&frame[led], &scale_color(&color, 255 - fraction));
+            if led + 1 < N {
+                frame[led + 1] = add_color(&frame[led + 1], &scale_color(&color, fraction));
+            }
+        }
+    }
+
+    /// Throws the ball up from the bottom of the strip to a random height in the upper half
+    fn throw(&mut self, idx: usize) {
+        let height = Self::TOP / 2 + ((self.rng.next_u8() as i32 * Self::TOP / 2) >> 8);
+        // v^2 = 2gh, the fixed-point scale factors cancel out
+        let velocity = sqrt_u32(2 * self.gravity as u32 * height as u32) as i32;
+        self.balls[idx] = Ball {
+            position: 0,
+            velocity,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(position: u8) -> [u8; 3] {
+        [position; 3]
+    }
+
+    fn render_frames<const N: usize>(balls: &mut BouncingBalls<N>, count: usize) -> Frame<N> {
+        let mut frame = [[0; 3]; N];
+        for _ in 0..count {
+            balls.render(&mut frame);
+        }
+        frame
+    }
+
+    #[test]
+    fn same_seed_renders_same_frames() {
+        let mut a = BouncingBalls::<64>::new(128, 255, 42, gray);
+        let mut b = BouncingBalls::<64>::new(128, 255, 42, gray);
+        let mut frame_a = [[0; 3]; 64];
+        let mut frame_b = [[0; 3]; 64];
+        for _ in 0..500 {
+            a.render(&mut frame_a);
+            b.render(&mut frame_b);
+            assert_eq!(frame_a, frame_b);
+        }
+    }
+
+    #[test]
+    fn balls_stay_on_the_strip() {
+        for gravity in [0, 128, 255] {
+            let mut balls = BouncingBalls::<16>::new(gravity, 255, 42, gray);
+            for _ in 0..2000 {
+                render_frames(&mut balls, 1);
+                for ball in &balls.balls[..balls.count] {
+                    assert!(ball.position >= 0);
+                    // Thrown to the upper half at most
+                    assert!(ball.position <= BouncingBalls::<16>::TOP);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn single_ball_starts_at_the_bottom() {
+        let mut balls = BouncingBalls::<16>::new(128, 0, 42, |_| [255, 0, 0]);
+        assert_eq!(balls.count, 1);
+        let frame = render_frames(&mut balls, 1);
+        // The ball has just been thrown and lights up at most the first two LEDs
+        assert_ne!(frame[0], [0; 3]);
+        assert!(frame[2..].iter().all(|led| *led == [0; 3]));
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut balls = BouncingBalls::<8>::new(128, 96, 0x89ab_cdef, gray);
+        assert_eq!(render_frames(&mut balls, 100), SNAPSHOT);
+    }
+
+    const SNAPSHOT: Frame<8> = [
+        [0, 0, 0],
+        [134, 134, 134],
+        [119, 119, 119],
+        [0, 0, 0],
+        [0, 0, 0],
+        [0, 0, 0],
+        [0, 0, 0],
+        [0, 0, 0],
+    ];
+}
@@ -0,0 +1,196 @@
+use crate::effects::Frame;
+use crate::effects::rng::Rng;
+use crate::effects::utils::{add_color, scale_color, sqrt_u32};
+
+/// Positions and velocities are fixed-point numbers in LEDs (per frame) with 8 fractional bits
+const FRACTION_BITS: u32 = 8;
+const ONE: i32 = 1 << FRACTION_BITS;
+const GRAVITY: i32 = 2;
+/// Share of the particle velocity kept each frame, out of 256
+const DRAG: i32 = 245;
+const MAX_PARTICLES: usize = 48;
+const ROCKET_COLOR: [u8; 3] = [255, 160, 64];
+
+#[derive(Clone, Copy, Default)]
+struct Particle {
+    position: i32,
+    velocity: i32,
+    color: [u8; 3],
+    /// Remaining brightness of the particle, 0 means that the particle is dead
+    life: u8,
+}
+
+enum Rocket {
+    Waiting(u16),
+    Flying { position: i32, velocity: i32 },
+}
+
+/// Rockets launched at random intervals that burst into particles of a random palette color
+pub struct Fireworks<const N: usize> {
+    rocket: Rocket,
+    particles: [Particle; MAX_PARTICLES],
+    particles_per_burst: usize,
+    max_wait: u16,
+    rng: Rng,
+}
+
+impl<const N: usize> Fireworks<N> {
+    const TOP: i32 = (N as i32 - 1) * ONE;
+
+    pub fn new(launch_rate: u8, particles: u8, seed: u32) -> Self {
+        Self {
+            rocket: Rocket::Waiting(0),
+            particles: [Particle::default(); MAX_PARTICLES],
+            particles_per_burst: 4 + particles as usize * 20 / 255,
+            max_wait: 10 + (255 - launch_rate as u16) * 2,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame<N>, color_at: impl Fn(u8) -> [u8; 3]) {
+        frame.fill([0; 3]);
+
+        self.update_rocket(frame, color_at);
+
+        for particle in self.particles.iter_mut().filter(|p| p.life > 0) {
+            particle.velocity = particle.velocity * DRAG / 256 - GRAVITY;
+            particle.position += particle.velocity;
+            particle.life = particle.life.saturating_sub(3);
+
+            if !(0..=Self::TOP).contains(&particle.position) {
+                particle.life = 0;
+                continue;
+            }
+
+            let led = (particle.position >> FRACTION_BITS) as usize;
+            let color = scale_color(&particle.color, particle.life);
+            frame[led] = add_color(&frame[led], &color);
+        }
+    }
+
+    fn update_rocket(&mut self, frame: &mut Frame<N>, color_at: impl Fn(u8) -> [u8; 3]) {
+        match &mut self.rocket {
+            Rocket::Waiting(0) => {
+                // Launch with a velocity that makes the rocket peak in the upper part of the strip
+                let height = Self::TOP / 2 + ((self.rng.next_u8() as i32 * Self::TOP / 3) >> 8);
+                let velocity = sqrt_u32(2 * GRAVITY as u32 * height as u32) as i32;
+                self.rocket = Rocket::Flying {
+                    position: 0,
+                    velocity,
+                };
+            }
+            Rocket::Waiting(frames) => *frames -= 1,
+            Rocket::Flying { position, velocity } => {
+                *velocity -= GRAVITY;
+                *position += *velocity;
+
+                if *velocity <= 0 {
+                    let burst_position = *position;
+                    self.burst(burst_position, color_at);
+                    let wait = 1 + self.rng.next_u32() as u16 % self.max_wait;
+                    self.rocket = Rocket::Waiting(wait);
+                } else {
+                    // The rocket peaks below the launch height, the index is bounded all the same
+                    let led = ((*position >> FRACTION_BITS) as usize).min(N - 1);
+                    frame[led] = ROCKET_COLOR;
+                }
+            }
+        }
+    }
+
+    fn burst(&mut self, position: i32, color_at: impl Fn(u8) -> [u8; 3]) {
+        let color = color_at(self.rng.next_u8());
+        let mut spawned = 0;
+        for particle in self.particles.iter_mut() {
+            if spawned == self.particles_per_burst {
+                break;
+            }
+            if particle.life > 0 {
+                continue;
+            }
+            *particle = Particle {
+                position,
+                // Random velocity in both directions of up to 1.5 LEDs per frame
+                velocity: (self.rng.next_u8() as i32 - 128) * 3,
+                color,
+                life: 255,
+            };
+            spawned += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(position: u8) -> [u8; 3] {
+        [position; 3]
+    }
+
+    fn render_frames<const N: usize>(fireworks: &mut Fireworks<N>, count: usize) -> Frame<N> {
+        let mut frame = [[0; 3]; N];
+        for _ in 0..count {
+            fireworks.render(&mut frame, gray);
+        }
+        frame
+    }
+
+    #[test]
+    fn same_seed_renders_same_frames() {
+        let mut a = Fireworks::<64>::new(255, 255, 42);
+        let mut b = Fireworks::<64>::new(255, 255, 42);
+        let mut frame_a = [[0; 3]; 64];
+        let mut frame_b = [[0; 3]; 64];
+        for _ in 0..500 {
+            a.render(&mut frame_a, gray);
+            b.render(&mut frame_b, gray);
+            assert_eq!(frame_a, frame_b);
+        }
+    }
+
+    #[test]
+    fn rocket_launches_from_the_bottom() {
+        let mut fireworks = Fireworks::<64>::new(128, 128, 42);
+        render_frames(&mut fireworks, 1);
+        let frame = render_frames(&mut fireworks, 1);
+        assert_eq!(frame[0], ROCKET_COLOR);
+    }
+
+    fn assert_rocket_stays_on_strip<const N: usize>(seed: u32) {
+        let mut fireworks = Fireworks::<N>::new(255, 255, seed);
+        for _ in 0..500 {
+            render_frames(&mut fireworks, 1);
+            if let Rocket::Flying { position, .. } = fireworks.rocket {
+                assert!((0..=Fireworks::<N>::TOP).contains(&position));
+            }
+        }
+    }
+
+    #[test]
+    fn rocket_stays_on_strip() {
+        for seed in 0..64 {
+            assert_rocket_stays_on_strip::<1>(seed);
+            assert_rocket_stays_on_strip::<2>(seed);
+            assert_rocket_stays_on_strip::<5>(seed);
+            assert_rocket_stays_on_strip::<300>(seed);
+        }
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut fireworks = Fireworks::<8>::new(128, 128, 0x89ab_cdef);
+        assert_eq!(render_frames(&mut fireworks, 40), SNAPSHOT);
+    }
+
+    const SNAPSHOT: Frame<8> = [
+        [0, 0, 0],
+        [0, 0, 0],
+        [0, 0, 0],
+        [141, 141, 141],
+        [0, 0, 0],
+        [255, 255, 255],
+        [255, 255, 255],
+        [0, 0, 0],
+    ];
+}
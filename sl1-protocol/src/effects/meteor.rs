@@ -0,0 +1,118 @@
+use crate::effects::Frame;
+use crate::effects::rng::Rng;
+use crate::effects::utils::scale_color;
+
+/// Meteor head position is kept with 4 fractional bits so that slow meteors move smoothly
+const POSITION_FRACTION_BITS: u32 = 4;
+
+/// Single meteor running along the strip with a sparkling trail
+pub struct Meteor<const N: usize> {
+    color: [u8; 3],
+    trail: Frame<N>,
+    rng: Rng,
+    position: u32,
+    speed: u32,
+    fade: u8,
+}
+
+impl<const N: usize> Meteor<N> {
+    pub fn new(color: [u8; 3], speed: u8, trail: u8, seed: u32) -> Self {
+        Self {
+            color,
+            trail: [[0; 3]; N],
+            rng: Rng::new(seed),
+            position: 0,
+            speed: speed as u32 / 8 + 1,
+            // Longer trails fade slower
+            fade: 255 - trail / 2,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame<N>) {
+        // Fading every LED on each frame makes the trail look too uniform, so only some of them
+        // are faded, which breaks the trail up into sparks
+        for led in self.trail.iter_mut() {
+            if self.rng.next_u8() < 160 {
+                *led = scale_color(led, self.fade);
+            }
+        }
+
+        let head = (self.position >> POSITION_FRACTION_BITS) as usize;
+        if head < N {
+            self.trail[head] = self.color;
+        }
+
+        // Let the trail run off the end of the strip before restarting
+        self.position += self.speed;
+        if self.position >> POSITION_FRACTION_BITS >= 2 * N as u32 {
+            self.position = 0;
+        }
+
+        frame.copy_from_slice(&self.trail);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_frames<const N: usize>(meteor: &mut Meteor<N>, count: usize) -> Frame<N> {
+        let mut frame = [[0; 3]; N];
+        for _ in 0..count {
+            meteor.render(&mut frame);
+        }
+        frame
+    }
+
+    #[test]
+    fn same_seed_renders_same_frames() {
+        let mut a = Meteor::<64>::new([255; 3], 128, 128, 42);
+        let mut b = Meteor::<64>::new([255; 3], 128, 128, 42);
+        let mut frame_a = [[0; 3]; 64];
+        let mut frame_b = [[0; 3]; 64];
+        for _ in 0..200 {
+            a.render(&mut frame_a);
+            b.render(&mut frame_b);
+            assert_eq!(frame_a, frame_b);
+        }
+    }
+
+    #[test]
+    fn head_moves_along_the_strip() {
+        // Full speed moves the head by 32 / 16 = 2 LEDs per frame
+        let mut meteor = Meteor::<16>::new([255; 3], 255, 0, 42);
+        for frame_idx in 0..8 {
+            let frame = render_frames(&mut meteor, 1);
+            assert_eq!(frame[frame_idx * 2], [255; 3]);
+            assert!(frame[frame_idx * 2 + 1..].iter().all(|led| *led == [0; 3]));
+        }
+    }
+
+    #[test]
+    fn restarts_after_running_off_the_strip() {
+        let mut meteor = Meteor::<16>::new([255; 3], 255, 0, 42);
+        // The head runs over 2 * 16 LEDs in 16 frames, the trail then has faded out
+        let frame = render_frames(&mut meteor, 16 + 1);
+        assert_eq!(frame[0], [255; 3]);
+    }
+
+    #[test]
+    fn snapshot() {
+        let frame = render_frames(
+            &mut Meteor::<8>::new([255, 128, 0], 128, 128, 0x89ab_cdef),
+            10,
+        );
+        assert_eq!(frame, SNAPSHOT);
+    }
+
+    const SNAPSHOT: Frame<8> = [
+        [32, 14, 0],
+        [78, 38, 0],
+        [43, 20, 0],
+        [43, 20, 0],
+        [141, 70, 0],
+        [105, 52, 0],
+        [105, 52, 0],
+        [190, 95, 0],
+    ];
+}
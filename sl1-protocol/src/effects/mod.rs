@@ -0,0 +1,14 @@
+//! Frame logic of the randomized presets. It does not depend on the state of the device, so that
+//! the frames rendered from a seed can be checked on the host. Palette colors are looked up
+//! through a function from the palette position to the color.
+
+pub mod bouncing_balls;
+pub mod fireworks;
+pub mod meteor;
+pub mod rng;
+pub mod sparkle;
+pub mod twinkle;
+pub mod utils;
+
+/// Colors of a strip of `N` LEDs
+pub type Frame<const N: usize> = [[u8; 3]; N];
@@ -0,0 +1,51 @@
+/// Xorshift generator used by the randomized effects. An effect started from the same seed
+/// renders the same sequence of frames, on the device as well as on the host.
+pub struct Rng(u32);
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        // Xorshift gets stuck on a zero state
+        Self(seed.max(1))
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u32() >> 24) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_reference_xorshift32() {
+        let mut rng = Rng::new(1);
+        assert_eq!(rng.next_u32(), 270369);
+        assert_eq!(rng.next_u32(), 67634689);
+        assert_eq!(rng.next_u32(), 2647435461);
+    }
+
+    #[test]
+    fn zero_seed_does_not_get_stuck() {
+        let mut rng = Rng::new(0);
+        assert_ne!(rng.next_u32(), 0);
+    }
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut a = Rng::new(0x89ab_cdef);
+        let mut b = Rng::new(0x89ab_cdef);
+        for _ in 0..100 {
+            assert_eq!(a.next_u8(), b.next_u8());
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::effects::Frame;
+use crate::effects::rng::Rng;
+use crate::effects::utils::whiten;
+
+/// Base color with random white sparks that decay back to it
+pub struct Sparkle<const N: usize> {
+    color: [u8; 3],
+    /// Whiteness of every LED, decays back to the base color after a sparkle
+    sparks: [u8; N],
+    rng: Rng,
+    decay: u8,
+    density: u8,
+}
+
+impl<const N: usize> Sparkle<N> {
+    pub fn new(color: [u8; 3], speed: u8, density: u8, seed: u32) -> Self {
+        Self {
+            color,
+            sparks: [0; N],
+            rng: Rng::new(seed),
+            decay: (speed / 4).max(1),
+            density,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame<N>) {
+        for (spark, led) in self.sparks.iter_mut().zip(frame.iter_mut()) {
+            *spark = spark.saturating_sub(self.decay);
+            // Each LED sparkles with a chance of density / 2^12 per frame
+            if (self.rng.next_u32() >> 20) < self.density as u32 {
+                *spark = u8::MAX;
+            }
+            *led = whiten(&self.color, *spark);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_frames<const N: usize>(sparkle: &mut Sparkle<N>, count: usize) -> Frame<N> {
+        let mut frame = [[0; 3]; N];
+        for _ in 0..count {
+            sparkle.render(&mut frame);
+        }
+        frame
+    }
+
+    #[test]
+    fn same_seed_renders_same_frames() {
+        let mut a = Sparkle::<64>::new([0, 0, 64], 128, 255, 42);
+        let mut b = Sparkle::<64>::new([0, 0, 64], 128, 255, 42);
+        let mut frame_a = [[0; 3]; 64];
+        let mut frame_b = [[0; 3]; 64];
+        for _ in 0..200 {
+            a.render(&mut frame_a);
+            b.render(&mut frame_b);
+            assert_eq!(frame_a, frame_b);
+        }
+    }
+
+    #[test]
+    fn zero_density_shows_base_color() {
+        let frame = render_frames(&mut Sparkle::<64>::new([0, 0, 64], 128, 0, 42), 100);
+        assert_eq!(frame, [[0, 0, 64]; 64]);
+    }
+
+    #[test]
+    fn sparks_decay_to_base_color() {
+        let mut sparkle = Sparkle::<1>::new([0, 0, 64], 128, 0, 42);
+        sparkle.sparks[0] = u8::MAX;
+        let mut frame = [[0; 3]];
+        sparkle.render(&mut frame);
+        assert!(frame[0][0] > 200);
+        // Decays by 32 per frame
+        let frame = render_frames(&mut sparkle, 7);
+        assert_eq!(frame, [[0, 0, 64]]);
+    }
+
+    #[test]
+    fn snapshot() {
+        let frame = render_frames(
+            &mut Sparkle::<8>::new([0, 0, 64], 128, 255, 0x89ab_cdef),
+            300,
+        );
+        assert_eq!(frame, SNAPSHOT);
+    }
+
+    const SNAPSHOT: Frame<8> = [
+        [0, 0, 64],
+        [0, 0, 64],
+        [0, 0, 64],
+        [126, 126, 158],
+        [222, 222, 230],
+        [0, 0, 64],
+        [0, 0, 64],
+        [0, 0, 64],
+    ];
+}
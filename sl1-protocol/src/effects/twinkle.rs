@@ -0,0 +1,132 @@
+use crate::effects::Frame;
+use crate::effects::rng::Rng;
+use crate::effects::utils::scale_color;
+
+#[derive(Clone, Copy, Default)]
+struct Star {
+    /// Position in the fade in/fade out cycle, 0 means that the LED is dark
+    phase: u8,
+    color: [u8; 3],
+}
+
+/// LEDs that light up at random in a palette color and fade out again
+pub struct Twinkle<const N: usize> {
+    stars: [Star; N],
+    rng: Rng,
+    step: u8,
+    density: u8,
+}
+
+impl<const N: usize> Twinkle<N> {
+    pub fn new(speed: u8, density: u8, seed: u32) -> Self {
+        Self {
+            stars: [Star::default(); N],
+            rng: Rng::new(seed),
+            step: (speed / 16).max(1),
+            density,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame<N>, color_at: impl Fn(u8) -> [u8; 3]) {
+        for (star, led) in self.stars.iter_mut().zip(frame.iter_mut()) {
+            if star.phase == 0 {
+                // Each dark LED lights up with a chance of density / 2^14 per frame
+                if (self.rng.next_u32() >> 18) < self.density as u32 {
+                    star.phase = 1;
+                    star.color = color_at(self.rng.next_u8());
+                }
+            } else {
+                star.phase = star.phase.saturating_add(self.step);
+                if star.phase == u8::MAX {
+                    star.phase = 0;
+                }
+            }
+
+            let level = match star.phase {
+                0..128 => star.phase * 2,
+                128.. => (u8::MAX - star.phase) * 2,
+            };
+            *led = scale_color(&star.color, level);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(position: u8) -> [u8; 3] {
+        [position; 3]
+    }
+
+    fn render_frames<const N: usize>(twinkle: &mut Twinkle<N>, count: usize) -> Frame<N> {
+        let mut frame = [[0; 3]; N];
+        for _ in 0..count {
+            twinkle.render(&mut frame, gray);
+        }
+        frame
+    }
+
+    #[test]
+    fn same_seed_renders_same_frames() {
+        let mut a = Twinkle::<64>::new(128, 255, 42);
+        let mut b = Twinkle::<64>::new(128, 255, 42);
+        let mut frame_a = [[0; 3]; 64];
+        let mut frame_b = [[0; 3]; 64];
+        for _ in 0..200 {
+            a.render(&mut frame_a, gray);
+            b.render(&mut frame_b, gray);
+            assert_eq!(frame_a, frame_b);
+        }
+    }
+
+    #[test]
+    fn different_seeds_render_different_frames() {
+        let a = render_frames(&mut Twinkle::<64>::new(128, 255, 1), 100);
+        let b = render_frames(&mut Twinkle::<64>::new(128, 255, 2), 100);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn zero_density_stays_dark() {
+        let frame = render_frames(&mut Twinkle::<64>::new(128, 0, 42), 100);
+        assert_eq!(frame, [[0; 3]; 64]);
+    }
+
+    #[test]
+    fn stars_fade_in_and_out() {
+        // Full speed steps the phase by 15, so a star lasts 17 frames
+        let mut twinkle = Twinkle::<1>::new(255, 255, 42);
+        twinkle.stars[0] = Star {
+            phase: 1,
+            color: [255; 3],
+        };
+        twinkle.density = 0;
+        let mut frame = [[0; 3]];
+        let mut levels = [0; 17];
+        for level in levels.iter_mut() {
+            twinkle.render(&mut frame, gray);
+            *level = frame[0][0];
+        }
+        assert!(levels[..8].is_sorted());
+        assert!(levels[9..].iter().rev().is_sorted());
+        assert_eq!(levels[16], 0);
+    }
+
+    #[test]
+    fn snapshot() {
+        let frame = render_frames(&mut Twinkle::<8>::new(128, 255, 0x89ab_cdef), 300);
+        assert_eq!(frame, SNAPSHOT);
+    }
+
+    const SNAPSHOT: Frame<8> = [
+        [0, 0, 0],
+        [199, 199, 199],
+        [110, 110, 110],
+        [0, 0, 0],
+        [0, 0, 0],
+        [108, 108, 108],
+        [0, 0, 0],
+        [0, 0, 0],
+    ];
+}
@@ -1,8 +1,20 @@
 #![no_std]
 
+pub mod clip;
+pub mod dither;
+pub mod effects;
+pub mod log;
+pub mod ota;
+pub mod schedule;
+pub mod script;
+pub mod time;
+
 pub type PresetId = u8;
 
-pub const MESSAGE_BUFFER_LENGTH: usize = 1024;
+/// Largest UDP payload that fits into a single unfragmented packet on a 1500 byte MTU link, used
+/// by the device and the desktop app alike. Messages used to be limited to 1024 bytes, builds from
+/// before the change cannot receive the longer messages
+pub const MESSAGE_BUFFER_LENGTH: usize = 1472;
 
 #[derive(Debug)]
 pub enum VersionError {
@@ -67,6 +79,57 @@ pub enum Method {
     SetSpeed = 0x10,
     SetScale = 0x11,
     SaveSettings = 0x12,
+
+    GetStats = 0x13,
+    SetFrameRate = 0x14,
+    SetColor = 0x15,
+    GetPalettes = 0x16,
+    GetPalette = 0x17,
+    UploadPalette = 0x18,
+    DeletePalette = 0x19,
+    SetPalette = 0x1a,
+    GetPlaylists = 0x1b,
+    GetPlaylist = 0x1c,
+    UploadPlaylist = 0x1d,
+    DeletePlaylist = 0x1e,
+    SetPlaylist = 0x1f,
+    GetFavorites = 0x20,
+    SaveFavorite = 0x21,
+    RecallFavorite = 0x22,
+    DeleteFavorite = 0x23,
+    GetLayers = 0x24,
+    SetLayers = 0x25,
+    GetScript = 0x26,
+    UploadScript = 0x27,
+    GetClips = 0x28,
+    UploadClipChunk = 0x29,
+    FinishClip = 0x2a,
+    DeleteClip = 0x2b,
+    PlayClip = 0x2c,
+    SetLayout = 0x2d,
+    GetTime = 0x2e,
+    SetTime = 0x2f,
+    SetTimeSettings = 0x30,
+    GetSchedules = 0x31,
+    SetSchedules = 0x32,
+    GetTimers = 0x33,
+    ArmSleepTimer = 0x34,
+    CancelSleepTimer = 0x35,
+    ArmSunrise = 0x36,
+    CancelSunrise = 0x37,
+    GetBootSettings = 0x38,
+    SetBootSettings = 0x39,
+    GetResetToken = 0x3a,
+    Reboot = 0x3b,
+    FactoryReset = 0x3c,
+    GetFirmwareInfo = 0x3d,
+    BeginFirmwareUpdate = 0x3e,
+    UploadFirmwareChunk = 0x3f,
+    FinishFirmwareUpdate = 0x40,
+    GetLogSettings = 0x41,
+    SetLogSettings = 0x42,
+    SubscribeLogs = 0x43,
+    LogRecord = 0x44,
 }
 
 impl TryFrom<u8> for Method {
@@ -93,6 +156,56 @@ impl TryFrom<u8> for Method {
             0x10 => Ok(Self::SetSpeed),
             0x11 => Ok(Self::SetScale),
             0x12 => Ok(Self::SaveSettings),
+            0x13 => Ok(Self::GetStats),
+            0x14 => Ok(Self::SetFrameRate),
+            0x15 => Ok(Self::SetColor),
+            0x16 => Ok(Self::GetPalettes),
+            0x17 => Ok(Self::GetPalette),
+            0x18 => Ok(Self::UploadPalette),
+            0x19 => Ok(Self::DeletePalette),
+            0x1a => Ok(Self::SetPalette),
+            0x1b => Ok(Self::GetPlaylists),
+            0x1c => Ok(Self::GetPlaylist),
+            0x1d => Ok(Self::UploadPlaylist),
+            0x1e => Ok(Self::DeletePlaylist),
+            0x1f => Ok(Self::SetPlaylist),
+            0x20 => Ok(Self::GetFavorites),
+            0x21 => Ok(Self::SaveFavorite),
+            0x22 => Ok(Self::RecallFavorite),
+            0x23 => Ok(Self::DeleteFavorite),
+            0x24 => Ok(Self::GetLayers),
+            0x25 => Ok(Self::SetLayers),
+            0x26 => Ok(Self::GetScript),
+            0x27 => Ok(Self::UploadScript),
+            0x28 => Ok(Self::GetClips),
+            0x29 => Ok(Self::UploadClipChunk),
+            0x2a => Ok(Self::FinishClip),
+            0x2b => Ok(Self::DeleteClip),
+            0x2c => Ok(Self::PlayClip),
+            0x2d => Ok(Self::SetLayout),
+            0x2e => Ok(Self::GetTime),
+            0x2f => Ok(Self::SetTime),
+            0x30 => Ok(Self::SetTimeSettings),
+            0x31 => Ok(Self::GetSchedules),
+            0x32 => Ok(Self::SetSchedules),
+            0x33 => Ok(Self::GetTimers),
+            0x34 => Ok(Self::ArmSleepTimer),
+            0x35 => Ok(Self::CancelSleepTimer),
+            0x36 => Ok(Self::ArmSunrise),
+            0x37 => Ok(Self::CancelSunrise),
+            0x38 => Ok(Self::GetBootSettings),
+            0x39 => Ok(Self::SetBootSettings),
+            0x3a => Ok(Self::GetResetToken),
+            0x3b => Ok(Self::Reboot),
+            0x3c => Ok(Self::FactoryReset),
+            0x3d => Ok(Self::GetFirmwareInfo),
+            0x3e => Ok(Self::BeginFirmwareUpdate),
+            0x3f => Ok(Self::UploadFirmwareChunk),
+            0x40 => Ok(Self::FinishFirmwareUpdate),
+            0x41 => Ok(Self::GetLogSettings),
+            0x42 => Ok(Self::SetLogSettings),
+            0x43 => Ok(Self::SubscribeLogs),
+            0x44 => Ok(Self::LogRecord),
             _ => Err(MethodError::InvalidProtocolMethodCode),
         }
     }
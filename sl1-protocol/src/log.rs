@@ -0,0 +1,119 @@
+//! Log records streamed by the device.
+//!
+//! Records are sent as unsolicited `LogRecord` messages from the server port, to the configured
+//! collector and to every client that has subscribed in the last [`LOG_SUBSCRIPTION_LIFETIME_S`]
+//! seconds. The payload of a record is `[level, uptime_ms: u64 le, target_length, target,
+//! message]`, where the target and the message are UTF-8 and the message runs until the end of
+//! the packet.
+
+pub const LOG_RECORD_HEADER_LENGTH: usize = 10;
+/// Clients have to subscribe again before their subscription expires to keep receiving records
+pub const LOG_SUBSCRIPTION_LIFETIME_S: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogRecordError {
+    InvalidLevel,
+    InvalidUtf8,
+    Truncated,
+}
+
+impl core::fmt::Display for LogRecordError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Same values as the levels of the `log` crate
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[repr(u8)]
+pub enum LogLevel {
+    Error = 0x01,
+    Warn = 0x02,
+    Info = 0x03,
+    Debug = 0x04,
+    Trace = 0x05,
+}
+
+impl TryFrom<u8> for LogLevel {
+    type Error = LogRecordError;
+
+    fn try_from(value: u8) -> Result<Self, LogRecordError> {
+        match value {
+            0x01 => Ok(Self::Error),
+            0x02 => Ok(Self::Warn),
+            0x03 => Ok(Self::Info),
+            0x04 => Ok(Self::Debug),
+            0x05 => Ok(Self::Trace),
+            _ => Err(LogRecordError::InvalidLevel),
+        }
+    }
+}
+
+impl core::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        };
+        f.pad(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogRecord<'a> {
+    pub level: LogLevel,
+    /// Time since boot of the device
+    pub uptime_ms: u64,
+    pub target: &'a str,
+    pub message: &'a str,
+}
+
+impl<'a> LogRecord<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, LogRecordError> {
+        let header = bytes
+            .get(..LOG_RECORD_HEADER_LENGTH)
+            .ok_or(LogRecordError::Truncated)?;
+        let level = LogLevel::try_from(header[0])?;
+        let uptime_ms = u64::from_le_bytes(header[1..9].try_into().unwrap());
+        let target_end = LOG_RECORD_HEADER_LENGTH + header[9] as usize;
+        let target = bytes
+            .get(LOG_RECORD_HEADER_LENGTH..target_end)
+            .ok_or(LogRecordError::Truncated)?;
+        let as_str = |bytes| core::str::from_utf8(bytes).map_err(|_| LogRecordError::InvalidUtf8);
+        Ok(Self {
+            level,
+            uptime_ms,
+            target: as_str(target)?,
+            message: as_str(&bytes[target_end..])?,
+        })
+    }
+
+    /// Writes the record into `buf` and returns its length. The target and the message are cut
+    /// short on a character boundary if they do not fit
+    pub fn to_bytes(&self, buf: &mut [u8]) -> usize {
+        if buf.len() < LOG_RECORD_HEADER_LENGTH {
+            return 0;
+        }
+        let target = truncate(self.target, (buf.len() - LOG_RECORD_HEADER_LENGTH).min(255));
+        let target_end = LOG_RECORD_HEADER_LENGTH + target.len();
+        let message = truncate(self.message, buf.len() - target_end);
+
+        buf[0] = self.level as u8;
+        buf[1..9].copy_from_slice(&self.uptime_ms.to_le_bytes());
+        buf[9] = target.len() as u8;
+        buf[LOG_RECORD_HEADER_LENGTH..target_end].copy_from_slice(target.as_bytes());
+        buf[target_end..target_end + message.len()].copy_from_slice(message.as_bytes());
+        target_end + message.len()
+    }
+}
+
+fn truncate(text: &str, max_length: usize) -> &str {
+    let mut end = text.len().min(max_length);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
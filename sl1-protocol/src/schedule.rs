@@ -0,0 +1,203 @@
+//! Times of the day at which the schedules stored on the device are triggered. Evaluation only
+//! depends on the unix time passed in, so that it does not need the clock of the device.
+
+use crate::time::weekday_of_day;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Day mask with every day of the week set, bit 0 is Monday
+pub const EVERY_DAY: u8 = 0x7f;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trigger {
+    /// Days of the week the trigger fires on, bit 0 is Monday
+    pub days: u8,
+    /// Local time of the day in minutes since midnight
+    pub minute: u16,
+}
+
+impl Trigger {
+    pub fn is_valid(&self) -> bool {
+        self.days & !EVERY_DAY == 0 && self.minute < MINUTES_PER_DAY
+    }
+
+    /// Unix time in seconds of the first time the trigger fires strictly after `unix_seconds`,
+    /// `None` if no days are selected
+    pub fn next_after(&self, unix_seconds: i64, utc_offset_minutes: i16) -> Option<i64> {
+        let offset = utc_offset_minutes as i64 * 60;
+        let local = unix_seconds + offset;
+        let today = local.div_euclid(SECONDS_PER_DAY);
+
+        // A week and a day, as the trigger may have already fired today
+        (today..=today + 7)
+            .filter(|day| self.days & (1 << weekday_of_day(*day)) != 0)
+            .map(|day| day * SECONDS_PER_DAY + self.minute as i64 * 60)
+            .find(|trigger| *trigger > local)
+            .map(|trigger| trigger - offset)
+    }
+
+    /// Whether the trigger fires after `from` and no later than `to`, both in unix seconds
+    pub fn is_due(&self, from: i64, to: i64, utc_offset_minutes: i16) -> bool {
+        self.next_after(from, utc_offset_minutes)
+            .is_some_and(|trigger| trigger <= to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::DateTime;
+
+    /// 2024-01-01 00:00 UTC, a Monday
+    const MONDAY: i64 = 1_704_067_200;
+    const HOUR: i64 = 60 * 60;
+    const DAY: i64 = SECONDS_PER_DAY;
+    const WEEK: i64 = 7 * DAY;
+
+    const MONDAYS: u8 = 1 << 0;
+    const TUESDAYS: u8 = 1 << 1;
+    const SUNDAYS: u8 = 1 << 6;
+
+    fn at(days: u8, hour: u16, minute: u16) -> Trigger {
+        Trigger {
+            days,
+            minute: hour * 60 + minute,
+        }
+    }
+
+    #[test]
+    fn fires_later_today() {
+        let trigger = at(EVERY_DAY, 7, 0);
+        assert_eq!(trigger.next_after(MONDAY, 0), Some(MONDAY + 7 * HOUR));
+    }
+
+    #[test]
+    fn fires_tomorrow_once_fired_today() {
+        let trigger = at(EVERY_DAY, 7, 0);
+        assert_eq!(
+            trigger.next_after(MONDAY + 8 * HOUR, 0),
+            Some(MONDAY + DAY + 7 * HOUR)
+        );
+    }
+
+    #[test]
+    fn day_mask_wraps_across_weeks() {
+        // Only on Mondays, which has already passed this week
+        let trigger = at(MONDAYS, 7, 0);
+        assert_eq!(
+            trigger.next_after(MONDAY + 8 * HOUR, 0),
+            Some(MONDAY + WEEK + 7 * HOUR)
+        );
+
+        // From late on Sunday to early on the following Monday
+        let trigger = at(MONDAYS, 0, 30);
+        assert_eq!(
+            trigger.next_after(MONDAY + 6 * DAY + 23 * HOUR, 0),
+            Some(MONDAY + WEEK + 30 * 60)
+        );
+
+        // The last day of the week from its first day
+        let trigger = at(SUNDAYS, 12, 0);
+        assert_eq!(
+            trigger.next_after(MONDAY, 0),
+            Some(MONDAY + 6 * DAY + 12 * HOUR)
+        );
+    }
+
+    #[test]
+    fn negative_utc_offset_around_midnight() {
+        // 23:30 on Monday in UTC-5 is 04:30 on Tuesday in UTC
+        let trigger = at(MONDAYS, 23, 30);
+        let monday_late_local = MONDAY + DAY + 4 * HOUR;
+        assert_eq!(
+            trigger.next_after(monday_late_local, -5 * 60),
+            Some(MONDAY + DAY + 4 * HOUR + 30 * 60)
+        );
+
+        // 23:00 on Monday in UTC is 18:00 on Monday in UTC-5, so the trigger fires at 00:30 on
+        // Tuesday local time, which is 05:30 in UTC
+        let trigger = at(TUESDAYS, 0, 30);
+        assert_eq!(
+            trigger.next_after(MONDAY + 23 * HOUR, -5 * 60),
+            Some(MONDAY + DAY + 5 * HOUR + 30 * 60)
+        );
+    }
+
+    #[test]
+    fn positive_utc_offset_around_midnight() {
+        // 00:30 on Monday in UTC+2 is 22:30 on Sunday in UTC
+        let trigger = at(MONDAYS, 0, 30);
+        assert_eq!(
+            trigger.next_after(MONDAY - 2 * HOUR, 2 * 60),
+            Some(MONDAY - 2 * HOUR + 30 * 60)
+        );
+
+        // It is already Monday in UTC+2 at 23:00 on Sunday in UTC, so the next Monday is a week
+        // away
+        assert_eq!(
+            trigger.next_after(MONDAY - HOUR, 2 * 60),
+            Some(MONDAY + WEEK - 2 * HOUR + 30 * 60)
+        );
+    }
+
+    #[test]
+    fn trigger_time_is_exclusive_for_from_and_inclusive_for_to() {
+        let trigger = at(EVERY_DAY, 7, 0);
+        let fires_at = MONDAY + 7 * HOUR;
+
+        assert_eq!(trigger.next_after(fires_at - 1, 0), Some(fires_at));
+        assert_eq!(trigger.next_after(fires_at, 0), Some(fires_at + DAY));
+
+        assert!(trigger.is_due(fires_at - 1, fires_at, 0));
+        assert!(!trigger.is_due(fires_at - 2, fires_at - 1, 0));
+        // Already fired when `from` was checked, it is not fired twice
+        assert!(!trigger.is_due(fires_at, fires_at + 60, 0));
+    }
+
+    #[test]
+    fn single_day_fires_again_a_week_later() {
+        let trigger = at(MONDAYS, 7, 0);
+        let fires_at = MONDAY + 7 * HOUR;
+        assert_eq!(trigger.next_after(fires_at, 0), Some(fires_at + WEEK));
+    }
+
+    #[test]
+    fn empty_day_mask_never_fires() {
+        let trigger = at(0, 7, 0);
+        assert!(trigger.is_valid());
+        assert_eq!(trigger.next_after(MONDAY, 0), None);
+        assert!(!trigger.is_due(MONDAY, MONDAY + 2 * WEEK, 0));
+    }
+
+    #[test]
+    fn fires_on_selected_local_day_and_time() {
+        let triggers = [
+            at(MONDAYS, 0, 0),
+            at(SUNDAYS, 23, 59),
+            at(MONDAYS | SUNDAYS, 12, 0),
+            at(0b0010101, 6, 15),
+        ];
+        for trigger in triggers {
+            for offset in [-12 * 60, -5 * 60 - 30, 0, 2 * 60, 14 * 60] {
+                for from in (MONDAY - WEEK..MONDAY + 2 * WEEK).step_by(7 * HOUR as usize + 13) {
+                    let fires_at = trigger.next_after(from, offset).unwrap();
+                    assert!(fires_at > from);
+                    assert!(fires_at - from <= WEEK);
+
+                    let local = DateTime::from_unix(fires_at, offset);
+                    assert_ne!(trigger.days & (1 << local.weekday), 0);
+                    assert_eq!(local.hour as u16 * 60 + local.minute as u16, trigger.minute);
+                    assert_eq!(local.second, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn validity() {
+        assert!(at(EVERY_DAY, 23, 59).is_valid());
+        assert!(!at(EVERY_DAY, 24, 0).is_valid());
+        assert!(!at(0x80, 7, 0).is_valid());
+    }
+}
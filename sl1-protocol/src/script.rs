@@ -0,0 +1,546 @@
+//! Bytecode of the effect scripts that are compiled by the desktop app and interpreted by the
+//! device. A script is a stack program run once per pixel, that ends with an instruction which
+//! turns the values left on the stack into the color of the pixel.
+
+/// Largest script that the device stores
+pub const MAX_SCRIPT_LENGTH: usize = 256;
+/// Largest number of values a script may keep on the stack at once
+pub const MAX_STACK_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptError {
+    InvalidOpcode,
+    MissingImmediate,
+    StackOverflow,
+    StackUnderflow,
+    MissingOutput,
+    TrailingCode,
+    TooLong,
+    BudgetExhausted,
+}
+
+impl core::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum Op {
+    /// Pushes the following 16-bit little-endian signed immediate
+    Push = 0x00,
+
+    Index = 0x01,
+    Count = 0x02,
+    Time = 0x03,
+    Speed = 0x04,
+    Scale = 0x05,
+
+    Add = 0x10,
+    Sub = 0x11,
+    Mul = 0x12,
+    Div = 0x13,
+    Mod = 0x14,
+    Neg = 0x15,
+    Abs = 0x16,
+    Min = 0x17,
+    Max = 0x18,
+    Lt = 0x19,
+    Gt = 0x1a,
+    Eq = 0x1b,
+    And = 0x1c,
+    Or = 0x1d,
+    Xor = 0x1e,
+    Shl = 0x1f,
+    Shr = 0x20,
+    /// Pops `condition, a, b` and pushes `a` if the condition is not zero, `b` otherwise
+    Select = 0x21,
+
+    Sin8 = 0x30,
+    Noise = 0x31,
+
+    /// Pops a position and looks the color up in the palette of the preset
+    Palette = 0x40,
+    /// Pops the blue, green and red components of the color
+    Rgb = 0x41,
+}
+
+impl TryFrom<u8> for Op {
+    type Error = ScriptError;
+
+    fn try_from(value: u8) -> Result<Self, ScriptError> {
+        match value {
+            0x00 => Ok(Self::Push),
+            0x01 => Ok(Self::Index),
+            0x02 => Ok(Self::Count),
+            0x03 => Ok(Self::Time),
+            0x04 => Ok(Self::Speed),
+            0x05 => Ok(Self::Scale),
+            0x10 => Ok(Self::Add),
+            0x11 => Ok(Self::Sub),
+            0x12 => Ok(Self::Mul),
+            0x13 => Ok(Self::Div),
+            0x14 => Ok(Self::Mod),
+            0x15 => Ok(Self::Neg),
+            0x16 => Ok(Self::Abs),
+            0x17 => Ok(Self::Min),
+            0x18 => Ok(Self::Max),
+            0x19 => Ok(Self::Lt),
+            0x1a => Ok(Self::Gt),
+            0x1b => Ok(Self::Eq),
+            0x1c => Ok(Self::And),
+            0x1d => Ok(Self::Or),
+            0x1e => Ok(Self::Xor),
+            0x1f => Ok(Self::Shl),
+            0x20 => Ok(Self::Shr),
+            0x21 => Ok(Self::Select),
+            0x30 => Ok(Self::Sin8),
+            0x31 => Ok(Self::Noise),
+            0x40 => Ok(Self::Palette),
+            0x41 => Ok(Self::Rgb),
+            _ => Err(ScriptError::InvalidOpcode),
+        }
+    }
+}
+
+impl Op {
+    /// Number of values the instruction pops and pushes
+    pub fn stack_effect(self) -> (usize, usize) {
+        match self {
+            Op::Push | Op::Index | Op::Count | Op::Time | Op::Speed | Op::Scale => (0, 1),
+            Op::Neg | Op::Abs | Op::Sin8 => (1, 1),
+            Op::Add
+            | Op::Sub
+            | Op::Mul
+            | Op::Div
+            | Op::Mod
+            | Op::Min
+            | Op::Max
+            | Op::Lt
+            | Op::Gt
+            | Op::Eq
+            | Op::And
+            | Op::Or
+            | Op::Xor
+            | Op::Shl
+            | Op::Shr
+            | Op::Noise => (2, 1),
+            Op::Select => (3, 1),
+            Op::Palette => (1, 0),
+            Op::Rgb => (3, 0),
+        }
+    }
+
+    fn is_output(self) -> bool {
+        matches!(self, Op::Palette | Op::Rgb)
+    }
+}
+
+/// Values a script can read, all of them are the same for every pixel except for the index
+#[derive(Debug, Clone, Copy)]
+pub struct Inputs {
+    pub index: i32,
+    pub count: i32,
+    /// Milliseconds since the script was started
+    pub time: i32,
+    pub speed: i32,
+    pub scale: i32,
+}
+
+/// Functions that the interpreter leaves to the host
+pub trait Environment {
+    /// 2-D Perlin noise, coordinates are in 1/256 of the noise lattice
+    fn noise(&self, x: u16, y: u16) -> u8;
+    fn palette(&self, position: u8) -> [u8; 3];
+}
+
+/// Checks that the script only contains known instructions, never overflows or underflows the
+/// stack and ends with exactly one output instruction. Scripts that pass the check can only fail
+/// at runtime by running out of their instruction budget.
+pub fn validate(code: &[u8]) -> Result<(), ScriptError> {
+    if code.len() > MAX_SCRIPT_LENGTH {
+        return Err(ScriptError::TooLong);
+    }
+
+    let mut depth: usize = 0;
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = Op::try_from(code[pc])?;
+        pc += 1;
+        if op == Op::Push {
+            if pc + 2 > code.len() {
+                return Err(ScriptError::MissingImmediate);
+            }
+            pc += 2;
+        }
+
+        let (pops, pushes) = op.stack_effect();
+        depth = depth.checked_sub(pops).ok_or(ScriptError::StackUnderflow)? + pushes;
+        if depth > MAX_STACK_DEPTH {
+            return Err(ScriptError::StackOverflow);
+        }
+
+        if op.is_output() {
+            return match pc == code.len() {
+                true => Ok(()),
+                false => Err(ScriptError::TrailingCode),
+            };
+        }
+    }
+
+    Err(ScriptError::MissingOutput)
+}
+
+/// Runs the script for a single pixel, every executed instruction is taken from the budget
+pub fn run(
+    code: &[u8],
+    inputs: &Inputs,
+    env: &impl Environment,
+    budget: &mut u32,
+) -> Result<[u8; 3], ScriptError> {
+    let mut stack = Stack::default();
+    let mut pc = 0;
+
+    loop {
+        *budget = budget.checked_sub(1).ok_or(ScriptError::BudgetExhausted)?;
+
+        let op = Op::try_from(*code.get(pc).ok_or(ScriptError::MissingOutput)?)?;
+        pc += 1;
+
+        match op {
+            Op::Push => {
+                let immediate = code.get(pc..pc + 2).ok_or(ScriptError::MissingImmediate)?;
+                stack.push(i16::from_le_bytes([immediate[0], immediate[1]]) as i32)?;
+                pc += 2;
+            }
+            Op::Index => stack.push(inputs.index)?,
+            Op::Count => stack.push(inputs.count)?,
+            Op::Time => stack.push(inputs.time)?,
+            Op::Speed => stack.push(inputs.speed)?,
+            Op::Scale => stack.push(inputs.scale)?,
+            Op::Neg => stack.unary(i32::wrapping_neg)?,
+            Op::Abs => stack.unary(i32::wrapping_abs)?,
+            Op::Sin8 => stack.unary(|x| sin8(x as u8) as i32)?,
+            Op::Add => stack.binary(i32::wrapping_add)?,
+            Op::Sub => stack.binary(i32::wrapping_sub)?,
+            Op::Mul => stack.binary(i32::wrapping_mul)?,
+            Op::Div => stack.binary(|a, b| a.checked_div(b).unwrap_or(0))?,
+            Op::Mod => stack.binary(|a, b| a.checked_rem(b).unwrap_or(0))?,
+            Op::Min => stack.binary(i32::min)?,
+            Op::Max => stack.binary(i32::max)?,
+            Op::Lt => stack.binary(|a, b| (a < b) as i32)?,
+            Op::Gt => stack.binary(|a, b| (a > b) as i32)?,
+            Op::Eq => stack.binary(|a, b| (a == b) as i32)?,
+            Op::And => stack.binary(|a, b| a & b)?,
+            Op::Or => stack.binary(|a, b| a | b)?,
+            Op::Xor => stack.binary(|a, b| a ^ b)?,
+            Op::Shl => stack.binary(|a, b| a.wrapping_shl(b as u32))?,
+            Op::Shr => stack.binary(|a, b| a.wrapping_shr(b as u32))?,
+            Op::Noise => stack.binary(|x, y| env.noise(x as u16, y as u16) as i32)?,
+            Op::Select => {
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                let condition = stack.pop()?;
+                stack.push(if condition != 0 { a } else { b })?;
+            }
+            Op::Palette => return Ok(env.palette(stack.pop()? as u8)),
+            Op::Rgb => {
+                let b = stack.pop()?;
+                let g = stack.pop()?;
+                let r = stack.pop()?;
+                let channel = |value: i32| value.clamp(0, 255) as u8;
+                return Ok([channel(r), channel(g), channel(b)]);
+            }
+        }
+    }
+}
+
+/// Sine wave over a full period of 256 steps with values in `1..=255`, approximated with two
+/// parabolas
+pub fn sin8(x: u8) -> u8 {
+    let half = (x & 127) as i32;
+    let y = half * (128 - half) * 127 / (64 * 64);
+    match x < 128 {
+        true => (128 + y) as u8,
+        false => (128 - y) as u8,
+    }
+}
+
+#[derive(Default)]
+struct Stack {
+    values: [i32; MAX_STACK_DEPTH],
+    len: usize,
+}
+
+impl Stack {
+    fn push(&mut self, value: i32) -> Result<(), ScriptError> {
+        let slot = self
+            .values
+            .get_mut(self.len)
+            .ok_or(ScriptError::StackOverflow)?;
+        *slot = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<i32, ScriptError> {
+        self.len = self.len.checked_sub(1).ok_or(ScriptError::StackUnderflow)?;
+        Ok(self.values[self.len])
+    }
+
+    fn unary(&mut self, f: impl FnOnce(i32) -> i32) -> Result<(), ScriptError> {
+        let a = self.pop()?;
+        self.push(f(a))
+    }
+
+    fn binary(&mut self, f: impl FnOnce(i32, i32) -> i32) -> Result<(), ScriptError> {
+        let b = self.pop()?;
+        let a = self.pop()?;
+        self.push(f(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    const INPUTS: Inputs = Inputs {
+        index: 3,
+        count: 10,
+        time: 1000,
+        speed: 128,
+        scale: 64,
+    };
+
+    struct TestEnvironment;
+
+    impl Environment for TestEnvironment {
+        fn noise(&self, x: u16, y: u16) -> u8 {
+            (x ^ y) as u8
+        }
+
+        fn palette(&self, position: u8) -> [u8; 3] {
+            [position, 0, 255 - position]
+        }
+    }
+
+    /// Builds scripts from instructions and pushed immediates
+    #[derive(Default)]
+    struct Code(Vec<u8>);
+
+    impl Code {
+        fn op(mut self, op: Op) -> Self {
+            self.0.push(op as u8);
+            self
+        }
+
+        fn push(mut self, value: i16) -> Self {
+            self.0.push(Op::Push as u8);
+            self.0.extend(value.to_le_bytes());
+            self
+        }
+
+        fn rgb(self) -> Vec<u8> {
+            self.op(Op::Rgb).0
+        }
+    }
+
+    fn run_code(code: &[u8]) -> Result<[u8; 3], ScriptError> {
+        run(code, &INPUTS, &TestEnvironment, &mut 1000)
+    }
+
+    /// Checks the value the expression leaves on the stack by comparing it with `expected`
+    fn assert_evaluates(expression: Code, expected: i16) {
+        let code = expression.push(expected).op(Op::Eq).push(0).push(0).rgb();
+        assert_eq!(validate(&code), Ok(()));
+        assert_eq!(run_code(&code), Ok([1, 0, 0]), "expected {expected}");
+    }
+
+    fn binary(a: i16, b: i16, op: Op) -> Code {
+        Code::default().push(a).push(b).op(op)
+    }
+
+    #[test]
+    fn inputs() {
+        assert_evaluates(Code::default().op(Op::Index), 3);
+        assert_evaluates(Code::default().op(Op::Count), 10);
+        assert_evaluates(Code::default().op(Op::Time), 1000);
+        assert_evaluates(Code::default().op(Op::Speed), 128);
+        assert_evaluates(Code::default().op(Op::Scale), 64);
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_evaluates(binary(7, 3, Op::Add), 10);
+        assert_evaluates(binary(7, 3, Op::Sub), 4);
+        assert_evaluates(binary(7, -3, Op::Mul), -21);
+        assert_evaluates(binary(-7, 2, Op::Div), -3);
+        assert_evaluates(binary(-7, 2, Op::Mod), -1);
+        assert_evaluates(binary(7, 3, Op::Min), 3);
+        assert_evaluates(binary(7, 3, Op::Max), 7);
+        assert_evaluates(Code::default().push(7).op(Op::Neg), -7);
+        assert_evaluates(Code::default().push(-7).op(Op::Abs), 7);
+    }
+
+    #[test]
+    fn division_by_zero_gives_zero() {
+        assert_evaluates(binary(7, 0, Op::Div), 0);
+        assert_evaluates(binary(7, 0, Op::Mod), 0);
+    }
+
+    #[test]
+    fn arithmetic_wraps() {
+        // 2^30 * 4 wraps to 0
+        let code = binary(1, 30, Op::Shl).push(4).op(Op::Mul);
+        assert_evaluates(code, 0);
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_evaluates(binary(2, 3, Op::Lt), 1);
+        assert_evaluates(binary(3, 3, Op::Lt), 0);
+        assert_evaluates(binary(4, 3, Op::Gt), 1);
+        assert_evaluates(binary(3, 3, Op::Gt), 0);
+        assert_evaluates(binary(3, 3, Op::Eq), 1);
+        assert_evaluates(binary(2, 3, Op::Eq), 0);
+    }
+
+    #[test]
+    fn bitwise() {
+        assert_evaluates(binary(0b1100, 0b1010, Op::And), 0b1000);
+        assert_evaluates(binary(0b1100, 0b1010, Op::Or), 0b1110);
+        assert_evaluates(binary(0b1100, 0b1010, Op::Xor), 0b0110);
+        assert_evaluates(binary(3, 4, Op::Shl), 48);
+        assert_evaluates(binary(-48, 4, Op::Shr), -3);
+    }
+
+    #[test]
+    fn select() {
+        let select = |condition| {
+            Code::default()
+                .push(condition)
+                .push(5)
+                .push(9)
+                .op(Op::Select)
+        };
+        assert_evaluates(select(1), 5);
+        assert_evaluates(select(-1), 5);
+        assert_evaluates(select(0), 9);
+    }
+
+    #[test]
+    fn sine() {
+        let sin8 = |x| Code::default().push(x).op(Op::Sin8);
+        assert_evaluates(sin8(0), 128);
+        assert_evaluates(sin8(64), 255);
+        assert_evaluates(sin8(128), 128);
+        assert_evaluates(sin8(192), 1);
+        // Only the low byte is used
+        assert_evaluates(sin8(256 + 64), 255);
+    }
+
+    #[test]
+    fn noise_is_taken_from_environment() {
+        assert_evaluates(binary(0x0f0f, 0x00ff, Op::Noise), 0xf0);
+    }
+
+    #[test]
+    fn outputs() {
+        let code = Code::default().push(100).op(Op::Palette).0;
+        assert_eq!(run_code(&code), Ok([100, 0, 155]));
+
+        // Channels are clamped to the 8-bit range
+        let code = Code::default().push(-5).push(128).push(300).rgb();
+        assert_eq!(run_code(&code), Ok([0, 128, 255]));
+    }
+
+    #[test]
+    fn budget_is_taken_per_instruction() {
+        let code = Code::default().push(1).push(2).push(3).rgb();
+        let mut budget = 10;
+        assert!(run(&code, &INPUTS, &TestEnvironment, &mut budget).is_ok());
+        assert_eq!(budget, 6);
+
+        let mut budget = 3;
+        assert_eq!(
+            run(&code, &INPUTS, &TestEnvironment, &mut budget),
+            Err(ScriptError::BudgetExhausted)
+        );
+        assert_eq!(budget, 0);
+    }
+
+    #[test]
+    fn stack_underflow() {
+        let code = Code::default()
+            .push(1)
+            .push(2)
+            .op(Op::Select)
+            .push(0)
+            .push(0)
+            .rgb();
+        assert_eq!(validate(&code), Err(ScriptError::StackUnderflow));
+        assert_eq!(run_code(&code), Err(ScriptError::StackUnderflow));
+
+        let code = Code::default().push(1).push(2).rgb();
+        assert_eq!(validate(&code), Err(ScriptError::StackUnderflow));
+        assert_eq!(run_code(&code), Err(ScriptError::StackUnderflow));
+    }
+
+    #[test]
+    fn stack_overflow() {
+        let full = (0..MAX_STACK_DEPTH).fold(Code::default(), |code, _| code.op(Op::Index));
+        let code = full.op(Op::Index).rgb();
+        assert_eq!(validate(&code), Err(ScriptError::StackOverflow));
+        assert_eq!(run_code(&code), Err(ScriptError::StackOverflow));
+
+        // Filling the stack exactly is fine
+        let full = (0..MAX_STACK_DEPTH).fold(Code::default(), |code, _| code.op(Op::Index));
+        assert_eq!(validate(&full.rgb()), Ok(()));
+    }
+
+    #[test]
+    fn code_bounds() {
+        // Running off the end of the code
+        let code = Code::default().push(1).0;
+        assert_eq!(validate(&code), Err(ScriptError::MissingOutput));
+        assert_eq!(run_code(&code), Err(ScriptError::MissingOutput));
+        assert_eq!(validate(&[]), Err(ScriptError::MissingOutput));
+        assert_eq!(run_code(&[]), Err(ScriptError::MissingOutput));
+
+        // Immediate cut short by the end of the code
+        let code = [Op::Push as u8, 0x01];
+        assert_eq!(validate(&code), Err(ScriptError::MissingImmediate));
+        assert_eq!(run_code(&code), Err(ScriptError::MissingImmediate));
+    }
+
+    #[test]
+    fn rejects_bad_bytecode() {
+        let code = [0xff, Op::Rgb as u8];
+        assert_eq!(validate(&code), Err(ScriptError::InvalidOpcode));
+        assert_eq!(run_code(&code), Err(ScriptError::InvalidOpcode));
+
+        let code = Code::default().push(0).push(0).push(0).rgb();
+        let mut trailing = code.clone();
+        trailing.push(Op::Index as u8);
+        assert_eq!(validate(&trailing), Err(ScriptError::TrailingCode));
+
+        let mut too_long = Code::default().0;
+        too_long.resize(MAX_SCRIPT_LENGTH - code.len() + 1, Op::Index as u8);
+        too_long.extend(&code);
+        assert_eq!(validate(&too_long), Err(ScriptError::TooLong));
+    }
+
+    #[test]
+    fn every_opcode_round_trips() {
+        for byte in 0..=u8::MAX {
+            if let Ok(op) = Op::try_from(byte) {
+                assert_eq!(op as u8, byte);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Calendar dates of the wall-clock time kept by the device, which is exchanged as unix time
+//! along with the offset of the local time zone from UTC in minutes.
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: i32,
+    /// Month of the year, starting at 1
+    pub month: u8,
+    /// Day of the month, starting at 1
+    pub day: u8,
+    /// Day of the week, 0 is Monday
+    pub weekday: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Local date and time of the unix time in seconds in the given time zone
+    pub fn from_unix(unix_seconds: i64, utc_offset_minutes: i16) -> Self {
+        let local = unix_seconds + utc_offset_minutes as i64 * 60;
+        let days = local.div_euclid(SECONDS_PER_DAY);
+        let seconds = local.rem_euclid(SECONDS_PER_DAY);
+
+        // Converts the days since the epoch to a date in the proleptic Gregorian calendar, with
+        // years starting in March, so that the leap day is the last day of the year
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = match shifted_month < 10 {
+            true => shifted_month + 3,
+            false => shifted_month - 9,
+        };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+            weekday: weekday_of_day(days),
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+        }
+    }
+}
+
+/// Day of the week of the given number of days since the unix epoch, 0 is Monday
+pub fn weekday_of_day(days: i64) -> u8 {
+    // The epoch was a Thursday
+    (days + 3).rem_euclid(7) as u8
+}
+
+impl core::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}